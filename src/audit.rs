@@ -0,0 +1,326 @@
+//! Analysis of how consistently components are invoked across a codebase: the order of their props
+//! and the style in which equivalent prop values are written
+
+use crate::formatter::HtmlMacroFinder;
+use crate::html::*;
+use crate::utils::{parse2_with_ctx, Result};
+use proc_macro2::LineColumn;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+use syn::{spanned::Spanned, visit::Visit, Expr, Stmt};
+
+/// The way a prop's value is written; only the styles from the same group are interchangeable
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PropStyle {
+    /// `prop="value"`, or any other literal
+    Literal,
+    /// `prop={"value"}`, or any other literal
+    BracedLiteral,
+    /// `{prop}`
+    Shorthand,
+    /// `prop={prop}`
+    Named,
+    /// `prop={expr}`, with no equivalent alternative
+    Expr,
+}
+
+impl PropStyle {
+    /// Returns the style the prop can be rewritten in without changing the meaning
+    const fn alternative(self) -> Option<Self> {
+        match self {
+            Self::Literal => Some(Self::BracedLiteral),
+            Self::BracedLiteral => Some(Self::Literal),
+            Self::Shorthand => Some(Self::Named),
+            Self::Named => Some(Self::Shorthand),
+            Self::Expr => None,
+        }
+    }
+}
+
+impl Display for PropStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Literal => "a literal",
+            Self::BracedLiteral => "a literal in braces",
+            Self::Shorthand => "the shorthand syntax",
+            Self::Named => "an explicitly named variable",
+            Self::Expr => "an expression",
+        })
+    }
+}
+
+/// A place in the audited code
+#[derive(Clone, Debug)]
+pub struct Location {
+    pub file: Rc<str>,
+    /// 1-based
+    pub line: usize,
+    /// 0-based
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column + 1)
+    }
+}
+
+struct Invocation {
+    location: Location,
+    props: Vec<(String, PropStyle)>,
+}
+
+/// An inconsistency found by [`Audit::findings`]
+#[derive(Clone, Debug)]
+pub enum Finding {
+    /// `first` is put before `second` here, while most invocations do the opposite
+    PropOrder {
+        component: String,
+        location: Location,
+        first: String,
+        second: String,
+        /// number of invocations that put `second` first
+        usual_count: usize,
+    },
+    /// the prop is written in `style` here, while most invocations use `usual`
+    PropStyle {
+        component: String,
+        location: Location,
+        prop: String,
+        style: PropStyle,
+        usual: PropStyle,
+        usual_count: usize,
+    },
+}
+
+impl Finding {
+    pub const fn location(&self) -> &Location {
+        match self {
+            Self::PropOrder { location, .. } | Self::PropStyle { location, .. } => location,
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PropOrder { component, first, second, usual_count, .. } => write!(
+                f,
+                "`{component}` gets `{first}` before `{second}`, while {usual_count} other \
+                 invocation(s) put `{second}` first"
+            ),
+            Self::PropStyle { component, prop, style, usual, usual_count, .. } => write!(
+                f,
+                "`{prop}` of `{component}` is written as {style}, while {usual_count} other \
+                 invocation(s) use {usual}"
+            ),
+        }
+    }
+}
+
+/// Aggregates the component invocations found in the added files
+#[derive(Default)]
+pub struct Audit {
+    /// the invocations of each component by its name
+    invocations: BTreeMap<String, Vec<Invocation>>,
+}
+
+impl Audit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the component invocations in the HTML macros of `src`; the macros that fail to be
+    /// parsed are skipped
+    pub fn add_file(&mut self, filename: &str, src: &str, ext: bool) -> Result {
+        let file = syn::parse_file(src)?;
+        let mut finder = HtmlMacroFinder::default();
+        finder.visit_file(&file);
+        let mut collector = Collector { file: filename.into(), audit: self, ext };
+        for mac in finder.0 {
+            if let Ok(html) = parse2_with_ctx::<Html>(mac.tokens.clone(), ext) {
+                collector.html(&html)
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the inconsistencies in the order of the components' names
+    pub fn findings(&self) -> Vec<Finding> {
+        let mut res = vec![];
+        for (component, invocations) in &self.invocations {
+            // how many times the prop at the key's 1st position is put before the one at the 2nd
+            let mut orders = BTreeMap::<(&str, &str), usize>::new();
+            let mut styles = BTreeMap::<(&str, PropStyle), usize>::new();
+            for inv in invocations {
+                for (i, (first, style)) in inv.props.iter().enumerate() {
+                    *styles.entry((first, *style)).or_default() += 1;
+                    for (second, _) in &inv.props[i + 1..] {
+                        *orders.entry((first, second)).or_default() += 1;
+                    }
+                }
+            }
+
+            for inv in invocations {
+                let found_order = inv.props.iter().enumerate().find_map(|(i, (first, _))| {
+                    inv.props[i + 1..].iter().find_map(|(second, _)| {
+                        let here = orders.get(&(&**first, &**second)).copied().unwrap_or(0);
+                        let usual_count = orders.get(&(&**second, &**first)).copied().unwrap_or(0);
+                        (usual_count > here).then(|| Finding::PropOrder {
+                            component: component.clone(),
+                            location: inv.location.clone(),
+                            first: first.clone(),
+                            second: second.clone(),
+                            usual_count,
+                        })
+                    })
+                });
+                res.extend(found_order);
+
+                for (prop, style) in &inv.props {
+                    let Some(usual) = style.alternative() else { continue };
+                    let here = styles.get(&(&**prop, *style)).copied().unwrap_or(0);
+                    let usual_count = styles.get(&(&**prop, usual)).copied().unwrap_or(0);
+                    if usual_count > here {
+                        res.push(Finding::PropStyle {
+                            component: component.clone(),
+                            location: inv.location.clone(),
+                            prop: prop.clone(),
+                            style: *style,
+                            usual,
+                            usual_count,
+                        });
+                    }
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Walks the HTML trees, recording the component invocations
+struct Collector<'audit> {
+    file: Rc<str>,
+    audit: &'audit mut Audit,
+    ext: bool,
+}
+
+impl Collector<'_> {
+    fn location(&self, at: LineColumn) -> Location {
+        Location { file: self.file.clone(), line: at.line, column: at.column }
+    }
+
+    /// Descends into the HTML macros nested in a Rust expression
+    fn nested(&mut self, expr: &Expr) {
+        let mut finder = HtmlMacroFinder::default();
+        finder.visit_expr(expr);
+        for mac in finder.0 {
+            if let Ok(html) = parse2_with_ctx::<Html>(mac.tokens.clone(), self.ext) {
+                self.html(&html)
+            }
+        }
+    }
+
+    fn html(&mut self, html: &Html) {
+        match html {
+            Html::Tree(tree) => self.tree(tree),
+            Html::Value(value) => self.block_content(value),
+        }
+    }
+
+    fn block_content(&mut self, content: &HtmlBlockContent) {
+        match content {
+            HtmlBlockContent::Expr(expr) | HtmlBlockContent::Iterable(_, expr) => self.nested(expr),
+        }
+    }
+
+    fn trees(&mut self, trees: &[HtmlTree]) {
+        trees.iter().for_each(|tree| self.tree(tree))
+    }
+
+    fn tree(&mut self, tree: &HtmlTree) {
+        match tree {
+            HtmlTree::Element(element) => self.element(element),
+            HtmlTree::Block(block) => self.block_content(&block.content),
+            HtmlTree::If(r#if) => self.r#if(r#if),
+            HtmlTree::For(r#for) => self.trees(&r#for.body),
+            HtmlTree::Match(r#match) => {
+                r#match.arms.iter().for_each(|arm| self.html(&arm.body));
+            }
+            HtmlTree::Let(_) => (),
+        }
+    }
+
+    fn r#if(&mut self, r#if: &HtmlIf) {
+        self.trees(&r#if.then_branch);
+        match &r#if.else_branch {
+            Some(HtmlElse::If(_, r#if)) => self.r#if(r#if),
+            Some(HtmlElse::Tree(_, _, trees)) => self.trees(trees),
+            None => (),
+        }
+    }
+
+    fn element(&mut self, element: &HtmlElement) {
+        let (props, children) = match element {
+            HtmlElement::Fragment(fragment) => return self.trees(&fragment.children),
+            HtmlElement::Dynamic(element) => (&element.props, &element.children),
+            HtmlElement::Literal(element) => {
+                self.invocation(element);
+                (&element.props, &element.children)
+            }
+        };
+        for prop in props {
+            if let HtmlPropKind::Block(_, _, block) = &prop.kind {
+                block.stmts.iter().for_each(|stmt| {
+                    if let Stmt::Expr(expr, _) = stmt {
+                        self.nested(expr)
+                    }
+                })
+            }
+        }
+        self.trees(children)
+    }
+
+    fn invocation(&mut self, element: &HtmlLiteralElement) {
+        let name: String = element.name.to_string().split_whitespace().collect();
+        let last_segment = name.rsplit("::").next().unwrap_or_default();
+        if !last_segment.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return;
+        }
+
+        let props = element
+            .props
+            .iter()
+            .map(|prop| {
+                let style = match &prop.kind {
+                    HtmlPropKind::Shortcut(..) => PropStyle::Shorthand,
+                    HtmlPropKind::Literal(..) => PropStyle::Literal,
+                    HtmlPropKind::Block(name, _, block) => match &*block.stmts {
+                        [Stmt::Expr(Expr::Lit(_), None)] => PropStyle::BracedLiteral,
+                        [Stmt::Expr(Expr::Path(p), None)]
+                            if name.len() == 1
+                                && name.first().is_some_and(|name| p.path.is_ident(&**name)) =>
+                        {
+                            PropStyle::Named
+                        }
+                        _ => PropStyle::Expr,
+                    },
+                };
+                (prop_name(&prop.kind), style)
+            })
+            .collect();
+
+        let location = self.location(element.lt_token.span().start());
+        self.audit.invocations.entry(name).or_default().push(Invocation { location, props });
+    }
+}
+
+pub(crate) fn prop_name(kind: &HtmlPropKind) -> String {
+    let name = match kind {
+        HtmlPropKind::Shortcut(_, name)
+        | HtmlPropKind::Literal(name, ..)
+        | HtmlPropKind::Block(name, ..) => name,
+    };
+    name.iter().map(|part| part.to_string()).collect::<Vec<_>>().join("-")
+}
@@ -0,0 +1,176 @@
+//! `cargo yew-fmt`: formats all the targets of the packages in a Cargo workspace with `yew-fmt`
+
+use anyhow::{bail, Context};
+use clap::{Args, Parser};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env::{self, consts::EXE_SUFFIX},
+    ffi::OsString,
+    path::PathBuf,
+    process::{Command, ExitCode},
+};
+use yew_fmt::Result;
+
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    YewFmt(Cli),
+}
+
+/// Formats all the targets of the packages in the workspace with `yew-fmt`
+#[derive(Args)]
+#[command(author, version)]
+struct Cli {
+    /// Run `yew-fmt` in 'check' mode, exiting with 1 and printing a diff if formatting is required
+    #[arg(long)]
+    check: bool,
+    /// Package to format, can be passed several times; all the workspace members are formatted
+    /// by default
+    #[arg(long, short, value_name = "package")]
+    package: Vec<String>,
+    /// Path to the Cargo.toml of the workspace
+    #[arg(long, value_name = "path")]
+    manifest_path: Option<PathBuf>,
+    /// Options passed to `yew-fmt`
+    #[arg(last = true, value_name = "yew-fmt options")]
+    yew_fmt_args: Vec<OsString>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+    workspace_members: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    id: String,
+    name: String,
+    dependencies: Vec<Dependency>,
+    targets: Vec<Target>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    name: String,
+}
+
+impl Package {
+    /// Whether the package's HTML is written in the syntax of `yew-html-ext`. The version of `yew`
+    /// isn't considered, the HTML of all of them is parsed alike
+    fn uses_html_ext(&self) -> bool {
+        self.dependencies.iter().any(|dep| dep.name == "yew-html-ext")
+    }
+
+    /// Whether the package's views are written with Maud's `html!`
+    fn uses_maud(&self) -> bool {
+        self.dependencies.iter().any(|dep| dep.name == "maud")
+    }
+}
+
+#[derive(Deserialize)]
+struct Target {
+    src_path: PathBuf,
+    edition: String,
+}
+
+fn metadata(manifest_path: Option<&PathBuf>) -> Result<Metadata> {
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo.args(["metadata", "--format-version", "1", "--no-deps"]);
+    if let Some(manifest_path) = manifest_path {
+        cargo.arg("--manifest-path").arg(manifest_path);
+    }
+    let output = cargo.output().context("failed to run `cargo metadata`")?;
+    if !output.status.success() {
+        bail!("`cargo metadata` failed:\n{}", String::from_utf8_lossy(&output.stderr).trim_end());
+    }
+    serde_json::from_slice(&output.stdout).context("failed to parse the output of `cargo metadata`")
+}
+
+/// Returns `yew-fmt` options with `option` set to `value`, unless it's already set explicitly, in
+/// which case the options are returned as is
+fn with_flavor(args: &[OsString], option: &str, value: &str) -> Vec<OsString> {
+    let flavor = format!("{option}={value}");
+    let mut res = args.to_vec();
+    let mut iter = res.iter_mut();
+    while let Some(arg) = iter.next() {
+        let config = if arg == "--config" {
+            let Some(config) = iter.next() else { break };
+            config
+        } else if arg.to_str().is_some_and(|arg| arg.starts_with("--config=")) {
+            arg
+        } else {
+            continue;
+        };
+        if !config.to_string_lossy().contains(option) {
+            config.push(format!(",{flavor}"));
+        }
+        return res;
+    }
+    res.extend(["--config".into(), flavor.into()]);
+    res
+}
+
+/// Prefers the `yew-fmt` binary installed alongside this one
+fn yew_fmt_path() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(format!("yew-fmt{EXE_SUFFIX}"))))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| "yew-fmt".into())
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let Cargo::YewFmt(args) = Cargo::parse();
+    let metadata = metadata(args.manifest_path.as_ref())?;
+
+    for name in &args.package {
+        if !metadata.packages.iter().any(|p| &p.name == name) {
+            bail!("package `{name}` is not a member of the workspace");
+        }
+    }
+    let packages = metadata.packages.iter().filter(|package| {
+        if args.package.is_empty() {
+            metadata.workspace_members.contains(&package.id)
+        } else {
+            args.package.contains(&package.name)
+        }
+    });
+
+    // the root files of the targets, grouped by edition & by whether the package uses the syntax
+    // of `yew-html-ext` or Maud, since those are passed to `yew-fmt` as options
+    let mut files = BTreeMap::<(&str, bool, bool), Vec<&PathBuf>>::new();
+    for package in packages {
+        for target in &package.targets {
+            let key = (&*target.edition, package.uses_html_ext(), package.uses_maud());
+            let files = files.entry(key).or_default();
+            if !files.contains(&&target.src_path) {
+                files.push(&target.src_path);
+            }
+        }
+    }
+
+    let yew_fmt = yew_fmt_path();
+    // the highest exit code of the runs, so that e.g. a config error isn't masked by a diff
+    let mut code = 0;
+    for ((edition, ext, maud), files) in files {
+        let mut cmd = Command::new(&yew_fmt);
+        cmd.args(["--edition", edition]);
+        if args.check {
+            cmd.arg("--check");
+        }
+        let mut yew_fmt_args = args.yew_fmt_args.clone();
+        if ext {
+            yew_fmt_args = with_flavor(&yew_fmt_args, "yew.html_flavor", r#""Ext""#);
+        }
+        if maud {
+            yew_fmt_args = with_flavor(&yew_fmt_args, "yew.view_flavor", r#""Maud""#);
+        }
+        cmd.args(yew_fmt_args);
+        let status =
+            cmd.args(files).status().with_context(|| format!("failed to run {yew_fmt:?}"))?;
+        code = code.max(status.code().map_or(1, |code| code.clamp(0, 255) as u8));
+    }
+    Ok(ExitCode::from(code))
+}
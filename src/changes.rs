@@ -0,0 +1,342 @@
+//! The registry of the changes to the formatter's output across releases, for telling what
+//! upgrading from an older version will reformat
+
+use crate::utils::Result;
+use anyhow::{bail, Context};
+use std::fmt::{self, Display, Formatter};
+
+/// A `major.minor.patch` version of `yew-fmt`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u64, pub u64, pub u64);
+
+impl Version {
+    /// Parses a version with an optional `v` prefix, a missing minor or patch component being
+    /// treated as 0
+    pub fn parse(src: &str) -> Result<Self> {
+        let mut parts = src.strip_prefix('v').unwrap_or(src).splitn(3, '.').map(|part| {
+            part.parse::<u64>().with_context(|| format!("invalid version component: `{part}`"))
+        });
+        let major = parts.next().context("empty version")??;
+        let minor = parts.next().transpose()?.unwrap_or(0);
+        let patch = parts.next().transpose()?.unwrap_or(0);
+        Ok(Self(major, minor, patch))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// A requirement on the version of `yew-fmt`, written the same way as those of Cargo: a list of
+/// comma-separated comparators like `>=0.5`, `~0.6.1`, `0.6.*` or `0.6`, the latter being the same
+/// as `^0.6`, or `*` for any version
+#[derive(Clone, Debug)]
+pub struct VersionReq {
+    src: String,
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Comparator {
+    op: Op,
+    version: Version,
+    /// the number of components written, e.g. 2 for `0.6`, which matches any `0.6.*` with `=`
+    n_parts: usize,
+}
+
+impl Comparator {
+    fn matches(self, v: Version) -> bool {
+        let Version(major, minor, patch) = self.version;
+        let next_major = Version(major + 1, 0, 0);
+        let next_minor = Version(major, minor + 1, 0);
+        // the first version past those matched by the written components
+        let past = match self.n_parts {
+            1 => next_major,
+            2 => next_minor,
+            _ => Version(major, minor, patch + 1),
+        };
+        let at_least = self.version <= v;
+        match self.op {
+            Op::Exact => at_least && v < past,
+            Op::Greater => v >= past,
+            Op::GreaterEq => at_least,
+            Op::Less => v < self.version,
+            Op::LessEq => v < past,
+            Op::Tilde => at_least && v < if self.n_parts == 1 { next_major } else { next_minor },
+            Op::Caret => {
+                at_least
+                    && v < if major > 0 || self.n_parts == 1 {
+                        next_major
+                    } else if minor > 0 || self.n_parts == 2 {
+                        next_minor
+                    } else {
+                        past
+                    }
+            }
+        }
+    }
+}
+
+impl VersionReq {
+    pub fn parse(src: &str) -> Result<Self> {
+        let src = src.trim();
+        if src == "*" {
+            return Ok(Self { src: src.to_owned(), comparators: vec![] });
+        }
+        let comparators = src
+            .split(',')
+            .map(|comparator| {
+                let comparator = comparator.trim();
+                let (op, version) = [
+                    (">=", Op::GreaterEq),
+                    ("<=", Op::LessEq),
+                    (">", Op::Greater),
+                    ("<", Op::Less),
+                    ("=", Op::Exact),
+                    ("~", Op::Tilde),
+                    ("^", Op::Caret),
+                ]
+                .into_iter()
+                .find_map(|(prefix, op)| Some((op, comparator.strip_prefix(prefix)?)))
+                .unwrap_or((Op::Caret, comparator));
+                // `0.6.*` is the same as `=0.6`
+                let mut version = version.trim();
+                let mut op = op;
+                while let Some(rest) = [".*", ".x", ".X"]
+                    .into_iter()
+                    .find_map(|wildcard| version.strip_suffix(wildcard))
+                {
+                    (version, op) = (rest, Op::Exact);
+                }
+                Ok(Comparator {
+                    op,
+                    version: Version::parse(version)?,
+                    n_parts: version.split('.').count(),
+                })
+            })
+            .collect::<Result<_>>()
+            .with_context(|| format!("invalid version requirement: `{src}`"))?;
+        Ok(Self { src: src.to_owned(), comparators })
+    }
+
+    pub fn matches(&self, version: Version) -> bool {
+        self.comparators.iter().all(|comparator| comparator.matches(version))
+    }
+}
+
+impl Display for VersionReq {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.src)
+    }
+}
+
+/// The release a change first appeared in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Release {
+    /// Not released yet, to be replaced with the version of the next release once it's cut
+    Unreleased,
+    Version(Version),
+}
+
+impl Release {
+    pub fn is_after(self, version: Version) -> bool {
+        match self {
+            Self::Unreleased => true,
+            Self::Version(release) => release > version,
+        }
+    }
+}
+
+impl Display for Release {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreleased => f.write_str("unreleased"),
+            Self::Version(version) => version.fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The default value of an option changed; the old behaviour can be reproduced by setting
+    /// `option` to `old`, written the same way as in `--config`
+    Default { option: &'static str, old: &'static str },
+    /// A rule of the layout changed, which can't be reverted with the config
+    Layout,
+}
+
+/// A change that makes the formatter produce different output from the same input & config
+#[derive(Clone, Copy, Debug)]
+pub struct StyleChange {
+    pub release: Release,
+    pub kind: ChangeKind,
+    pub description: &'static str,
+}
+
+impl Display for StyleChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ChangeKind::Default { option, old } => {
+                write!(
+                    f,
+                    "{}: {} (`{option}` used to default to `{old}`)",
+                    self.release, self.description
+                )
+            }
+            ChangeKind::Layout => write!(f, "{}: {}", self.release, self.description),
+        }
+    }
+}
+
+/// All the changes to the output since 0.5.1, newest first.
+/// Every change that makes already formatted code get reformatted must be added here
+pub const STYLE_CHANGES: &[StyleChange] = &[
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "with `hard_tabs` set, the HTML is indented with tabs, each counting as \
+                      `tab_spaces` columns",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the closing delimiter of a block ending with a line comment is indented \
+                      like the block's start when `tab_spaces` isn't 4",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Default { option: "yew.width_heuristic", old: "\"bytes\"" },
+        description: "the width of the HTML is measured in the columns it's displayed in, \
+                      rather than in bytes",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the line breaks of the formatted HTML follow `newline_style`, instead of \
+                      always being `\\n`",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the HTML in lines indented with tabs is indented relative to their width, \
+                      a tab counting as `tab_spaces` columns",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the HTML macros in expressions & in items inside functions marked with \
+                      `#[rustfmt::skip]` are left as is",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the width available to an HTML macro is measured from its column in the \
+                      formatted code, which differs from the source one after other macros on \
+                      the same line",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "with `format_code_in_doc_comments` set, the HTML in the code blocks of doc \
+                      comments is formatted",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "`html!` invocations resolved to `maud::html` are formatted as Maud's",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "`props!` invocations delimited with braces are formatted",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description:
+            "HTML macros nested in the blocks among the children of elements are formatted",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "HTML macros nested in the tokens of other macros are formatted",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the aliases of `html!` imported with `use` or set in `yew.macro_aliases` \
+                      are formatted",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "block comments on their own line stay there, attached to the following node",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "the macros listed in `skip_macro_invocations` or `#[rustfmt::skip::macros]` \
+                      are left as is",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "long `classes!` arguments in props are broken up",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "elements with `~` props are formatted",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "control characters aren't counted towards the width of the text",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "long call arguments in the blocks among the children of elements are \
+                      broken up",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "multiline text copied verbatim is re-indented to match its new position",
+    },
+    StyleChange {
+        release: Release::Unreleased,
+        kind: ChangeKind::Layout,
+        description: "HTML macros nested in prop values, e.g. in `Suspense`'s `fallback`, \
+                      are formatted",
+    },
+];
+
+/// The oldest version the changes since which are recorded in [`STYLE_CHANGES`]
+pub const FIRST_RECORDED: Version = Version(0, 5, 1);
+
+/// Returns the changes made after the version `since`, newest first.
+/// Fails if `since` is newer than `current`, or older than [`FIRST_RECORDED`], since the changes
+/// made before it aren't known
+pub fn changes_since(since: Version, current: Version) -> Result<Vec<&'static StyleChange>> {
+    if since > current {
+        bail!("{since} is newer than the current version, {current}");
+    }
+    if since < FIRST_RECORDED {
+        bail!("the changes made before {FIRST_RECORDED} aren't recorded");
+    }
+    Ok(STYLE_CHANGES.iter().filter(|change| change.release.is_after(since)).collect())
+}
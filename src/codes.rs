@@ -0,0 +1,134 @@
+//! The registry of the codes of the diagnostics reported for the macros that can't be formatted,
+//! with the longer explanations printed by `yew-fmt --explain`
+
+use std::fmt::Display;
+
+/// A class of diagnostics, reported as `error[Y0001]: ...`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ErrorCode {
+    /// `Y` followed by 4 digits; never reused once released, even if the diagnostic is removed
+    pub code: &'static str,
+    pub title: &'static str,
+    /// A description of the error & of how to fix it, with examples
+    pub explanation: &'static str,
+}
+
+pub const SYNTAX_ERROR: ErrorCode = ErrorCode {
+    code: "Y0001",
+    title: "the macro's input isn't valid HTML",
+    explanation: "\
+The contents of an HTML macro couldn't be parsed with the syntax of the configured flavor, so the
+macro is left as is, while the rest of the file is still formatted.
+The message of the error tells what was expected where the parsing stopped, e.g.:
+
+    html! { <div>{ \"text\" </div> }
+
+Here the block isn't closed before the closing tag, which is fixed by adding the `}`:
+
+    html! { <div>{ \"text\" }</div> }",
+};
+
+pub const STRAY_PROP_PUNCT: ErrorCode = ErrorCode {
+    code: "Y0002",
+    title: "punctuation among the props of an element",
+    explanation: "\
+The props of an element are separated with whitespace only, so punctuation other than the `..` of
+the base props isn't allowed between them. The props after it are still checked, so every stray
+token is pointed at, e.g.:
+
+    html! { <div class=\"row\", id=\"main\"; /> }
+
+Both the comma & the semicolon have to be removed:
+
+    html! { <div class=\"row\" id=\"main\" /> }",
+};
+
+pub const UNCLOSED_TAG: ErrorCode = ErrorCode {
+    code: "Y0003",
+    title: "an element with children is never closed",
+    explanation: "\
+An element that has children must end with a closing tag, the end of the macro's input was
+reached before it, e.g.:
+
+    html! { <p>{ \"text\" } }
+
+Either the closing tag is to be added, or the element is to be made self-closing if it has no
+children:
+
+    html! { <p>{ \"text\" }</p> }",
+};
+
+pub const MISMATCHED_TAG: ErrorCode = ErrorCode {
+    code: "Y0004",
+    title: "a closing tag doesn't match the innermost open element",
+    explanation: "\
+A closing tag must have the same name as the opening tag of the innermost element that isn't
+closed yet, which is pointed at by the error, e.g.:
+
+    html! { <div><p>{ \"text\" }</div> }
+
+Here the `<p>` is never closed, which is fixed by closing it before the `<div>`:
+
+    html! { <div><p>{ \"text\" }</p></div> }",
+};
+
+pub const EXT_SYNTAX: ErrorCode = ErrorCode {
+    code: "Y0005",
+    title: "the syntax of `yew-html-ext` is used with the base flavor",
+    explanation: "\
+The `for`, `match` & `let` nodes, as well as the other extensions of `yew-html-ext`, aren't
+supported by Yew's own `html!`, so they're only parsed once `yew.html_flavor` is set to \"Ext\",
+e.g. for
+
+    html! { <ul>for item in items { <li>{ item }</li> }</ul> }
+
+the following is to be put into `rustfmt.toml`:
+
+    [yew]
+    html_flavor = \"Ext\"",
+};
+
+pub const INVALID_INLINE_CONFIG: ErrorCode = ErrorCode {
+    code: "Y0006",
+    title: "invalid config override above a macro",
+    explanation: "\
+A `// yew-fmt: key=value, ...` comment above a macro overrides the options of the config for that
+macro only, so its keys must be names of the `yew.*` options, written without the `yew.` prefix,
+with values those options accept, e.g.:
+
+    // yew-fmt: html_width=60, use_small_heuristics=\"Max\"
+    html! { <div /> }",
+};
+
+/// All the codes, in the order of their numbers
+pub const ERROR_CODES: &[ErrorCode] = &[
+    SYNTAX_ERROR,
+    STRAY_PROP_PUNCT,
+    UNCLOSED_TAG,
+    MISMATCHED_TAG,
+    EXT_SYNTAX,
+    INVALID_INLINE_CONFIG,
+];
+
+/// Looks up a code, ignoring the case of the `Y`
+pub fn find(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES.iter().find(|c| c.code.eq_ignore_ascii_case(code))
+}
+
+impl ErrorCode {
+    /// Prepends the code to the message of a parse error, for it to be reported with the
+    /// diagnostic made from the error
+    pub(crate) fn tag(&self, message: impl Display) -> String {
+        format!("[{}] {message}", self.code)
+    }
+}
+
+/// Splits the code prepended with [`ErrorCode::tag`] off the message of a parse error, the errors
+/// without one being [`SYNTAX_ERROR`]s
+pub(crate) fn untag(message: &str) -> (&'static ErrorCode, &str) {
+    message
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+        .and_then(|(code, message)| Some((find(code)?, message)))
+        .unwrap_or((&SYNTAX_ERROR, message))
+}
@@ -1,50 +1,299 @@
+use crate::changes::VersionReq;
+use crate::glob;
 use crate::utils::{Result, StrExt};
-use anyhow::{anyhow, bail, Context};
+use anyhow::{bail, Context};
 use dirs::{config_dir, home_dir};
 use serde::{Deserialize, Deserializer};
 use std::{
-    collections::HashMap, env::current_dir, fs::read_to_string, io, num::ParseIntError, path::Path,
+    collections::HashMap,
+    env::current_dir,
+    fmt::{self, Display},
+    fs::read_to_string,
+    num::ParseIntError,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
 };
 
+/// The name of the config file that only holds `yew-fmt`'s own options, without the `yew.` prefix,
+/// for them not to be seen by `rustfmt` & the other tools reading `rustfmt.toml`
+const YEW_FMT_TOML: &str = "yew-fmt.toml";
+
+/// The manifest of a crate or a workspace, whose `[package.metadata.yew-fmt]` &
+/// `[workspace.metadata.yew-fmt]` tables can hold `yew-fmt`'s own options, same as `yew-fmt.toml`
+const CARGO_TOML: &str = "Cargo.toml";
+
 #[derive(Clone)]
 pub struct Config {
     pub tab_spaces: usize,
+    /// whether the code is indented with tabs, `tab_spaces` columns wide, instead of spaces
+    pub hard_tabs: bool,
+    /// the edition of Rust the files are parsed by the rules of
+    pub edition: Edition,
+    /// the line breaks the formatted code is written with
+    pub newline_style: NewlineStyle,
+    /// names of the macros whose invocations shouldn't be formatted, `*` matches any macro
+    pub skip_macro_invocations: MacroNames,
+    /// the files & directories not to be formatted
+    pub ignore: IgnoreList,
     pub yew: YewConfig,
 }
 
 #[derive(Clone)]
 pub struct YewConfig {
     pub html_width: usize,
+    /// the widest an opening tag can be on one line, props included, if it's to be narrower than
+    /// `html_width`
+    pub max_element_width: Option<usize>,
+    /// the most props an element can have without them being put on one line each regardless of
+    /// the width
+    pub attr_count_threshold: Option<usize>,
     pub use_small_heuristics: UseSmallHeuristics,
     pub unwrap_literal_prop_values: bool,
     pub use_prop_init_shorthand: bool,
     pub self_close_elements: bool,
+    pub space_after_access_spec: bool,
+    pub compact_closing_tags: bool,
+    /// whether the children of a broken element are put on one line each when they fit there,
+    /// regardless of `use_small_heuristics`
+    pub collapse_children: bool,
+    /// whether to format the CSS in the string literals passed to `stylist`'s `css!` & `style!`
+    pub format_stylist: bool,
+    /// whether to format the HTML macros in the bodies of `macro_rules!` definitions that parse
+    /// without substituting the macro's arguments
+    pub format_macro_rules: bool,
+    /// whether the formatted files are made to end with exactly one line break
+    pub newline_at_eof: bool,
+    /// whether to format the HTML in the Rust code blocks of `///` & `//!` doc comments
+    pub format_doc_comments: bool,
+    /// names or paths of the macros to be formatted as the `html!` or `html_nested!` macros they
+    /// map to
+    pub macro_aliases: MacroAliases,
+    /// names or paths of the macros to be formatted with the syntax of one of the dialects,
+    /// regardless of `view_flavor`
+    pub dialect_macros: DialectMacros,
     pub ext: bool,
+    pub view_flavor: ViewFlavor,
+    /// how the props of an opening tag broken into several lines are indented
+    pub attr_indent_style: IndentStyle,
+    /// the number of spaces the props of a broken opening tag are indented by with the `Block`
+    /// style, `tab_spaces` unless set
+    pub attr_indent: usize,
+    /// whether the `>` or `/>` ending a broken opening tag is put on the line of the last prop
+    /// with the `Block` style, instead of on a line of its own
+    pub closing_bracket_same_line: bool,
+    /// whether the props of a broken opening tag are put on one line each, instead of on as few
+    /// lines as they fit on
+    pub single_attribute_per_line: bool,
+    /// how the width of the code is measured when deciding whether it fits on a line
+    pub width_heuristic: WidthHeuristic,
+    /// the versions of `yew-fmt` the project is to be formatted with
+    pub required_version: Option<VersionReq>,
     pub unknown: HashMap<String, Unknown>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 struct RawConfig {
     tab_spaces: Option<usize>,
+    hard_tabs: Option<bool>,
+    edition: Option<Edition>,
+    newline_style: Option<NewlineStyle>,
     max_width: Option<usize>,
     use_field_init_shorthand: Option<bool>,
     use_small_heuristics: Option<UseSmallHeuristics>,
+    skip_macro_invocations: Option<MacroNames>,
+    format_code_in_doc_comments: Option<bool>,
+    ignore: Option<Vec<String>>,
     #[serde(default)]
     yew: RawConfigYew,
+    /// the named sets of `yew-fmt`'s options, one of which can be selected with `--profile`
+    profile: Option<Profiles>,
+}
+
+type Profiles = HashMap<String, RawConfigYew>;
+
+/// The contents of a `yew-fmt.toml`
+#[derive(Deserialize)]
+struct RawYewFmtToml {
+    profile: Option<Profiles>,
+    #[serde(flatten)]
+    yew: RawConfigYew,
 }
 
 #[derive(Deserialize, Default)]
 struct RawConfigYew {
     html_width: Option<usize>,
+    html_width_percent: Option<usize>,
+    max_element_width: Option<usize>,
+    attr_count_threshold: Option<usize>,
     unwrap_literal_prop_values: Option<bool>,
     use_small_heuristics: Option<UseSmallHeuristics>,
     use_prop_init_shorthand: Option<bool>,
     self_close_elements: Option<bool>,
+    space_after_access_spec: Option<bool>,
+    compact_closing_tags: Option<bool>,
+    collapse_children: Option<bool>,
+    format_stylist: Option<bool>,
+    format_doc_comments: Option<bool>,
+    newline_at_eof: Option<bool>,
+    format_macro_rules: Option<bool>,
+    macro_aliases: Option<MacroAliases>,
+    dialect_macros: Option<DialectMacros>,
     html_flavor: Option<HtmlFlavor>,
+    view_flavor: Option<ViewFlavor>,
+    attr_indent_style: Option<IndentStyle>,
+    attr_indent: Option<usize>,
+    closing_bracket_same_line: Option<bool>,
+    single_attribute_per_line: Option<bool>,
+    width_heuristic: Option<WidthHeuristic>,
+    required_version: Option<VersionReq>,
     #[serde(flatten)]
     unknown: HashMap<String, Unknown>,
 }
 
+/// Merges 2 tables of options, the entries of `inner` overriding those of `outer` with the same key
+fn or_table<V>(
+    inner: Option<HashMap<String, V>>,
+    outer: Option<HashMap<String, V>>,
+) -> Option<HashMap<String, V>> {
+    match (inner, outer) {
+        (Some(inner), Some(mut outer)) => {
+            outer.extend(inner);
+            Some(outer)
+        }
+        (inner, outer) => inner.or(outer),
+    }
+}
+
+impl RawConfig {
+    /// Fills in the options not set in `self` with those of `outer`, the config of one of the
+    /// parent directories. The tables are merged, the other options are taken as a whole
+    fn or(self, outer: Self) -> Self {
+        Self {
+            tab_spaces: self.tab_spaces.or(outer.tab_spaces),
+            hard_tabs: self.hard_tabs.or(outer.hard_tabs),
+            edition: self.edition.or(outer.edition),
+            newline_style: self.newline_style.or(outer.newline_style),
+            max_width: self.max_width.or(outer.max_width),
+            use_field_init_shorthand: self
+                .use_field_init_shorthand
+                .or(outer.use_field_init_shorthand),
+            use_small_heuristics: self.use_small_heuristics.or(outer.use_small_heuristics),
+            skip_macro_invocations: self.skip_macro_invocations.or(outer.skip_macro_invocations),
+            format_code_in_doc_comments: self
+                .format_code_in_doc_comments
+                .or(outer.format_code_in_doc_comments),
+            // collected separately by `Config::fetch`, since the paths are relative to the
+            // directories of their config files
+            ignore: None,
+            yew: self.yew.or(outer.yew),
+            // applied to the options of their config files before they're merged
+            profile: None,
+        }
+    }
+}
+
+impl RawConfigYew {
+    fn or(self, outer: Self) -> Self {
+        let mut unknown = outer.unknown;
+        unknown.extend(self.unknown);
+        Self {
+            html_width: self.html_width.or(outer.html_width),
+            html_width_percent: self.html_width_percent.or(outer.html_width_percent),
+            max_element_width: self.max_element_width.or(outer.max_element_width),
+            attr_count_threshold: self.attr_count_threshold.or(outer.attr_count_threshold),
+            unwrap_literal_prop_values: self
+                .unwrap_literal_prop_values
+                .or(outer.unwrap_literal_prop_values),
+            use_small_heuristics: self.use_small_heuristics.or(outer.use_small_heuristics),
+            use_prop_init_shorthand: self.use_prop_init_shorthand.or(outer.use_prop_init_shorthand),
+            self_close_elements: self.self_close_elements.or(outer.self_close_elements),
+            space_after_access_spec: self.space_after_access_spec.or(outer.space_after_access_spec),
+            compact_closing_tags: self.compact_closing_tags.or(outer.compact_closing_tags),
+            collapse_children: self.collapse_children.or(outer.collapse_children),
+            format_stylist: self.format_stylist.or(outer.format_stylist),
+            format_doc_comments: self.format_doc_comments.or(outer.format_doc_comments),
+            newline_at_eof: self.newline_at_eof.or(outer.newline_at_eof),
+            format_macro_rules: self.format_macro_rules.or(outer.format_macro_rules),
+            macro_aliases: or_table(self.macro_aliases, outer.macro_aliases),
+            dialect_macros: or_table(self.dialect_macros, outer.dialect_macros),
+            html_flavor: self.html_flavor.or(outer.html_flavor),
+            view_flavor: self.view_flavor.or(outer.view_flavor),
+            attr_indent_style: self.attr_indent_style.or(outer.attr_indent_style),
+            attr_indent: self.attr_indent.or(outer.attr_indent),
+            closing_bracket_same_line: self
+                .closing_bracket_same_line
+                .or(outer.closing_bracket_same_line),
+            single_attribute_per_line: self
+                .single_attribute_per_line
+                .or(outer.single_attribute_per_line),
+            width_heuristic: self.width_heuristic.or(outer.width_heuristic),
+            required_version: self.required_version.or(outer.required_version),
+            unknown,
+        }
+    }
+}
+
+/// Exists to be used as a type name in [`Config::parse`]
+pub type MacroNames = Vec<String>;
+
+/// Exists to be used as a type name in [`Config::parse`]
+pub type MacroAliases = HashMap<String, String>;
+
+/// Exists to be used as a type name in [`Config::parse`]
+pub type DialectMacros = HashMap<String, DialectName>;
+
+/// The paths listed in rustfmt's `ignore` option, relative to the directory of the config file.
+/// The paths are split into components at both `/` & `\` regardless of the platform, so that a
+/// config shared between platforms ignores the same files on all of them. A component can contain
+/// `*` & `?` wildcards, and a `**` component matches any number of directories
+#[derive(Clone, Default)]
+pub struct IgnoreList {
+    /// the paths listed in each of the merged config files, along with the directory they're
+    /// relative to, the current one if `None`
+    lists: Vec<(Option<PathBuf>, Vec<Vec<String>>)>,
+}
+
+impl IgnoreList {
+    fn new(paths: &[String]) -> Self {
+        let paths: Vec<Vec<String>> =
+            paths.iter().map(|path| glob::components(path).map(str::to_owned).collect()).collect();
+        Self { lists: if paths.is_empty() { vec![] } else { vec![(None, paths)] } }
+    }
+
+    /// Whether `file` is one of the ignored files or is in one of the ignored directories
+    pub fn is_ignored(&self, file: &Path) -> bool {
+        if self.lists.is_empty() {
+            return false;
+        }
+        let absolute = |path: &Path| {
+            path.canonicalize().or_else(|_| Ok::<_, std::io::Error>(current_dir()?.join(path)))
+        };
+        let Ok(file) = absolute(file) else { return false };
+        self.lists.iter().any(|(root, paths)| {
+            let Ok(root) = root.as_deref().map_or_else(current_dir, absolute) else { return false };
+            let Ok(relative) = file.strip_prefix(root) else { return false };
+            // the components are split again in case of a `\` on a platform where it's not a
+            // separator
+            let components: Vec<&str> = relative
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(c) => c.to_str(),
+                    _ => None,
+                })
+                .flat_map(glob::components)
+                .collect();
+            paths.iter().any(|pattern| glob::path_matches(pattern, &components, true))
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionReq {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let src = String::deserialize(deserializer)?;
+        Self::parse(&src).map_err(|e| serde::de::Error::custom(format!("{e:#}")))
+    }
+}
+
 /// exists to avoid any handling of the values of unknown keys
 #[derive(Clone, Copy)]
 pub struct Unknown;
@@ -55,7 +304,77 @@ impl<'de> Deserialize<'de> for Unknown {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+/// An edition of Rust, deciding which names are keywords, e.g. `async` is an identifier in Rust 2015
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Edition {
+    E2015,
+    E2018,
+    #[default]
+    E2021,
+    E2024,
+}
+
+impl Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::E2015 => "2015",
+            Self::E2018 => "2018",
+            Self::E2021 => "2021",
+            Self::E2024 => "2024",
+        })
+    }
+}
+
+impl FromStr for Edition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "2015" => Self::E2015,
+            "2018" => Self::E2018,
+            "2021" => Self::E2021,
+            "2024" => Self::E2024,
+            _ => bail!("expected `2015`, `2018`, `2021` or `2024`, instead got `{s}`"),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Edition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let src = String::deserialize(deserializer)?;
+        src.parse().map_err(|e| serde::de::Error::custom(format!("{e:#}")))
+    }
+}
+
+/// The line breaks to write the formatted code with, same as rustfmt's `newline_style`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum NewlineStyle {
+    /// the line breaks of the first line of the file, `Native` if it has only one line
+    #[default]
+    Auto,
+    /// `\r\n` on Windows, `\n` elsewhere
+    Native,
+    Unix,
+    Windows,
+}
+
+impl NewlineStyle {
+    /// The line break to write the code formatted from `src` with
+    pub fn newline(self, src: &str) -> &'static str {
+        match self {
+            Self::Auto => match src.find('\n') {
+                Some(i) if src[..i].ends_with('\r') => "\r\n",
+                Some(_) => "\n",
+                None => Self::Native.newline(src),
+            },
+            Self::Native if cfg!(windows) => "\r\n",
+            Self::Native | Self::Unix => "\n",
+            Self::Windows => "\r\n",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub enum UseSmallHeuristics {
     Off,
     Default,
@@ -70,6 +389,73 @@ pub enum HtmlFlavor {
     Ext,
 }
 
+/// The framework whose view macro is formatted on top of Yew's `html!`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum ViewFlavor {
+    Yew,
+    /// `leptos::view!`
+    Leptos,
+    /// `dioxus::rsx!`
+    Dioxus,
+    /// `sycamore::view!`
+    Sycamore,
+    /// `maud::html!`
+    Maud,
+}
+
+/// How the items of a list broken into several lines are indented, same as rustfmt's `indent_style`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum IndentStyle {
+    /// each item on its own line, one level deeper than the line the list starts on
+    #[default]
+    Block,
+    /// the first item on the line the list starts on, the rest aligned under it
+    Visual,
+}
+
+/// How the width of the code is measured when deciding whether it fits on a line
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WidthHeuristic {
+    /// the columns the code takes up in a terminal, e.g. 2 for a CJK character & 0 for a combining
+    /// mark
+    #[default]
+    Display,
+    /// the number of characters
+    Chars,
+    /// the number of bytes in UTF-8, the way the earlier versions measured it
+    Bytes,
+}
+
+/// How the width of the code is measured, as set by the config
+#[derive(Clone, Copy, Debug)]
+pub struct Widths {
+    pub heuristic: WidthHeuristic,
+    /// the width of a tab
+    pub tab_spaces: usize,
+}
+
+impl Display for WidthHeuristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Display => "display",
+            Self::Chars => "chars",
+            Self::Bytes => "bytes",
+        })
+    }
+}
+
+/// The syntaxes that macros can be assigned with `yew.dialect_macros`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum DialectName {
+    Leptos,
+    Dioxus,
+    Sycamore,
+    Maud,
+    /// rows of cells of Yew's HTML, e.g. `tr { <b>{ "Name" }</b>, { name } }`
+    Table,
+}
+
 fn parse_usize(src: &str) -> Result<usize, ParseIntError> {
     let (base, src) = match src.try_split_at(2) {
         Some(("0x", rest)) => (0x10, rest),
@@ -97,6 +483,77 @@ fn parse_use_small_heuristics(src: &str) -> Result<UseSmallHeuristics> {
     })
 }
 
+fn parse_macro_names(src: &str) -> Result<MacroNames> {
+    #[derive(Deserialize)]
+    struct Wrapper {
+        value: MacroNames,
+    }
+
+    Ok(basic_toml::from_str::<Wrapper>(&format!("value = {src}"))?.value)
+}
+
+fn parse_macro_aliases(src: &str) -> Result<MacroAliases> {
+    #[derive(Deserialize)]
+    struct Wrapper {
+        value: MacroAliases,
+    }
+
+    Ok(basic_toml::from_str::<Wrapper>(&format!("value = {src}"))?.value)
+}
+
+/// Parses an edition written either as a string, as in the config files, or as a number, the way
+/// rustfmt's `--edition` takes it
+fn parse_edition(src: &str) -> Result<Edition> {
+    src.strip_prefix('"').and_then(|src| src.strip_suffix('"')).unwrap_or(src).parse()
+}
+
+fn parse_version_req(src: &str) -> Result<VersionReq> {
+    #[derive(Deserialize)]
+    struct Wrapper {
+        value: VersionReq,
+    }
+
+    Ok(basic_toml::from_str::<Wrapper>(&format!("value = {src}"))?.value)
+}
+
+fn parse_dialect_macros(src: &str) -> Result<DialectMacros> {
+    #[derive(Deserialize)]
+    struct Wrapper {
+        value: DialectMacros,
+    }
+
+    Ok(basic_toml::from_str::<Wrapper>(&format!("value = {src}"))?.value)
+}
+
+fn parse_indent_style(src: &str) -> Result<IndentStyle> {
+    Ok(match src {
+        "\"Block\"" => IndentStyle::Block,
+        "\"Visual\"" => IndentStyle::Visual,
+        _ => bail!(r#"expected `"Block"` or `"Visual"`, instead got `{src}`"#),
+    })
+}
+
+fn parse_width_heuristic(src: &str) -> Result<WidthHeuristic> {
+    Ok(match src {
+        "\"display\"" => WidthHeuristic::Display,
+        "\"chars\"" => WidthHeuristic::Chars,
+        "\"bytes\"" => WidthHeuristic::Bytes,
+        _ => bail!(r#"expected `"display"`, `"chars"` or `"bytes"`, instead got `{src}`"#),
+    })
+}
+
+/// Parses a newline style written either as a string, as in the config files, or bare, the way
+/// rustfmt's `--config` takes it
+fn parse_newline_style(src: &str) -> Result<NewlineStyle> {
+    Ok(match src.strip_prefix('"').and_then(|src| src.strip_suffix('"')).unwrap_or(src) {
+        "Auto" => NewlineStyle::Auto,
+        "Native" => NewlineStyle::Native,
+        "Unix" => NewlineStyle::Unix,
+        "Windows" => NewlineStyle::Windows,
+        _ => bail!("expected `Auto`, `Native`, `Unix` or `Windows`, instead got `{src}`"),
+    })
+}
+
 fn parse_html_flavor(src: &str) -> Result<HtmlFlavor> {
     Ok(match src {
         "\"Base\"" => HtmlFlavor::Base,
@@ -105,11 +562,405 @@ fn parse_html_flavor(src: &str) -> Result<HtmlFlavor> {
     })
 }
 
+fn parse_view_flavor(src: &str) -> Result<ViewFlavor> {
+    Ok(match src {
+        "\"Yew\"" => ViewFlavor::Yew,
+        "\"Leptos\"" => ViewFlavor::Leptos,
+        "\"Dioxus\"" => ViewFlavor::Dioxus,
+        "\"Sycamore\"" => ViewFlavor::Sycamore,
+        "\"Maud\"" => ViewFlavor::Maud,
+        _ => bail!(
+            r#"expected `"Yew"`, `"Leptos"`, `"Dioxus"`, `"Sycamore"` or `"Maud"`, instead got `{src}`"#
+        ),
+    })
+}
+
+/// Parses the options in `[package.metadata.yew-fmt]` of a `Cargo.toml`, falling back to those in
+/// `[workspace.metadata.yew-fmt]` for the ones not set there, returns `None` if there are neither
+fn parse_cargo_metadata(src: &str) -> Result<Option<RawConfigYew>> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        package: Option<Section>,
+        workspace: Option<Section>,
+    }
+
+    #[derive(Deserialize)]
+    struct Section {
+        metadata: Option<Metadata>,
+    }
+
+    #[derive(Deserialize)]
+    struct Metadata {
+        #[serde(rename = "yew-fmt")]
+        yew_fmt: Option<RawConfigYew>,
+    }
+
+    let manifest: Manifest = basic_toml::from_str(src)?;
+    let [package, workspace] = [manifest.package, manifest.workspace]
+        .map(|section| section.and_then(|section| section.metadata?.yew_fmt));
+    Ok(match (package, workspace) {
+        (Some(package), Some(workspace)) => Some(package.or(workspace)),
+        (package, workspace) => package.or(workspace),
+    })
+}
+
+/// Returns the edition of the crate `dir` is in, from the `Cargo.toml` in it or in the nearest of
+/// its ancestors that has one, or `None` if it's not in a crate or the edition can't be found.
+/// Same as with Cargo, a crate that doesn't set its edition is in Rust 2015, & one that inherits it
+/// from its workspace takes it from the nearest `Cargo.toml` above it with a `[workspace.package]`
+/// table setting one
+pub fn crate_edition(dir: &Path) -> Result<Option<Edition>> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        package: Option<Package>,
+        workspace: Option<Workspace>,
+    }
+
+    #[derive(Deserialize)]
+    struct Package {
+        edition: Option<EditionField>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum EditionField {
+        Set(Edition),
+        Inherited {},
+    }
+
+    #[derive(Deserialize)]
+    struct Workspace {
+        package: Option<WorkspacePackage>,
+    }
+
+    #[derive(Deserialize)]
+    struct WorkspacePackage {
+        edition: Option<Edition>,
+    }
+
+    let dir = dir.canonicalize().with_context(|| format!("failed to resolve {dir:?}"))?;
+    let mut inherited = false;
+    for dir in dir.ancestors() {
+        let path = dir.join(CARGO_TOML);
+        if !path.try_exists().with_context(|| format!("failed to access {path:?}"))? {
+            continue;
+        }
+        let src = read_to_string(&path).with_context(|| format!("failed to read {path:?}"))?;
+        let manifest: Manifest =
+            basic_toml::from_str(&src).with_context(|| format!("failed to parse {path:?}"))?;
+        if inherited {
+            if let Some(edition) = manifest.workspace.and_then(|w| w.package?.edition) {
+                return Ok(Some(edition));
+            }
+            continue;
+        }
+        // e.g. the manifest of a virtual workspace, whose root isn't a crate
+        let Some(package) = manifest.package else { return Ok(None) };
+        match package.edition {
+            Some(EditionField::Set(edition)) => return Ok(Some(edition)),
+            Some(EditionField::Inherited {}) => inherited = true,
+            None => return Ok(Some(Edition::E2015)),
+        }
+        if let Some(edition) = manifest.workspace.and_then(|w| w.package?.edition) {
+            return Ok(Some(edition));
+        }
+    }
+    // a missing workspace edition is Cargo's error to report
+    Ok(None)
+}
+
+/// Calls `$callback` with the tokens passed to it followed by the list of the options that can be
+/// set with `--config`, with the types of their values & their descriptions, the list being the
+/// single source of both the parsing of the options & [`OPTIONS`]
+macro_rules! with_options {
+    ($callback:ident!($($args:tt)*)) => {
+        $callback!($($args)* {
+            tab_spaces: usize = "Number of spaces per level of indentation",
+            hard_tabs: bool = "Indent with tabs instead of spaces",
+            edition: Edition = "The edition of Rust the code is parsed by the rules of",
+            newline_style: NewlineStyle = "The line breaks the formatted code is written with",
+            max_width: usize = "Maximum width of each line, the default of `yew.html_width`",
+            use_field_init_shorthand: bool =
+                "Use field init shorthand if possible, the default of `yew.use_prop_init_shorthand`",
+            use_small_heuristics: UseSmallHeuristics =
+                "The rules for breaking up the code, the default of `yew.use_small_heuristics`",
+            skip_macro_invocations: MacroNames =
+                "Names of the macros whose invocations aren't formatted, `*` matches any macro",
+            format_code_in_doc_comments: bool =
+                "Format the code in doc comments, the default of `yew.format_doc_comments`",
+            yew.html_width: usize =
+                "Maximum width of an HTML node before falling back to vertical formatting",
+            yew.html_width_percent: usize =
+                "The default of `yew.html_width` as a percentage of `max_width`",
+            yew.max_element_width: usize =
+                "Maximum width of the opening tag of an element, props included",
+            yew.attr_count_threshold: usize =
+                "Maximum number of props of an element before they're put on one line each",
+            yew.use_small_heuristics: UseSmallHeuristics =
+                "The set of rules for when the different kinds of nodes are broken up",
+            yew.unwrap_literal_prop_values: bool =
+                "Remove braces around prop initialisers if they consist of only a literal",
+            yew.use_prop_init_shorthand: bool = "Use prop initialiser shorthand if possible",
+            yew.self_close_elements: bool = "Make elements self-closed if they have no children",
+            yew.space_after_access_spec: bool =
+                "Put a space between the `~` access specifier & the name of its prop",
+            yew.compact_closing_tags: bool =
+                "Put a closing tag on the line of the last child of a broken element if it fits",
+            yew.collapse_children: bool =
+                "Put each child of a broken element on one line if it fits there",
+            yew.format_stylist: bool =
+                "Format the CSS passed to the `css!` & `style!` macros of `stylist`",
+            yew.format_doc_comments: bool =
+                "Format the HTML macros in the Rust code blocks of doc comments",
+            yew.newline_at_eof: bool = "Make the formatted files end with exactly one line break",
+            yew.format_macro_rules: bool =
+                "Format the HTML macros in the bodies of `macro_rules!` definitions",
+            yew.macro_aliases: MacroAliases =
+                "Names or paths of macros to be formatted as `html!` or `html_nested!`",
+            yew.dialect_macros: DialectMacros =
+                "Names or paths of macros to be formatted with the syntax of one of the dialects",
+            yew.html_flavor: HtmlFlavor =
+                "The flavor of the HTML syntax, Yew's original or that of `yew-html-ext`",
+            yew.view_flavor: ViewFlavor = "The framework whose view macro is formatted",
+            yew.attr_indent_style: IndentStyle =
+                "Indent the props of a broken opening tag one level deeper, or align them",
+            yew.attr_indent: usize =
+                "Number of spaces the props of a broken opening tag are indented by",
+            yew.closing_bracket_same_line: bool =
+                "Put the end of a broken opening tag on the line of its last prop",
+            yew.single_attribute_per_line: bool =
+                "Put each prop of a broken opening tag on its own line, instead of packing them",
+            yew.width_heuristic: WidthHeuristic =
+                "How the width of the code is measured: in display columns, chars or bytes",
+            yew.required_version: VersionReq =
+                "The versions of yew-fmt allowed to format the project, e.g. `>=0.5, <0.7`"
+        })
+    };
+}
+
+macro_rules! field_kind {
+    [usize] => {"an integer"};
+    [bool] => {"a boolean"};
+    [UseSmallHeuristics] => {"`use_small_heuristics` enum"};
+    [HtmlFlavor] => {"`html_flavor` enum"};
+    [ViewFlavor] => {"`view_flavor` enum"};
+    [IndentStyle] => {"`attr_indent_style` enum"};
+    [WidthHeuristic] => {"`width_heuristic` enum"};
+    [MacroNames] => {"a list of macro names"};
+    [MacroAliases] => {"a table of macro names"};
+    [DialectMacros] => {"a table of macro names"};
+    [VersionReq] => {"a version requirement"};
+    [Edition] => {"an edition"};
+    [NewlineStyle] => {"`newline_style` enum"};
+}
+
+macro_rules! option_infos {
+    ({ $($($name:ident).+ : $ty:ident = $description:literal),+ }) => {
+        &[$(OptionInfo {
+            name: stringify!($($name).+),
+            kind: field_kind!($ty),
+            description: $description,
+        }),+]
+    };
+}
+
+/// An option that can be set in the config files & with `--config`
+#[derive(Clone, Copy, Debug)]
+pub struct OptionInfo {
+    /// The key of the option, e.g. `yew.html_width`
+    pub name: &'static str,
+    /// What the value of the option is, e.g. "an integer"
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+/// The options that can be set with `--config`: those of rustfmt read by `yew-fmt` too & all of
+/// `yew-fmt`'s own, in the order of the fields of [`Config`]
+pub const OPTIONS: &[OptionInfo] = with_options!(option_infos!());
+
+/// The number of single-character insertions, deletions & substitutions turning `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The `yew.*` option whose name is the closest to `name`, given without the `yew.` prefix, if
+/// it's close enough to be a likely misspelling of it
+pub fn closest_option(name: &str) -> Option<&'static str> {
+    OPTIONS
+        .iter()
+        .filter_map(|option| option.name.strip_prefix("yew."))
+        .map(|option| (edit_distance(name, option), option))
+        .filter(|(distance, _)| *distance <= (name.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, option)| option)
+}
+
+/// Writes a table of options as an inline TOML table, with the keys sorted
+fn inline_table<V>(table: &HashMap<String, V>, value: impl Fn(&V) -> String) -> String {
+    let mut entries: Vec<String> =
+        table.iter().map(|(k, v)| format!("{k:?} = {}", value(v))).collect();
+    entries.sort();
+    if entries.is_empty() {
+        "{}".to_owned()
+    } else {
+        format!("{{ {} }}", entries.join(", "))
+    }
+}
+
+impl YewConfig {
+    /// Lists the options with their values written as in TOML, in the order of the fields, leaving
+    /// out `max_element_width`, `attr_count_threshold` & `required_version` if they're not set since
+    /// TOML has no way to express that
+    pub fn options(&self) -> Vec<(&'static str, String)> {
+        let mut options = vec![("html_width", self.html_width.to_string())];
+        if let Some(max_element_width) = self.max_element_width {
+            options.push(("max_element_width", max_element_width.to_string()));
+        }
+        if let Some(attr_count_threshold) = self.attr_count_threshold {
+            options.push(("attr_count_threshold", attr_count_threshold.to_string()));
+        }
+        options.extend([
+            ("use_small_heuristics", format!("\"{:?}\"", self.use_small_heuristics)),
+            ("unwrap_literal_prop_values", self.unwrap_literal_prop_values.to_string()),
+            ("use_prop_init_shorthand", self.use_prop_init_shorthand.to_string()),
+            ("self_close_elements", self.self_close_elements.to_string()),
+            ("space_after_access_spec", self.space_after_access_spec.to_string()),
+            ("compact_closing_tags", self.compact_closing_tags.to_string()),
+            ("collapse_children", self.collapse_children.to_string()),
+            ("format_stylist", self.format_stylist.to_string()),
+            ("format_macro_rules", self.format_macro_rules.to_string()),
+            ("newline_at_eof", self.newline_at_eof.to_string()),
+            ("format_doc_comments", self.format_doc_comments.to_string()),
+            ("macro_aliases", inline_table(&self.macro_aliases, |v| format!("{v:?}"))),
+            ("dialect_macros", inline_table(&self.dialect_macros, |v| format!("\"{v:?}\""))),
+            ("html_flavor", if self.ext { "\"Ext\"" } else { "\"Base\"" }.to_owned()),
+            ("view_flavor", format!("\"{:?}\"", self.view_flavor)),
+            ("attr_indent_style", format!("\"{:?}\"", self.attr_indent_style)),
+            ("attr_indent", self.attr_indent.to_string()),
+            ("closing_bracket_same_line", self.closing_bracket_same_line.to_string()),
+            ("single_attribute_per_line", self.single_attribute_per_line.to_string()),
+            ("width_heuristic", format!("\"{}\"", self.width_heuristic)),
+        ]);
+        if let Some(required_version) = &self.required_version {
+            options.push(("required_version", format!("{:?}", required_version.to_string())));
+        }
+        options
+    }
+
+    /// Overrides the options with `key=value` pairs, the keys being the names of the options
+    /// without the `yew.` prefix and the values being written the same way as in `--config`
+    pub fn apply_overrides<'kv>(
+        &mut self,
+        overrides: impl IntoIterator<Item = (&'kv str, &'kv str)>,
+    ) -> Result {
+        for (key, value) in overrides {
+            let key = key.strip_prefix("yew.").unwrap_or(key);
+            let err_msg = || format!("received an invalid `{key}` value: `{value}`");
+            match key {
+                "html_width" => self.html_width = parse_usize(value).with_context(err_msg)?,
+                "max_element_width" => {
+                    self.max_element_width = Some(parse_usize(value).with_context(err_msg)?)
+                }
+                "attr_count_threshold" => {
+                    self.attr_count_threshold = Some(parse_usize(value).with_context(err_msg)?)
+                }
+                "use_small_heuristics" => {
+                    self.use_small_heuristics =
+                        parse_use_small_heuristics(value).with_context(err_msg)?
+                }
+                "unwrap_literal_prop_values" => {
+                    self.unwrap_literal_prop_values = parse_bool(value).with_context(err_msg)?
+                }
+                "use_prop_init_shorthand" => {
+                    self.use_prop_init_shorthand = parse_bool(value).with_context(err_msg)?
+                }
+                "self_close_elements" => {
+                    self.self_close_elements = parse_bool(value).with_context(err_msg)?
+                }
+                "space_after_access_spec" => {
+                    self.space_after_access_spec = parse_bool(value).with_context(err_msg)?
+                }
+                "compact_closing_tags" => {
+                    self.compact_closing_tags = parse_bool(value).with_context(err_msg)?
+                }
+                "collapse_children" => {
+                    self.collapse_children = parse_bool(value).with_context(err_msg)?
+                }
+                "format_stylist" => {
+                    self.format_stylist = parse_bool(value).with_context(err_msg)?
+                }
+                "format_doc_comments" => {
+                    self.format_doc_comments = parse_bool(value).with_context(err_msg)?
+                }
+                "format_macro_rules" => {
+                    self.format_macro_rules = parse_bool(value).with_context(err_msg)?
+                }
+                "newline_at_eof" => {
+                    self.newline_at_eof = parse_bool(value).with_context(err_msg)?
+                }
+                "html_flavor" => {
+                    self.ext = parse_html_flavor(value).with_context(err_msg)? == HtmlFlavor::Ext
+                }
+                "view_flavor" => {
+                    self.view_flavor = parse_view_flavor(value).with_context(err_msg)?
+                }
+                "attr_indent_style" => {
+                    self.attr_indent_style = parse_indent_style(value).with_context(err_msg)?
+                }
+                "attr_indent" => self.attr_indent = parse_usize(value).with_context(err_msg)?,
+                "closing_bracket_same_line" => {
+                    self.closing_bracket_same_line = parse_bool(value).with_context(err_msg)?
+                }
+                "single_attribute_per_line" => {
+                    self.single_attribute_per_line = parse_bool(value).with_context(err_msg)?
+                }
+                "width_heuristic" => {
+                    self.width_heuristic = parse_width_heuristic(value).with_context(err_msg)?
+                }
+                _ => bail!("unknown option `{key}`"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The config used when no config file is found and no options are overridden
+impl Default for Config {
+    fn default() -> Self {
+        Self::from_raw(RawConfig::default())
+    }
+}
+
 impl Config {
-    #[rustfmt::skip]
+    /// How the width of the code is measured
+    pub fn widths(&self) -> Widths {
+        Widths { heuristic: self.yew.width_heuristic, tab_spaces: self.tab_spaces }
+    }
+
+    /// Parses the config from the contents of a `rustfmt.toml` file, with `ext` overriding its
+    /// values in the same way the `--config` CLI option does
     pub fn parse<'ext>(
         src: &str,
         ext: impl IntoIterator<Item = &'ext (impl AsRef<str> + 'ext, impl AsRef<str> + 'ext)>,
+    ) -> Result<Self> {
+        Self::from_raw_with_overrides(basic_toml::from_str(src)?, ext)
+    }
+
+    #[rustfmt::skip]
+    fn from_raw_with_overrides<'ext>(
+        mut raw: RawConfig,
+        ext: impl IntoIterator<Item = &'ext (impl AsRef<str> + 'ext, impl AsRef<str> + 'ext)>,
     ) -> Result<Self> {
         // TODO: rewrite with `concat_idents!(parser_, $ty)` once stabilised
         macro_rules! parser {
@@ -117,60 +968,81 @@ impl Config {
             [bool] => {parse_bool};
             [UseSmallHeuristics] => {parse_use_small_heuristics};
             [HtmlFlavor] => {parse_html_flavor};
-        }
-
-        macro_rules! field_kind {
-            [usize] => {"an integer"};
-            [bool] => {"a boolean"};
-            [UseSmallHeuristics] => {"`use_small_heuristics` enum "};
-            [HtmlFlavor] => {"`html_flavor` enum"};
+            [ViewFlavor] => {parse_view_flavor};
+            [IndentStyle] => {parse_indent_style};
+            [WidthHeuristic] => {parse_width_heuristic};
+            [MacroNames] => {parse_macro_names};
+            [MacroAliases] => {parse_macro_aliases};
+            [DialectMacros] => {parse_dialect_macros};
+            [VersionReq] => {parse_version_req};
+            [Edition] => {parse_edition};
+            [NewlineStyle] => {parse_newline_style};
         }
 
         macro_rules! parse_field {
-            ($k:expr, $v:expr, $cfg:ident . { $($($name:ident).+ : $ty:ident),+ }) => {{
+            ($k:expr, $v:expr, $cfg:ident . { $($($name:ident).+ : $ty:ident = $_:literal),+ }) => {{
                 let v = $v;
                 match $k {
                     $(
                         stringify!($($name).+) => {
-                            let err_msg = concat!(
-                                "received a `",
+                            let err_msg = || format!(
+                                "received a `{}` value that's not {}: `{v}`",
                                 stringify!($($name).+),
-                                "` value that's not ",
-                                field_kind!($ty)
+                                field_kind!($ty),
                             );
-                            $cfg.$($name).+ = Some(parser![$ty](v).context(err_msg)?);
+                            $cfg.$($name).+ = Some(parser![$ty](v).with_context(err_msg)?);
                         }
                     )+
-                    k => if let Some(k) = k.strip_prefix("yew.") {
-                        $cfg.yew.unknown.insert(k.to_owned(), Unknown);
+                    // the other options are left for rustfmt to check
+                    k if k.starts_with("yew.") => {
+                        let known: Vec<&str> = [$(stringify!($($name).+)),+]
+                            .into_iter()
+                            .filter(|k| k.starts_with("yew."))
+                            .collect();
+                        bail!("unknown option `{k}`, expected one of: `{}`", known.join("`, `"));
                     }
+                    _ => (),
                 }
             }};
         }
 
-        let mut raw: RawConfig = basic_toml::from_str(src)?;
         for (key, value) in ext {
-            parse_field!(key.as_ref(), value.as_ref(), raw.{
-                tab_spaces: usize,
-                max_width: usize,
-                use_field_init_shorthand: bool,
-                use_small_heuristics: UseSmallHeuristics,
-                yew.html_width: usize,
-                yew.use_small_heuristics: UseSmallHeuristics,
-                yew.unwrap_literal_prop_values: bool,
-                yew.use_prop_init_shorthand: bool,
-                yew.self_close_elements: bool,
-                yew.html_flavor: HtmlFlavor
-            });
-        }
-
-        Ok(Self {
+            with_options!(parse_field!(key.as_ref(), value.as_ref(), raw.));
+        }
+
+        for (alias, target) in raw.yew.macro_aliases.iter().flatten() {
+            if target != "html" && target != "html_nested" {
+                bail!(r#"`{alias}` is aliased to `{target}`, expected `"html"` or `"html_nested"`"#);
+            }
+        }
+        if let Some(percent) = raw.yew.html_width_percent.filter(|p| !(1..=100).contains(p)) {
+            bail!("`yew.html_width_percent` is {percent}, expected a percentage from 1 to 100");
+        }
+
+        Ok(Self::from_raw(raw))
+    }
+
+    #[rustfmt::skip]
+    fn from_raw(raw: RawConfig) -> Self {
+        Self {
             tab_spaces: raw.tab_spaces
                 .unwrap_or(4),
+            hard_tabs: raw.hard_tabs
+                .unwrap_or(false),
+            edition: raw.edition
+                .unwrap_or_default(),
+            newline_style: raw.newline_style
+                .unwrap_or_default(),
+            skip_macro_invocations: raw.skip_macro_invocations
+                .unwrap_or_default(),
+            ignore: IgnoreList::new(raw.ignore.as_deref().unwrap_or_default()),
             yew: YewConfig {
                 html_width: raw.yew.html_width
+                    .or(raw.yew.html_width_percent.map(|p| raw.max_width.unwrap_or(100) * p / 100))
                     .or(raw.max_width)
                     .unwrap_or(100),
+                max_element_width: raw.yew.max_element_width,
+                attr_count_threshold: raw.yew.attr_count_threshold,
                 use_small_heuristics: raw.yew.use_small_heuristics
                     .or(raw.use_small_heuristics)
                     .unwrap_or(UseSmallHeuristics::Default),
@@ -181,27 +1053,53 @@ impl Config {
                     .unwrap_or(false),
                 self_close_elements: raw.yew.self_close_elements
                     .unwrap_or(true),
-                ext: raw.yew.html_flavor
-                    .map_or(false, |f| f == HtmlFlavor::Ext),
+                space_after_access_spec: raw.yew.space_after_access_spec
+                    .unwrap_or(false),
+                compact_closing_tags: raw.yew.compact_closing_tags
+                    .unwrap_or(false),
+                collapse_children: raw.yew.collapse_children
+                    .unwrap_or(false),
+                format_stylist: raw.yew.format_stylist
+                    .unwrap_or(false),
+                format_doc_comments: raw.yew.format_doc_comments
+                    .or(raw.format_code_in_doc_comments)
+                    .unwrap_or(false),
+                newline_at_eof: raw.yew.newline_at_eof
+                    .unwrap_or(true),
+                format_macro_rules: raw.yew.format_macro_rules
+                    .unwrap_or(false),
+                macro_aliases: raw.yew.macro_aliases
+                    .unwrap_or_default(),
+                dialect_macros: raw.yew.dialect_macros
+                    .unwrap_or_default(),
+                ext: raw.yew.html_flavor == Some(HtmlFlavor::Ext),
+                view_flavor: raw.yew.view_flavor
+                    .unwrap_or(ViewFlavor::Yew),
+                attr_indent_style: raw.yew.attr_indent_style
+                    .unwrap_or_default(),
+                attr_indent: raw.yew.attr_indent
+                    .or(raw.tab_spaces)
+                    .unwrap_or(4),
+                closing_bracket_same_line: raw.yew.closing_bracket_same_line
+                    .unwrap_or(false),
+                single_attribute_per_line: raw.yew.single_attribute_per_line
+                    .unwrap_or(true),
+                width_heuristic: raw.yew.width_heuristic
+                    .unwrap_or_default(),
+                required_version: raw.yew.required_version,
                 unknown: raw.yew.unknown,
             },
-        })
+        }
     }
 
-    pub fn fetch<'add>(
-        path: Option<&Path>,
-        additional: impl IntoIterator<Item = &'add (impl AsRef<str> + 'add, impl AsRef<str> + 'add)>,
-    ) -> Result<Self> {
-        macro_rules! return_parsed_if_file_exists {
+    /// Searches for the config file the same way `rustfmt` does, returns the path to the first
+    /// one found
+    pub fn find(path: Option<&Path>) -> Result<Option<PathBuf>> {
+        macro_rules! return_if_file_exists {
             ($path:expr) => {{
                 let path: &Path = $path;
-                match read_to_string(path) {
-                    Ok(src) => return Self::parse(&src, additional),
-                    Err(err) => {
-                        if err.kind() != io::ErrorKind::NotFound {
-                            return Err(anyhow!(err).context(format!("failed to read {path:?}")));
-                        }
-                    }
+                if path.try_exists().with_context(|| format!("failed to access {path:?}"))? {
+                    return Ok(Some(path.to_owned()));
                 }
             }};
         }
@@ -210,9 +1108,9 @@ impl Config {
             let mut path = path.canonicalize()?;
             loop {
                 path.push("rustfmt.toml");
-                return_parsed_if_file_exists!(&path);
+                return_if_file_exists!(&path);
                 path.set_file_name(".rustfmt.toml");
-                return_parsed_if_file_exists!(&path);
+                return_if_file_exists!(&path);
                 path.pop();
                 if !path.pop() {
                     break;
@@ -223,9 +1121,9 @@ impl Config {
         let mut aqui = current_dir()?;
         loop {
             aqui.push("rustfmt.toml");
-            return_parsed_if_file_exists!(&aqui);
+            return_if_file_exists!(&aqui);
             aqui.set_file_name(".rustfmt.toml");
-            return_parsed_if_file_exists!(&aqui);
+            return_if_file_exists!(&aqui);
             aqui.pop();
             if !aqui.pop() {
                 break;
@@ -234,17 +1132,142 @@ impl Config {
 
         let mut home = home_dir().context("failed to get the user's home directory")?;
         home.push("rustfmt.toml");
-        return_parsed_if_file_exists!(&home);
+        return_if_file_exists!(&home);
         home.set_file_name(".rustfmt.toml");
-        return_parsed_if_file_exists!(&home);
+        return_if_file_exists!(&home);
 
         let mut global = config_dir().context("failed to get the user's config directory")?;
         global.push("rustfmt");
         global.push("rustfmt.toml");
-        return_parsed_if_file_exists!(&global);
+        return_if_file_exists!(&global);
         global.set_file_name(".rustfmt.toml");
-        return_parsed_if_file_exists!(&global);
+        return_if_file_exists!(&global);
 
-        Self::parse("", additional)
+        Ok(None)
+    }
+
+    /// Searches for the config files in the directory `dir`, appends the paths to the ones found to
+    /// `dst`, a `yew-fmt.toml` coming before a `Cargo.toml` with `yew-fmt`'s options, which comes
+    /// before a `rustfmt.toml` or, if there's none, a `.rustfmt.toml`
+    fn find_in(dir: &Path, dst: &mut Vec<PathBuf>) -> Result {
+        let path = dir.join(YEW_FMT_TOML);
+        if path.try_exists().with_context(|| format!("failed to access {path:?}"))? {
+            dst.push(path);
+        }
+        // only taken if it has the options, since there's one in every crate
+        let path = dir.join(CARGO_TOML);
+        if path.try_exists().with_context(|| format!("failed to access {path:?}"))? {
+            let src = read_to_string(&path).with_context(|| format!("failed to read {path:?}"))?;
+            if parse_cargo_metadata(&src)
+                .with_context(|| format!("failed to parse {path:?}"))?
+                .is_some()
+            {
+                dst.push(path);
+            }
+        }
+        for name in ["rustfmt.toml", ".rustfmt.toml"] {
+            let path = dir.join(name);
+            if path.try_exists().with_context(|| format!("failed to access {path:?}"))? {
+                dst.push(path);
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Searches for the config files in the directory `path`, or in the current one, & in all of
+    /// its ancestors, returns the paths to the ones found, nearest first, in the order of
+    /// precedence within a directory. If none are found, falls back to the one found by
+    /// [`Config::find`], if any, i.e. the one in the user's home or config directory
+    pub fn find_all(path: Option<&Path>) -> Result<Vec<PathBuf>> {
+        let start = match path {
+            Some(path) => path.canonicalize()?,
+            None => current_dir()?,
+        };
+        let mut found = vec![];
+        for dir in start.ancestors() {
+            Self::find_in(dir, &mut found)?;
+        }
+        if found.is_empty() {
+            found.extend(Self::find(path)?);
+        }
+        Ok(found)
+    }
+
+    /// Finds and parses the config files, merging them so that the options set in the nested
+    /// directories override those set in their parents, see [`Config::find_all`] &
+    /// [`Config::parse`]. The `ignore` lists of all the files apply. A `yew-fmt.toml` holds the
+    /// options of the `yew` table of `rustfmt.toml` at its top level, a `Cargo.toml` holds them in
+    /// its metadata tables. With `profile`, the options of the `[profile.<name>]` tables of the
+    /// `rustfmt.toml`s & `yew-fmt.toml`s apply on top of the others, failing if there are none
+    pub fn fetch<'add>(
+        path: Option<&Path>,
+        profile: Option<&str>,
+        additional: impl IntoIterator<Item = &'add (impl AsRef<str> + 'add, impl AsRef<str> + 'add)>,
+    ) -> Result<Self> {
+        Self::read_merged(&Self::find_all(path)?, profile, additional)
+    }
+
+    /// Reads the config from `path` alone, bypassing the lookup: from the file itself, or, if
+    /// it's a directory, from the config files in it, merged the same way as by [`Config::fetch`].
+    /// Fails if there are no config files there
+    pub fn load<'add>(
+        path: &Path,
+        profile: Option<&str>,
+        additional: impl IntoIterator<Item = &'add (impl AsRef<str> + 'add, impl AsRef<str> + 'add)>,
+    ) -> Result<Self> {
+        let mut files = vec![];
+        if path.is_dir() {
+            Self::find_in(path, &mut files)?;
+        } else if path.try_exists().with_context(|| format!("failed to access {path:?}"))? {
+            files.push(path.to_owned());
+        }
+        if files.is_empty() {
+            bail!("no config file found at {path:?}");
+        }
+        Self::read_merged(&files, profile, additional)
+    }
+
+    /// Parses the config files in the order of precedence & merges them, the options of the
+    /// profile named `profile` in each file, if it defines one, overriding the others of the file
+    fn read_merged<'add>(
+        files: &[PathBuf],
+        profile: Option<&str>,
+        additional: impl IntoIterator<Item = &'add (impl AsRef<str> + 'add, impl AsRef<str> + 'add)>,
+    ) -> Result<Self> {
+        let mut raw = RawConfig::default();
+        let mut ignore = IgnoreList::default();
+        let mut profile_found = false;
+        for path in files {
+            let src = read_to_string(path).with_context(|| format!("failed to read {path:?}"))?;
+            let parse = || -> Result<RawConfig> {
+                Ok(if path.ends_with(YEW_FMT_TOML) {
+                    let RawYewFmtToml { profile, yew } = basic_toml::from_str(&src)?;
+                    RawConfig { yew, profile, ..RawConfig::default() }
+                } else if path.ends_with(CARGO_TOML) {
+                    let yew = parse_cargo_metadata(&src)?.unwrap_or_default();
+                    RawConfig { yew, ..RawConfig::default() }
+                } else {
+                    basic_toml::from_str(&src)?
+                })
+            };
+            let mut outer = parse().with_context(|| format!("failed to parse {path:?}"))?;
+            if let Some(selected) = profile.and_then(|name| outer.profile.as_mut()?.remove(name)) {
+                outer.yew = selected.or(outer.yew);
+                profile_found = true;
+            }
+            let mut outer_ignore = IgnoreList::new(outer.ignore.as_deref().unwrap_or_default());
+            for (root, _) in &mut outer_ignore.lists {
+                *root = path.parent().map(Path::to_owned);
+            }
+            ignore.lists.append(&mut outer_ignore.lists);
+            raw = raw.or(outer);
+        }
+        if let Some(name) = profile.filter(|_| !profile_found) {
+            bail!("profile `{name}` isn't defined in any of the config files");
+        }
+        let mut config = Self::from_raw_with_overrides(raw, additional)?;
+        config.ignore = ignore;
+        Ok(config)
     }
 }
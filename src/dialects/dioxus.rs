@@ -0,0 +1,418 @@
+//! `dioxus::rsx!`, in which elements & components are written as `name { attrs.. children.. }`,
+//! attributes are written as struct fields & text is quoted
+
+use crate::{
+    config::UseSmallHeuristics,
+    formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Spacing},
+    html::{block_children_spacing, AnyIdent},
+    utils::{OptionExt, Result},
+};
+use proc_macro2::{LineColumn, TokenStream};
+use quote::ToTokens;
+use syn::{
+    braced,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token::Brace,
+    Block, Expr, Ident, LitStr, Pat, Path, Token,
+};
+
+pub struct View {
+    pub nodes: Vec<(Node, Option<Token![,]>)>,
+}
+
+pub enum Node {
+    Element(Box<Element>),
+    If(Box<If>),
+    For(Box<For>),
+    Text(LitStr),
+    Block(Block),
+}
+
+pub struct Element {
+    pub name: TokenStream,
+    pub brace: Brace,
+    pub attrs: Vec<(Attr, Option<Token![,]>)>,
+    pub children: Vec<(Node, Option<Token![,]>)>,
+}
+
+pub enum Attr {
+    /// `name: value`, the name being either an identifier or a string literal
+    Named(TokenStream, Token![:], Expr),
+    /// `name`, short for `name: name`
+    Shorthand(AnyIdent),
+    /// `..attrs`
+    Spread(Token![..], Expr),
+}
+
+pub struct If {
+    pub if_token: Token![if],
+    pub condition: Expr,
+    pub brace: Brace,
+    pub then_branch: Vec<(Node, Option<Token![,]>)>,
+    pub else_branch: Option<Else>,
+}
+
+pub enum Else {
+    If(Token![else], Box<If>),
+    Tree(Token![else], Brace, Vec<(Node, Option<Token![,]>)>),
+}
+
+pub struct For {
+    pub for_token: Token![for],
+    pub pat: Pat,
+    pub in_token: Token![in],
+    pub iter: Expr,
+    pub brace: Brace,
+    pub body: Vec<(Node, Option<Token![,]>)>,
+}
+
+impl Parse for View {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self { nodes: Node::parse_children(input)? })
+    }
+}
+
+impl Node {
+    /// Parses nodes until the end of the input, each optionally followed by a comma
+    fn parse_children(input: ParseStream) -> syn::Result<Vec<(Self, Option<Token![,]>)>> {
+        let mut children = vec![];
+        while !input.is_empty() {
+            if Attr::parseable(input) {
+                return Err(input.error("attributes must precede the children of an element"));
+            }
+            children.push((input.parse()?, input.parse()?));
+        }
+        Ok(children)
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Token![if]) {
+            Self::If(input.parse()?)
+        } else if input.peek(Token![for]) {
+            Self::For(input.parse()?)
+        } else if input.peek(Brace) {
+            Self::Block(input.parse()?)
+        } else if input.peek(LitStr) {
+            Self::Text(input.parse()?)
+        } else if input.peek(Ident::peek_any) || input.peek(Token![::]) {
+            Self::Element(input.parse()?)
+        } else {
+            return Err(input.error("expected an element, a component, a block or quoted text"));
+        })
+    }
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // dashed names are those of web components
+        let name = if input.peek2(Token![-]) {
+            Punctuated::<AnyIdent, Token![-]>::parse_separated_nonempty(input)?.into_token_stream()
+        } else {
+            input.parse::<Path>()?.into_token_stream()
+        };
+        let inner;
+        let brace = braced!(inner in input);
+        let mut attrs = vec![];
+        while Attr::parseable(&inner) {
+            attrs.push((inner.parse()?, inner.parse()?));
+        }
+        Ok(Self { name, brace, attrs, children: Node::parse_children(&inner)? })
+    }
+}
+
+impl Attr {
+    fn parseable(input: ParseStream) -> bool {
+        if input.peek(Token![..]) {
+            return true;
+        }
+        let fork = input.fork();
+        let is_named = |fork: ParseStream| fork.peek(Token![:]) && !fork.peek(Token![::]);
+        if fork.parse::<LitStr>().is_ok() {
+            is_named(&fork)
+        } else if fork.parse::<AnyIdent>().is_ok() {
+            is_named(&fork) || fork.is_empty() || fork.peek(Token![,])
+        } else {
+            false
+        }
+    }
+}
+
+impl Parse for Attr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![..]) {
+            return Ok(Self::Spread(input.parse()?, input.parse()?));
+        }
+        if input.peek(LitStr) {
+            let name = input.parse::<LitStr>()?.into_token_stream();
+            return Ok(Self::Named(name, input.parse()?, input.parse()?));
+        }
+        let name = input.parse::<AnyIdent>()?;
+        Ok(if input.peek(Token![:]) {
+            Self::Named(name.into_token_stream(), input.parse()?, input.parse()?)
+        } else {
+            Self::Shorthand(name)
+        })
+    }
+}
+
+impl Parse for If {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let then_branch;
+        Ok(Self {
+            if_token: input.parse()?,
+            condition: Expr::parse_without_eager_brace(input)?,
+            brace: braced!(then_branch in input),
+            then_branch: Node::parse_children(&then_branch)?,
+            else_branch: if input.peek(Token![else]) { Some(input.parse()?) } else { None },
+        })
+    }
+}
+
+impl Parse for Else {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let else_token = input.parse()?;
+        Ok(if input.peek(Token![if]) {
+            Self::If(else_token, input.parse()?)
+        } else {
+            let inner;
+            Self::Tree(else_token, braced!(inner in input), Node::parse_children(&inner)?)
+        })
+    }
+}
+
+impl Parse for For {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let body;
+        Ok(Self {
+            for_token: input.parse()?,
+            pat: Pat::parse_multi_with_leading_vert(input)?,
+            in_token: input.parse()?,
+            iter: Expr::parse_without_eager_brace(input)?,
+            brace: braced!(body in input),
+            body: Node::parse_children(&body)?,
+        })
+    }
+}
+
+/// Unlike Yew's elements, in which only the children can be broken up, here the attributes are put
+/// in the same block as the children, so only the children are taken into account by the heuristics
+fn element_spacing(ctx: &FormatCtx, element: &Element) -> Option<Spacing> {
+    let spacing = Spacing { between: true, ..Spacing::AROUND };
+    match ctx.config.yew.use_small_heuristics {
+        UseSmallHeuristics::Off => element.children.is_empty().then_some(spacing),
+        UseSmallHeuristics::Default => element
+            .children
+            .iter()
+            .all(|(child, _)| matches!(child, Node::Text(_) | Node::Block(_)))
+            .then_some(spacing),
+        UseSmallHeuristics::Max => Some(spacing),
+    }
+}
+
+fn format_items<'src, T: Format<'src> + Located>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    items: &[(T, Option<Token![,]>)],
+) -> Result {
+    for (item, comma) in items {
+        item.format(block, ctx)?;
+        if let Some(comma) = comma {
+            block.add_source(ctx, comma)?;
+            block.add_sep(ctx, comma.end())?;
+        } else {
+            block.add_sep(ctx, item.end())?;
+        }
+    }
+    Ok(())
+}
+
+impl<'src> Format<'src> for View {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        for (i, (node, comma)) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                block.add_sep(ctx, node.start())?;
+            }
+            node.format(block, ctx)?;
+            comma.try_map_or((), |comma| block.add_source(ctx, comma))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'src> Format<'src> for Node {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.start())?;
+        if block.take_skip_request() || ctx.is_formatting_off(self.loc()) {
+            return block.add_source(ctx, self.loc());
+        }
+        match self {
+            Self::Element(element) => element.format(block, ctx),
+            Self::If(r#if) => r#if.format(block, ctx),
+            Self::For(r#for) => r#for.format(block, ctx),
+            Self::Text(lit) => block.add_source(ctx, lit),
+            Self::Block(b) => b.format(block, ctx),
+        }
+    }
+}
+
+impl<'src> Format<'src> for Element {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source_iter(ctx, self.name.clone())?;
+        if self.attrs.is_empty() && self.children.is_empty() {
+            block.add_source_with_space(ctx, self.brace.span.open())?;
+            return block.add_source(ctx, self.brace.span.close());
+        }
+        block.add_delimited_block_with_space(
+            ctx,
+            self.brace.span.open(),
+            self.brace.span.close(),
+            element_spacing(ctx, self),
+            ChainingRule::Off,
+            |block, ctx| {
+                format_items(block, ctx, &self.attrs)?;
+                format_items(block, ctx, &self.children)
+            },
+        )
+    }
+}
+
+impl<'src> Format<'src> for Attr {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Named(name, colon, value) => {
+                block.add_source_iter(ctx, name.clone())?;
+                block.add_source(ctx, colon)?;
+                value.format_with_space(block, ctx)
+            }
+            Self::Shorthand(name) => block.add_source(ctx, name),
+            Self::Spread(dot2, value) => {
+                block.add_source(ctx, dot2)?;
+                value.format(block, ctx)
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for If {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.if_token)?;
+        block.add_source_with_space(ctx, &self.condition)?;
+        block.add_delimited_block_with_space(
+            ctx,
+            self.brace.span.open(),
+            self.brace.span.close(),
+            block_children_spacing(ctx),
+            self.else_branch.choose(ChainingRule::On, ChainingRule::End),
+            |block, ctx| format_items(block, ctx, &self.then_branch),
+        )?;
+        self.else_branch.as_ref().try_map_or((), |b| b.format_with_space(block, ctx))
+    }
+}
+
+impl<'src> Format<'src> for Else {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::If(r#else, r#if) => {
+                block.add_source(ctx, r#else)?;
+                r#if.format_with_space(block, ctx)
+            }
+            Self::Tree(r#else, brace, children) => {
+                block.add_source(ctx, r#else)?;
+                block.add_delimited_block_with_space(
+                    ctx,
+                    brace.span.open(),
+                    brace.span.close(),
+                    block_children_spacing(ctx),
+                    ChainingRule::End,
+                    |block, ctx| format_items(block, ctx, children),
+                )
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for For {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.for_token)?;
+        block.add_source_with_space(ctx, &self.pat)?;
+        block.add_source_with_space(ctx, self.in_token)?;
+        block.add_source_with_space(ctx, &self.iter)?;
+        block.add_delimited_block_with_space(
+            ctx,
+            self.brace.span.open(),
+            self.brace.span.close(),
+            block_children_spacing(ctx),
+            ChainingRule::Off,
+            |block, ctx| format_items(block, ctx, &self.body),
+        )
+    }
+}
+
+impl Located for Node {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => x.name.start(),
+            Self::If(x) => x.if_token.start(),
+            Self::For(x) => x.for_token.start(),
+            Self::Text(x) => x.start(),
+            Self::Block(x) => x.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => x.brace.span.close().end(),
+            Self::If(x) => x.end(),
+            Self::For(x) => x.brace.span.close().end(),
+            Self::Text(x) => x.end(),
+            Self::Block(x) => x.end(),
+        }
+    }
+}
+
+impl Located for If {
+    fn start(&self) -> LineColumn {
+        self.if_token.start()
+    }
+
+    fn end(&self) -> LineColumn {
+        self.else_branch.as_ref().map_or_else(|| self.brace.span.close().end(), Else::end)
+    }
+}
+
+impl Located for Else {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::If(r#else, _) | Self::Tree(r#else, ..) => r#else.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::If(_, r#if) => r#if.end(),
+            Self::Tree(_, brace, _) => brace.span.close().end(),
+        }
+    }
+}
+
+impl Located for Attr {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Named(name, ..) => name.start(),
+            Self::Shorthand(name) => name.start(),
+            Self::Spread(dot2, _) => dot2.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Named(.., value) => value.end(),
+            Self::Shorthand(name) => name.end(),
+            Self::Spread(_, value) => value.end(),
+        }
+    }
+}
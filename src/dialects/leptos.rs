@@ -0,0 +1,404 @@
+//! `leptos::view!`, in which the text is quoted, the values of attributes can be arbitrary
+//! expressions, e.g. closures, and the names of attributes can have prefixes like `on:`
+
+use super::{parse_attr_name, parse_part};
+use crate::{
+    config::UseSmallHeuristics,
+    formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Spacing},
+    html::{props_layout, props_spacing, AnyIdent},
+    utils::{OptionExt, Result},
+};
+use proc_macro2::{LineColumn, Spacing as PunctSpacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token::Brace,
+    Block, Expr, Ident, Lit, Path, Token,
+};
+
+pub struct View {
+    /// the `cx,` that preceded the nodes before Leptos 0.5
+    pub scope: Option<(Ident, Token![,])>,
+    pub nodes: Vec<Node>,
+}
+
+pub enum Node {
+    Element(Box<Element>),
+    Fragment(Box<Fragment>),
+    Text(Lit),
+    Block(Block),
+}
+
+pub struct Element {
+    pub lt_token: Token![<],
+    pub name: TokenStream,
+    pub attrs: Vec<Attr>,
+    pub children: Vec<Node>,
+    pub closing_tag: Option<(Token![>], Token![<], TokenStream)>,
+    pub div_token: Token![/],
+    pub closing_gt_token: Token![>],
+}
+
+pub struct Fragment {
+    pub lt_token: Token![<],
+    pub gt_token: Token![>],
+    pub children: Vec<Node>,
+    pub closing_lt_token: Token![<],
+    pub div_token: Token![/],
+    pub closing_gt_token: Token![>],
+}
+
+pub enum Attr {
+    /// `name` or `name=value`, e.g. `on:click=move |_| set_count(0)`
+    Keyed(TokenStream, Option<(Token![=], AttrValue)>),
+    /// `{..}` or `{..attrs}`, spreading attributes onto a component
+    Spread(TokenTree),
+}
+
+pub enum AttrValue {
+    Block(Block),
+    Expr(Box<Expr>),
+}
+
+impl Parse for View {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let scope = if input.peek(Ident) && input.peek2(Token![,]) {
+            Some((input.parse()?, input.parse()?))
+        } else {
+            None
+        };
+        let mut nodes = vec![];
+        while !input.is_empty() {
+            nodes.push(input.parse()?);
+        }
+        Ok(Self { scope, nodes })
+    }
+}
+
+impl Node {
+    fn parse_children(input: ParseStream) -> syn::Result<Vec<Self>> {
+        let mut children = vec![];
+        while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+            if input.is_empty() {
+                return Err(input.error("expected a closing tag"));
+            }
+            children.push(input.parse()?);
+        }
+        Ok(children)
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Token![<]) {
+            if input.peek2(Token![>]) {
+                Self::Fragment(input.parse()?)
+            } else {
+                Self::Element(input.parse()?)
+            }
+        } else if input.peek(Brace) {
+            Self::Block(input.parse()?)
+        } else if input.peek(Lit) {
+            Self::Text(input.parse()?)
+        } else {
+            return Err(input.error("expected an element, a block or quoted text"));
+        })
+    }
+}
+
+/// Parses the name of an element, which is either a path or a dashed name of a custom element
+fn parse_name(input: ParseStream) -> syn::Result<TokenStream> {
+    Ok(if input.peek2(Token![::]) {
+        Path::parse_mod_style(input)?.into_token_stream()
+    } else {
+        Punctuated::<AnyIdent, Token![-]>::parse_separated_nonempty(input)?.into_token_stream()
+    })
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lt_token = input.parse()?;
+        let name = parse_name(input)?;
+        let mut attrs = vec![];
+        while !input.peek(Token![>]) && !input.peek(Token![/]) {
+            attrs.push(input.parse()?);
+        }
+
+        let (children, closing_tag, div_token) = if input.peek(Token![>]) {
+            let gt_token = input.parse()?;
+            let children = Node::parse_children(input)?;
+            let closing_lt_token = input.parse()?;
+            let div_token = input.parse()?;
+            let closing_name = parse_name(input)?;
+            if closing_name.to_string() != name.to_string() {
+                return Err(syn::Error::new_spanned(
+                    closing_name,
+                    format!("expected the closing tag of `{name}`"),
+                ));
+            }
+            (children, Some((gt_token, closing_lt_token, closing_name)), div_token)
+        } else {
+            (vec![], None, input.parse()?)
+        };
+
+        Ok(Self {
+            lt_token,
+            name,
+            attrs,
+            children,
+            closing_tag,
+            div_token,
+            closing_gt_token: input.parse()?,
+        })
+    }
+}
+
+impl Parse for Fragment {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            lt_token: input.parse()?,
+            gt_token: input.parse()?,
+            children: Node::parse_children(input)?,
+            closing_lt_token: input.parse()?,
+            div_token: input.parse()?,
+            closing_gt_token: input.parse()?,
+        })
+    }
+}
+
+impl Parse for Attr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Brace) {
+            return Ok(Self::Spread(input.parse()?));
+        }
+        let name = parse_attr_name(input)?;
+        let value = if input.peek(Token![=]) {
+            let eq_token = input.parse()?;
+            let value = if input.peek(Brace) {
+                AttrValue::Block(input.parse()?)
+            } else {
+                AttrValue::Expr(Box::new(parse_unbraced_value(input)?))
+            };
+            Some((eq_token, value))
+        } else {
+            None
+        };
+        Ok(Self::Keyed(name, value))
+    }
+}
+
+/// Parses a value that's not wrapped in braces, which ends either where the expression does or at
+/// the end of the tag, since the closing `>` would otherwise be parsed as a comparison
+fn parse_unbraced_value(input: ParseStream) -> syn::Result<Expr> {
+    let fork = input.fork();
+    let mut tokens = vec![];
+    // whether the last token is joined with the next one, like `-` in `->`
+    let mut joint = false;
+    while !fork.is_empty() {
+        if !joint && (fork.peek(Token![>]) || fork.peek(Token![/]) && fork.peek2(Token![>])) {
+            break;
+        }
+        let token = fork.parse::<TokenTree>()?;
+        joint = matches!(&token, TokenTree::Punct(p) if p.spacing() == PunctSpacing::Joint);
+        tokens.push(token);
+    }
+    if tokens.is_empty() {
+        return Err(input.error("expected a value"));
+    }
+
+    let n_tokens = tokens.len();
+    let end = tokens[n_tokens - 1].span();
+    let (expr, n_left) = parse_part(
+        |input: ParseStream| {
+            let expr = input.parse::<Expr>()?;
+            Ok((expr, input.parse::<TokenStream>()?.into_iter().count()))
+        },
+        tokens.into_iter().collect(),
+        end,
+    )?;
+    for _ in n_left..n_tokens {
+        input.parse::<TokenTree>()?;
+    }
+    Ok(expr)
+}
+
+fn children_spacing(ctx: &FormatCtx, children: &[Node]) -> Option<Spacing> {
+    let spacing = Spacing { between: true, ..Spacing::default() };
+    match ctx.config.yew.use_small_heuristics {
+        UseSmallHeuristics::Off => None,
+        UseSmallHeuristics::Default => children
+            .iter()
+            .all(|child| matches!(child, Node::Text(_) | Node::Block(_)))
+            .then_some(spacing),
+        UseSmallHeuristics::Max => Some(spacing),
+    }
+}
+
+fn format_children<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    children: &[Node],
+    closing_width: usize,
+) -> Result {
+    for child in children {
+        child.format(block, ctx)?;
+        block.add_sep(ctx, child.end())?;
+    }
+    if ctx.config.yew.compact_closing_tags {
+        block.allow_compact_end(closing_width);
+    }
+    Ok(())
+}
+
+impl<'src> Format<'src> for View {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        if let Some((scope, comma)) = &self.scope {
+            block.add_source(ctx, scope)?;
+            block.add_source(ctx, comma)?;
+            block.add_sep(ctx, comma.end())?;
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                block.add_sep(ctx, node.start())?;
+            }
+            node.format(block, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'src> Format<'src> for Node {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.start())?;
+        if block.take_skip_request() || ctx.is_formatting_off(self.loc()) {
+            return block.add_source(ctx, self.loc());
+        }
+        match self {
+            Self::Element(element) => element.format(block, ctx),
+            Self::Fragment(fragment) => fragment.format(block, ctx),
+            Self::Text(lit) => block.add_source(ctx, lit),
+            Self::Block(b) => b.format(block, ctx),
+        }
+    }
+}
+
+impl<'src> Format<'src> for Element {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.lt_token)?;
+        block.add_source_iter(ctx, self.name.clone())?;
+        let closing_tag = self
+            .closing_tag
+            .as_ref()
+            .filter(|_| !self.children.is_empty() || !ctx.config.yew.self_close_elements);
+
+        block.add_block(
+            Some(props_spacing(closing_tag.is_none())),
+            closing_tag.choose(ChainingRule::On, ChainingRule::Off),
+            |block| {
+                for attr in &self.attrs {
+                    attr.format(block, ctx)?;
+                    block.add_sep(ctx, attr.end())?;
+                }
+                let end =
+                    self.closing_tag.as_ref().map_or(self.div_token.start(), |(gt, ..)| gt.start());
+                props_layout(block, ctx, self.attrs.len(), end)
+            },
+        )?;
+
+        if let Some((gt, closing_lt, closing_name)) = closing_tag {
+            let name: String = closing_name.to_string().split_whitespace().collect();
+            block.add_delimited_block(
+                ctx,
+                gt,
+                closing_lt,
+                children_spacing(ctx, &self.children),
+                ChainingRule::End,
+                |block, ctx| format_children(block, ctx, &self.children, "</>".len() + name.len()),
+            )?;
+            block.add_source(ctx, self.div_token)?;
+            block.add_source_iter(ctx, closing_name.clone())?;
+        } else {
+            block.add_source(ctx, self.div_token)?;
+        }
+        block.add_source(ctx, self.closing_gt_token)
+    }
+}
+
+impl<'src> Format<'src> for Fragment {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.lt_token)?;
+        block.add_delimited_block(
+            ctx,
+            self.gt_token,
+            self.closing_lt_token,
+            children_spacing(ctx, &self.children),
+            ChainingRule::Off,
+            |block, ctx| format_children(block, ctx, &self.children, "</>".len()),
+        )?;
+        block.add_source(ctx, self.div_token)?;
+        block.add_source(ctx, self.closing_gt_token)
+    }
+}
+
+impl<'src> Format<'src> for Attr {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Keyed(name, value) => {
+                block.add_source_iter(ctx, name.clone())?;
+                match value {
+                    Some((eq, AttrValue::Block(value))) => {
+                        block.add_source(ctx, eq)?;
+                        value.format(block, ctx)
+                    }
+                    Some((eq, AttrValue::Expr(value))) => {
+                        block.add_source(ctx, eq)?;
+                        value.format(block, ctx)
+                    }
+                    None => Ok(()),
+                }
+            }
+            Self::Spread(spread) => block.add_source(ctx, spread),
+        }
+    }
+}
+
+impl Located for Node {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => x.lt_token.start(),
+            Self::Fragment(x) => x.lt_token.start(),
+            Self::Text(x) => x.start(),
+            Self::Block(x) => x.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => x.closing_gt_token.end(),
+            Self::Fragment(x) => x.closing_gt_token.end(),
+            Self::Text(x) => x.end(),
+            Self::Block(x) => x.end(),
+        }
+    }
+}
+
+impl Located for Attr {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Keyed(name, _) => name.start(),
+            Self::Spread(spread) => spread.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Keyed(name, None) => {
+                name.clone().into_iter().last().map_or_else(|| name.end(), |last| last.end())
+            }
+            Self::Keyed(_, Some((_, AttrValue::Block(value)))) => value.end(),
+            Self::Keyed(_, Some((_, AttrValue::Expr(value)))) => value.end(),
+            Self::Spread(spread) => spread.end(),
+        }
+    }
+}
@@ -0,0 +1,813 @@
+//! `maud::html!`, in which elements are written as `name.class#id attr="value" { children.. }` or as
+//! `name;` if they're void, text is quoted, Rust values are spliced in with `(expr)` & control flow
+//! is prefixed with `@`
+
+use crate::{
+    config::UseSmallHeuristics,
+    formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Spacing},
+    html::{block_children_spacing, AnyIdent},
+    utils::{OptionExt, Result},
+};
+use proc_macro2::{LineColumn, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    token::{Brace, Bracket, Paren},
+    Expr, Ident, Lit, LitInt, LitStr, Local, Pat, Stmt, Token,
+};
+
+pub struct View {
+    pub nodes: Vec<Node>,
+}
+
+pub enum Node {
+    Element(Box<Element>),
+    Text(LitStr),
+    /// `(expr)`
+    Splice(Paren, Box<Expr>),
+    /// `{ nodes.. }`, markup grouped without an enclosing element
+    Group(Brace, Vec<Node>),
+    If(Token![@], Box<If>),
+    For(Token![@], Box<For>),
+    While(Token![@], Box<While>),
+    Match(Token![@], Box<Match>),
+    /// `@let pat = expr;`
+    Let(Token![@], Box<Local>),
+}
+
+pub struct Element {
+    /// `None` if the element is a `div` written only as its classes or its ID
+    pub name: Option<TokenStream>,
+    pub attrs: Vec<Attr>,
+    pub body: ElementBody,
+}
+
+pub enum ElementBody {
+    /// `;`, ending an element that has no children
+    Void(Token![;]),
+    Children(Brace, Vec<Node>),
+}
+
+pub enum Attr {
+    /// `.name`, optionally followed by `[condition]`
+    Class(Token![.], Name, Option<Toggle>),
+    /// `#name`
+    Id(Token![#], Name),
+    /// `name=value`, `name` or `name[condition]`
+    Named(TokenStream, Option<(Token![=], AttrValue)>, Option<Toggle>),
+}
+
+/// The name of a class or of an ID
+pub enum Name {
+    Ident(TokenStream),
+    Lit(LitStr),
+    Splice(Paren, Box<Expr>),
+}
+
+pub enum AttrValue {
+    Lit(Lit),
+    Splice(Paren, Box<Expr>),
+    /// `{ nodes.. }`, markup rendered as the value
+    Group(Brace, Vec<Node>),
+    /// `[option]`, the attribute being left out if the option is `None`
+    Optional(Bracket, Box<Expr>),
+}
+
+/// `[condition]`, the attribute or class being left out if the condition is false
+pub struct Toggle(Bracket, Box<Expr>);
+
+pub struct If {
+    pub if_token: Token![if],
+    pub condition: Expr,
+    pub brace: Brace,
+    pub then_branch: Vec<Node>,
+    pub else_branch: Option<Else>,
+}
+
+pub enum Else {
+    If(Token![@], Token![else], Box<If>),
+    Tree(Token![@], Token![else], Brace, Vec<Node>),
+}
+
+pub struct For {
+    pub for_token: Token![for],
+    pub pat: Pat,
+    pub in_token: Token![in],
+    pub iter: Expr,
+    pub brace: Brace,
+    pub body: Vec<Node>,
+}
+
+pub struct While {
+    pub while_token: Token![while],
+    pub condition: Expr,
+    pub brace: Brace,
+    pub body: Vec<Node>,
+}
+
+pub struct Match {
+    pub match_token: Token![match],
+    pub expr: Expr,
+    pub brace: Brace,
+    pub arms: Vec<Arm>,
+}
+
+pub struct Arm {
+    pub pat: Pat,
+    pub guard: Option<(Token![if], Expr)>,
+    pub fat_arrow: Token![=>],
+    pub body: Node,
+    pub comma: Option<Token![,]>,
+}
+
+impl Parse for View {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self { nodes: Node::parse_children(input)? })
+    }
+}
+
+impl Node {
+    fn parse_children(input: ParseStream) -> syn::Result<Vec<Self>> {
+        let mut children = vec![];
+        while !input.is_empty() {
+            children.push(input.parse()?);
+        }
+        Ok(children)
+    }
+
+    fn parse_braced(input: ParseStream) -> syn::Result<(Brace, Vec<Self>)> {
+        let inner;
+        Ok((braced!(inner in input), Self::parse_children(&inner)?))
+    }
+
+    fn parse_control_flow(input: ParseStream) -> syn::Result<Self> {
+        let at = input.parse()?;
+        Ok(if input.peek(Token![if]) {
+            Self::If(at, input.parse()?)
+        } else if input.peek(Token![for]) {
+            Self::For(at, input.parse()?)
+        } else if input.peek(Token![while]) {
+            Self::While(at, input.parse()?)
+        } else if input.peek(Token![match]) {
+            Self::Match(at, input.parse()?)
+        } else if input.peek(Token![let]) {
+            match input.parse()? {
+                Stmt::Local(local) => Self::Let(at, Box::new(local)),
+                _ => return Err(input.error("expected `@let pattern = value;`")),
+            }
+        } else {
+            return Err(input.error("expected `@if`, `@for`, `@while`, `@match` or `@let`"));
+        })
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Token![@]) {
+            Self::parse_control_flow(input)?
+        } else if input.peek(LitStr) {
+            Self::Text(input.parse()?)
+        } else if input.peek(Paren) {
+            let inner;
+            Self::Splice(parenthesized!(inner in input), inner.parse()?)
+        } else if input.peek(Brace) {
+            let (brace, nodes) = Self::parse_braced(input)?;
+            Self::Group(brace, nodes)
+        } else if input.peek(Ident::peek_any) || input.peek(Token![.]) || input.peek(Token![#]) {
+            Self::Element(input.parse()?)
+        } else {
+            return Err(input.error("expected an element, quoted text, a splice or `@`"));
+        })
+    }
+}
+
+/// Parses a name made of identifiers & integers joined by `-` or `:`, e.g. `data-id` or `col-2`
+fn parse_name(input: ParseStream) -> syn::Result<TokenStream> {
+    let mut name = AnyIdent::parse(input)?.into_token_stream();
+    while input.peek(Token![-]) || input.peek(Token![:]) && !input.peek(Token![::]) {
+        name.extend([input.parse::<TokenTree>()?]);
+        if input.peek(LitInt) {
+            name.extend(input.parse::<LitInt>()?.into_token_stream());
+        } else {
+            name.extend(AnyIdent::parse(input)?.into_token_stream());
+        }
+    }
+    Ok(name)
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = if input.peek(Ident::peek_any) { Some(parse_name(input)?) } else { None };
+        let mut attrs = vec![];
+        while input.peek(Ident::peek_any) || input.peek(Token![.]) || input.peek(Token![#]) {
+            attrs.push(input.parse()?);
+        }
+        let body = if input.peek(Token![;]) {
+            ElementBody::Void(input.parse()?)
+        } else if input.peek(Brace) {
+            let (brace, children) = Node::parse_braced(input)?;
+            ElementBody::Children(brace, children)
+        } else {
+            return Err(input.error("expected `;` or the children of the element"));
+        };
+        Ok(Self { name, attrs, body })
+    }
+}
+
+impl Parse for Attr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Token![.]) {
+            Self::Class(input.parse()?, input.parse()?, Toggle::parse_opt(input)?)
+        } else if input.peek(Token![#]) {
+            Self::Id(input.parse()?, input.parse()?)
+        } else {
+            let name = parse_name(input)?;
+            if input.peek(Token![=]) {
+                Self::Named(name, Some((input.parse()?, input.parse()?)), None)
+            } else {
+                Self::Named(name, None, Toggle::parse_opt(input)?)
+            }
+        })
+    }
+}
+
+impl Parse for Name {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(LitStr) {
+            Self::Lit(input.parse()?)
+        } else if input.peek(Paren) {
+            let inner;
+            Self::Splice(parenthesized!(inner in input), inner.parse()?)
+        } else {
+            Self::Ident(parse_name(input)?)
+        })
+    }
+}
+
+impl Parse for AttrValue {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Paren) {
+            let inner;
+            Self::Splice(parenthesized!(inner in input), inner.parse()?)
+        } else if input.peek(Brace) {
+            let (brace, nodes) = Node::parse_braced(input)?;
+            Self::Group(brace, nodes)
+        } else if input.peek(Bracket) {
+            let inner;
+            Self::Optional(bracketed!(inner in input), inner.parse()?)
+        } else if input.peek(Lit) {
+            Self::Lit(input.parse()?)
+        } else {
+            return Err(input.error("expected a literal, a splice, a block or `[option]`"));
+        })
+    }
+}
+
+impl Toggle {
+    fn parse_opt(input: ParseStream) -> syn::Result<Option<Self>> {
+        if !input.peek(Bracket) {
+            return Ok(None);
+        }
+        let inner;
+        Ok(Some(Self(bracketed!(inner in input), inner.parse()?)))
+    }
+}
+
+impl Parse for If {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let if_token = input.parse()?;
+        let condition = Expr::parse_without_eager_brace(input)?;
+        let (brace, then_branch) = Node::parse_braced(input)?;
+        let else_branch = if input.peek(Token![@]) && input.peek2(Token![else]) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { if_token, condition, brace, then_branch, else_branch })
+    }
+}
+
+impl Parse for Else {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let at = input.parse()?;
+        let else_token = input.parse()?;
+        Ok(if input.peek(Token![if]) {
+            Self::If(at, else_token, input.parse()?)
+        } else {
+            let (brace, children) = Node::parse_braced(input)?;
+            Self::Tree(at, else_token, brace, children)
+        })
+    }
+}
+
+impl Parse for For {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let for_token = input.parse()?;
+        let pat = Pat::parse_multi_with_leading_vert(input)?;
+        let in_token = input.parse()?;
+        let iter = Expr::parse_without_eager_brace(input)?;
+        let (brace, body) = Node::parse_braced(input)?;
+        Ok(Self { for_token, pat, in_token, iter, brace, body })
+    }
+}
+
+impl Parse for While {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let while_token = input.parse()?;
+        let condition = Expr::parse_without_eager_brace(input)?;
+        let (brace, body) = Node::parse_braced(input)?;
+        Ok(Self { while_token, condition, brace, body })
+    }
+}
+
+impl Parse for Match {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let match_token = input.parse()?;
+        let expr = Expr::parse_without_eager_brace(input)?;
+        let inner;
+        let brace = braced!(inner in input);
+        let mut arms = vec![];
+        while !inner.is_empty() {
+            arms.push(inner.parse()?);
+        }
+        Ok(Self { match_token, expr, brace, arms })
+    }
+}
+
+impl Parse for Arm {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            pat: Pat::parse_multi_with_leading_vert(input)?,
+            guard: if input.peek(Token![if]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            fat_arrow: input.parse()?,
+            body: input.parse()?,
+            comma: input.parse()?,
+        })
+    }
+}
+
+fn children_spacing(ctx: &FormatCtx, children: &[Node]) -> Option<Spacing> {
+    let spacing = Spacing { between: true, ..Spacing::AROUND };
+    match ctx.config.yew.use_small_heuristics {
+        UseSmallHeuristics::Off => None,
+        UseSmallHeuristics::Default => children
+            .iter()
+            .all(|child| matches!(child, Node::Text(_) | Node::Splice(..)))
+            .then_some(spacing),
+        UseSmallHeuristics::Max => Some(spacing),
+    }
+}
+
+fn format_nodes<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    nodes: &[Node],
+) -> Result {
+    for node in nodes {
+        node.format(block, ctx)?;
+        block.add_sep(ctx, node.end())?;
+    }
+    Ok(())
+}
+
+/// Formats `{ nodes.. }`, printing an empty group as `{}`
+fn format_braced<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    brace: &Brace,
+    nodes: &[Node],
+    spacing: Option<Spacing>,
+    chaining_rule: ChainingRule,
+) -> Result {
+    if nodes.is_empty() {
+        block.add_source_with_space(ctx, brace.span.open())?;
+        return block.add_source(ctx, brace.span.close());
+    }
+    block.add_delimited_block_with_space(
+        ctx,
+        brace.span.open(),
+        brace.span.close(),
+        spacing,
+        chaining_rule,
+        |block, ctx| format_nodes(block, ctx, nodes),
+    )
+}
+
+fn format_splice<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    paren: &Paren,
+    expr: &Expr,
+) -> Result {
+    block.add_source(ctx, paren.span.open())?;
+    expr.format(block, ctx)?;
+    block.add_source(ctx, paren.span.close())
+}
+
+impl<'src> Format<'src> for View {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                block.add_sep(ctx, node.start())?;
+            }
+            node.format(block, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'src> Format<'src> for Node {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.start())?;
+        if block.take_skip_request() || ctx.is_formatting_off(self.loc()) {
+            return block.add_source(ctx, self.loc());
+        }
+        match self {
+            Self::Element(element) => element.format(block, ctx),
+            Self::Text(lit) => block.add_source(ctx, lit),
+            Self::Splice(paren, expr) => format_splice(block, ctx, paren, expr),
+            Self::Group(brace, nodes) => block.add_delimited_block(
+                ctx,
+                brace.span.open(),
+                brace.span.close(),
+                children_spacing(ctx, nodes),
+                ChainingRule::Off,
+                |block, ctx| format_nodes(block, ctx, nodes),
+            ),
+            Self::If(at, r#if) => {
+                block.add_source(ctx, at)?;
+                r#if.format(block, ctx)
+            }
+            Self::For(at, r#for) => {
+                block.add_source(ctx, at)?;
+                r#for.format(block, ctx)
+            }
+            Self::While(at, r#while) => {
+                block.add_source(ctx, at)?;
+                r#while.format(block, ctx)
+            }
+            Self::Match(at, r#match) => {
+                block.add_source(ctx, at)?;
+                r#match.format(block, ctx)
+            }
+            Self::Let(at, local) => {
+                block.add_source(ctx, at)?;
+                block.add_source(ctx, &**local)
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for Element {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        // the classes & the ID written right after the name are attached to it, as in CSS selectors
+        let mut in_selector = true;
+        if let Some(name) = &self.name {
+            block.add_source_iter(ctx, name.clone())?;
+        }
+        for attr in &self.attrs {
+            in_selector &= !matches!(attr, Attr::Named(..));
+            if in_selector {
+                attr.format(block, ctx)?;
+            } else {
+                attr.format_with_space(block, ctx)?;
+            }
+        }
+        match &self.body {
+            ElementBody::Void(semi) => block.add_source(ctx, semi),
+            ElementBody::Children(brace, children) => format_braced(
+                block,
+                ctx,
+                brace,
+                children,
+                children_spacing(ctx, children),
+                ChainingRule::Off,
+            ),
+        }
+    }
+}
+
+impl<'src> Format<'src> for Attr {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Class(dot, name, toggle) => {
+                block.add_source(ctx, dot)?;
+                name.format(block, ctx)?;
+                toggle.as_ref().try_map_or((), |toggle| toggle.format(block, ctx))
+            }
+            Self::Id(pound, name) => {
+                block.add_source(ctx, pound)?;
+                name.format(block, ctx)
+            }
+            Self::Named(name, value, toggle) => {
+                block.add_source_iter(ctx, name.clone())?;
+                if let Some((eq, value)) = value {
+                    block.add_source(ctx, eq)?;
+                    value.format(block, ctx)?;
+                }
+                toggle.as_ref().try_map_or((), |toggle| toggle.format(block, ctx))
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for Name {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Ident(name) => block.add_source_iter(ctx, name.clone()),
+            Self::Lit(lit) => block.add_source(ctx, lit),
+            Self::Splice(paren, expr) => format_splice(block, ctx, paren, expr),
+        }
+    }
+}
+
+impl<'src> Format<'src> for AttrValue {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Lit(lit) => block.add_source(ctx, lit),
+            Self::Splice(paren, expr) => format_splice(block, ctx, paren, expr),
+            Self::Group(brace, nodes) => block.add_delimited_block(
+                ctx,
+                brace.span.open(),
+                brace.span.close(),
+                Some(Spacing { between: true, ..Spacing::AROUND }),
+                ChainingRule::Off,
+                |block, ctx| format_nodes(block, ctx, nodes),
+            ),
+            Self::Optional(bracket, expr) => {
+                block.add_source(ctx, bracket.span.open())?;
+                expr.format(block, ctx)?;
+                block.add_source(ctx, bracket.span.close())
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for Toggle {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.0.span.open())?;
+        self.1.format(block, ctx)?;
+        block.add_source(ctx, self.0.span.close())
+    }
+}
+
+impl<'src> Format<'src> for If {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.if_token)?;
+        block.add_source_with_space(ctx, &self.condition)?;
+        format_braced(
+            block,
+            ctx,
+            &self.brace,
+            &self.then_branch,
+            block_children_spacing(ctx),
+            self.else_branch.choose(ChainingRule::On, ChainingRule::End),
+        )?;
+        self.else_branch.as_ref().try_map_or((), |b| b.format_with_space(block, ctx))
+    }
+}
+
+impl<'src> Format<'src> for Else {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::If(at, r#else, r#if) => {
+                block.add_source(ctx, at)?;
+                block.add_source(ctx, r#else)?;
+                r#if.format_with_space(block, ctx)
+            }
+            Self::Tree(at, r#else, brace, children) => {
+                block.add_source(ctx, at)?;
+                block.add_source(ctx, r#else)?;
+                format_braced(
+                    block,
+                    ctx,
+                    brace,
+                    children,
+                    block_children_spacing(ctx),
+                    ChainingRule::End,
+                )
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for For {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.for_token)?;
+        block.add_source_with_space(ctx, &self.pat)?;
+        block.add_source_with_space(ctx, self.in_token)?;
+        block.add_source_with_space(ctx, &self.iter)?;
+        format_braced(
+            block,
+            ctx,
+            &self.brace,
+            &self.body,
+            block_children_spacing(ctx),
+            ChainingRule::Off,
+        )
+    }
+}
+
+impl<'src> Format<'src> for While {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.while_token)?;
+        block.add_source_with_space(ctx, &self.condition)?;
+        format_braced(
+            block,
+            ctx,
+            &self.brace,
+            &self.body,
+            block_children_spacing(ctx),
+            ChainingRule::Off,
+        )
+    }
+}
+
+impl<'src> Format<'src> for Match {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source(ctx, self.match_token)?;
+        block.add_source_with_space(ctx, &self.expr)?;
+        block.add_delimited_block_with_space(
+            ctx,
+            self.brace.span.open(),
+            self.brace.span.close(),
+            None,
+            ChainingRule::Off,
+            |block, ctx| {
+                for arm in &self.arms {
+                    arm.format(block, ctx)?;
+                    block.add_sep(ctx, arm.end())?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<'src> Format<'src> for Arm {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.pat.start())?;
+        block.add_source(ctx, &self.pat)?;
+        if let Some((if_token, guard)) = &self.guard {
+            block.add_source_with_space(ctx, if_token)?;
+            block.add_source_with_space(ctx, guard)?;
+        }
+        block.add_source_with_space(ctx, self.fat_arrow)?;
+        self.body.format_with_space(block, ctx)?;
+        self.comma.as_ref().try_map_or((), |comma| block.add_source(ctx, comma))
+    }
+}
+
+impl Located for Node {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => x.start(),
+            Self::Text(x) => x.start(),
+            Self::Splice(paren, _) => paren.span.open().start(),
+            Self::Group(brace, _) => brace.span.open().start(),
+            Self::If(at, _)
+            | Self::For(at, _)
+            | Self::While(at, _)
+            | Self::Match(at, _)
+            | Self::Let(at, _) => at.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => match &x.body {
+                ElementBody::Void(semi) => semi.end(),
+                ElementBody::Children(brace, _) => brace.span.close().end(),
+            },
+            Self::Text(x) => x.end(),
+            Self::Splice(paren, _) => paren.span.close().end(),
+            Self::Group(brace, _) => brace.span.close().end(),
+            Self::If(_, x) => x.end(),
+            Self::For(_, x) => x.brace.span.close().end(),
+            Self::While(_, x) => x.brace.span.close().end(),
+            Self::Match(_, x) => x.brace.span.close().end(),
+            Self::Let(_, x) => x.end(),
+        }
+    }
+}
+
+impl Located for Element {
+    fn start(&self) -> LineColumn {
+        match (&self.name, self.attrs.first()) {
+            (Some(name), _) => name.start(),
+            (None, Some(attr)) => attr.start(),
+            (None, None) => match &self.body {
+                ElementBody::Void(semi) => semi.start(),
+                ElementBody::Children(brace, _) => brace.span.open().start(),
+            },
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match &self.body {
+            ElementBody::Void(semi) => semi.end(),
+            ElementBody::Children(brace, _) => brace.span.close().end(),
+        }
+    }
+}
+
+impl Located for Attr {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Class(dot, ..) => dot.start(),
+            Self::Id(pound, _) => pound.start(),
+            Self::Named(name, ..) => name.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Class(.., Some(toggle)) | Self::Named(.., Some(toggle)) => {
+                toggle.0.span.close().end()
+            }
+            Self::Class(_, name, None) | Self::Id(_, name) => name.end(),
+            Self::Named(_, Some((_, value)), None) => value.end(),
+            Self::Named(name, None, None) => name.end(),
+        }
+    }
+}
+
+impl Located for Name {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Ident(x) => x.start(),
+            Self::Lit(x) => x.start(),
+            Self::Splice(paren, _) => paren.span.open().start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Ident(x) => x.end(),
+            Self::Lit(x) => x.end(),
+            Self::Splice(paren, _) => paren.span.close().end(),
+        }
+    }
+}
+
+impl Located for AttrValue {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Lit(x) => x.start(),
+            Self::Splice(paren, _) => paren.span.open().start(),
+            Self::Group(brace, _) => brace.span.open().start(),
+            Self::Optional(bracket, _) => bracket.span.open().start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Lit(x) => x.end(),
+            Self::Splice(paren, _) => paren.span.close().end(),
+            Self::Group(brace, _) => brace.span.close().end(),
+            Self::Optional(bracket, _) => bracket.span.close().end(),
+        }
+    }
+}
+
+impl Located for If {
+    fn start(&self) -> LineColumn {
+        self.if_token.start()
+    }
+
+    fn end(&self) -> LineColumn {
+        self.else_branch.as_ref().map_or_else(|| self.brace.span.close().end(), Else::end)
+    }
+}
+
+impl Located for Else {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::If(at, ..) | Self::Tree(at, ..) => at.start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::If(_, _, r#if) => r#if.end(),
+            Self::Tree(_, _, brace, _) => brace.span.close().end(),
+        }
+    }
+}
+
+impl Located for Arm {
+    fn start(&self) -> LineColumn {
+        self.pat.start()
+    }
+
+    fn end(&self) -> LineColumn {
+        self.comma.as_ref().map_or_else(|| self.body.end(), |comma| comma.end())
+    }
+}
@@ -0,0 +1,123 @@
+//! The syntaxes of the view macros of frameworks other than Yew, formatted by the same rules as
+//! Yew's HTML when selected with `yew.view_flavor` or assigned to macros with `yew.dialect_macros`
+
+mod dioxus;
+mod leptos;
+mod maud;
+mod sycamore;
+mod table;
+
+use crate::config::{DialectName, ViewFlavor};
+use crate::formatter::{FmtBlock, Format, FormatCtx};
+use crate::html::AnyIdent;
+use crate::utils::{parse2_with_ctx, Result};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::parse::{Parse, ParseStream};
+use syn::{Macro, Token};
+
+/// A view macro syntax other than Yew's
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Leptos,
+    Dioxus,
+    Sycamore,
+    Maud,
+    Table,
+}
+
+/// The parsed contents of a view macro of one of the dialects
+pub enum View {
+    Leptos(leptos::View),
+    Dioxus(dioxus::View),
+    Sycamore(sycamore::View),
+    Maud(maud::View),
+    Table(table::View),
+}
+
+impl Dialect {
+    /// Returns `None` for Yew's own flavor, whose macros are recognised separately
+    pub fn of(flavor: ViewFlavor) -> Option<Self> {
+        match flavor {
+            ViewFlavor::Yew => None,
+            ViewFlavor::Leptos => Some(Self::Leptos),
+            ViewFlavor::Dioxus => Some(Self::Dioxus),
+            ViewFlavor::Sycamore => Some(Self::Sycamore),
+            ViewFlavor::Maud => Some(Self::Maud),
+        }
+    }
+
+    pub const fn named(name: DialectName) -> Self {
+        match name {
+            DialectName::Leptos => Self::Leptos,
+            DialectName::Dioxus => Self::Dioxus,
+            DialectName::Sycamore => Self::Sycamore,
+            DialectName::Maud => Self::Maud,
+            DialectName::Table => Self::Table,
+        }
+    }
+
+    pub fn macro_name(self) -> &'static str {
+        match self {
+            Self::Leptos | Self::Sycamore => "view",
+            Self::Dioxus => "rsx",
+            Self::Maud => "html",
+            Self::Table => "table",
+        }
+    }
+
+    pub fn is_view_macro(self, mac: &Macro) -> bool {
+        mac.path.segments.last().is_some_and(|name| name.ident == self.macro_name())
+    }
+
+    /// `ext` is whether the extensions of `yew-html-ext` are allowed in the dialects that embed
+    /// Yew's HTML
+    pub fn parse(self, tokens: TokenStream, ext: bool) -> syn::Result<View> {
+        match self {
+            Self::Leptos => syn::parse2(tokens).map(View::Leptos),
+            Self::Dioxus => syn::parse2(tokens).map(View::Dioxus),
+            Self::Sycamore => syn::parse2(tokens).map(View::Sycamore),
+            Self::Maud => syn::parse2(tokens).map(View::Maud),
+            Self::Table => parse2_with_ctx(tokens, ext).map(View::Table),
+        }
+    }
+}
+
+impl<'src> Format<'src> for View {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Leptos(view) => view.format(block, ctx),
+            Self::Dioxus(view) => view.format(block, ctx),
+            Self::Sycamore(view) => view.format(block, ctx),
+            Self::Maud(view) => view.format(block, ctx),
+            Self::Table(view) => view.format(block, ctx),
+        }
+    }
+}
+
+/// Parses a part of the input that has been split off from it, moving the errors at the end of
+/// the part, which `syn` puts at the call site, to `end`
+fn parse_part<T>(
+    parser: impl syn::parse::Parser<Output = T>,
+    tokens: TokenStream,
+    end: Span,
+) -> syn::Result<T> {
+    parser.parse2(tokens).map_err(|e| {
+        if e.span().start().line == 0 {
+            syn::Error::new(end, e)
+        } else {
+            e
+        }
+    })
+}
+
+/// Parses the name of an attribute, which consists of identifiers joined by `-` or `:`,
+/// e.g. `on:click` or `data-id`
+fn parse_attr_name(input: ParseStream) -> syn::Result<TokenStream> {
+    let mut name = AnyIdent::parse(input)?.into_token_stream();
+    while input.peek(Token![-]) || input.peek(Token![:]) && !input.peek(Token![::]) {
+        name.extend([input.parse::<TokenTree>()?]);
+        name.extend(AnyIdent::parse(input)?.into_token_stream());
+    }
+    Ok(name)
+}
@@ -0,0 +1,252 @@
+//! `sycamore::view!`, in which elements & components are written as `name(attrs..) { children.. }`,
+//! either of the parts being optional, text is quoted & dynamic values are wrapped in parentheses
+
+use super::parse_attr_name;
+use crate::{
+    config::UseSmallHeuristics,
+    formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Spacing},
+    html::AnyIdent,
+    utils::{OptionExt, Result},
+};
+use proc_macro2::{LineColumn, TokenStream};
+use quote::ToTokens;
+use syn::{
+    braced, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::{Pair, Punctuated},
+    token::{Brace, Paren},
+    Expr, Ident, LitStr, Path, Token,
+};
+
+pub struct View {
+    /// the `cx,` that preceded the nodes before Sycamore 0.9
+    pub scope: Option<(Ident, Token![,])>,
+    pub nodes: Vec<Node>,
+}
+
+pub enum Node {
+    Element(Box<Element>),
+    Text(LitStr),
+    /// `(expr)`
+    Dynamic(Paren, Box<Expr>),
+}
+
+pub struct Element {
+    pub name: TokenStream,
+    pub attrs: Option<(Paren, Punctuated<Attr, Token![,]>)>,
+    pub children: Option<(Brace, Vec<Node>)>,
+}
+
+pub enum Attr {
+    /// `name=value`, e.g. `on:click=move |_| count.set(0)`
+    Named(TokenStream, Token![=], Expr),
+    /// `..attrs`
+    Spread(Token![..], Expr),
+}
+
+impl Parse for View {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let scope = if input.peek(Ident) && input.peek2(Token![,]) {
+            Some((input.parse()?, input.parse()?))
+        } else {
+            None
+        };
+        Ok(Self { scope, nodes: Node::parse_children(input)? })
+    }
+}
+
+impl Node {
+    fn parse_children(input: ParseStream) -> syn::Result<Vec<Self>> {
+        let mut children = vec![];
+        while !input.is_empty() {
+            children.push(input.parse()?);
+        }
+        Ok(children)
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Paren) {
+            let inner;
+            Self::Dynamic(parenthesized!(inner in input), inner.parse()?)
+        } else if input.peek(LitStr) {
+            Self::Text(input.parse()?)
+        } else if input.peek(Ident) || input.peek(Token![::]) {
+            Self::Element(input.parse()?)
+        } else {
+            return Err(input.error("expected an element, a component, a dynamic value or text"));
+        })
+    }
+}
+
+impl Parse for Element {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // dashed names are those of custom elements
+        let name = if input.peek2(Token![-]) {
+            Punctuated::<AnyIdent, Token![-]>::parse_separated_nonempty(input)?.into_token_stream()
+        } else {
+            Path::parse_mod_style(input)?.into_token_stream()
+        };
+        let attrs = if input.peek(Paren) {
+            let inner;
+            Some((parenthesized!(inner in input), Punctuated::parse_terminated(&inner)?))
+        } else {
+            None
+        };
+        let children = if input.peek(Brace) {
+            let inner;
+            Some((braced!(inner in input), Node::parse_children(&inner)?))
+        } else {
+            None
+        };
+        if attrs.is_none() && children.is_none() {
+            return Err(input.error("expected the attributes or the children of the element"));
+        }
+        Ok(Self { name, attrs, children })
+    }
+}
+
+impl Parse for Attr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(if input.peek(Token![..]) {
+            Self::Spread(input.parse()?, input.parse()?)
+        } else {
+            Self::Named(parse_attr_name(input)?, input.parse()?, input.parse()?)
+        })
+    }
+}
+
+fn children_spacing(ctx: &FormatCtx, children: &[Node]) -> Option<Spacing> {
+    let spacing = Spacing { between: true, ..Spacing::AROUND };
+    match ctx.config.yew.use_small_heuristics {
+        UseSmallHeuristics::Off => None,
+        UseSmallHeuristics::Default => children
+            .iter()
+            .all(|child| matches!(child, Node::Text(_) | Node::Dynamic(..)))
+            .then_some(spacing),
+        UseSmallHeuristics::Max => Some(spacing),
+    }
+}
+
+impl<'src> Format<'src> for View {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        if let Some((scope, comma)) = &self.scope {
+            block.add_source(ctx, scope)?;
+            block.add_source(ctx, comma)?;
+            block.add_sep(ctx, comma.end())?;
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                block.add_sep(ctx, node.start())?;
+            }
+            node.format(block, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'src> Format<'src> for Node {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.start())?;
+        if block.take_skip_request() || ctx.is_formatting_off(self.loc()) {
+            return block.add_source(ctx, self.loc());
+        }
+        match self {
+            Self::Element(element) => element.format(block, ctx),
+            Self::Text(lit) => block.add_source(ctx, lit),
+            Self::Dynamic(paren, expr) => {
+                block.add_source(ctx, paren.span.open())?;
+                expr.format(block, ctx)?;
+                block.add_source(ctx, paren.span.close())
+            }
+        }
+    }
+}
+
+impl<'src> Format<'src> for Element {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_source_iter(ctx, self.name.clone())?;
+        // the attributes are chained with the children only if the latter are formatted as a block
+        let has_children = self.children.as_ref().is_some_and(|(_, children)| !children.is_empty());
+        if let Some((paren, attrs)) = &self.attrs {
+            block.add_delimited_block(
+                ctx,
+                paren.span.open(),
+                paren.span.close(),
+                Some(Spacing { between: true, ..Spacing::default() }),
+                if has_children { ChainingRule::On } else { ChainingRule::Off },
+                |block, ctx| {
+                    // a trailing comma is dropped, the same way it is from call arguments
+                    let n_attrs = attrs.len();
+                    for (i, (attr, comma)) in attrs.pairs().map(Pair::into_tuple).enumerate() {
+                        attr.format(block, ctx)?;
+                        if let Some(comma) = comma.filter(|_| i + 1 < n_attrs) {
+                            block.add_source(ctx, comma)?;
+                            block.add_sep(ctx, comma.end())?;
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+
+        let Some((brace, children)) = &self.children else { return Ok(()) };
+        if children.is_empty() {
+            block.add_source_with_space(ctx, brace.span.open())?;
+            return block.add_source(ctx, brace.span.close());
+        }
+        block.add_delimited_block_with_space(
+            ctx,
+            brace.span.open(),
+            brace.span.close(),
+            children_spacing(ctx, children),
+            self.attrs.choose(ChainingRule::End, ChainingRule::Off),
+            |block, ctx| {
+                for child in children {
+                    child.format(block, ctx)?;
+                    block.add_sep(ctx, child.end())?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<'src> Format<'src> for Attr {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Named(name, eq, value) => {
+                block.add_source_iter(ctx, name.clone())?;
+                block.add_source(ctx, eq)?;
+                value.format(block, ctx)
+            }
+            Self::Spread(dot2, value) => {
+                block.add_source(ctx, dot2)?;
+                value.format(block, ctx)
+            }
+        }
+    }
+}
+
+impl Located for Node {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => x.name.start(),
+            Self::Text(x) => x.start(),
+            Self::Dynamic(paren, _) => paren.span.open().start(),
+        }
+    }
+
+    fn end(&self) -> LineColumn {
+        match self {
+            Self::Element(x) => match (&x.attrs, &x.children) {
+                (_, Some((brace, _))) => brace.span.close().end(),
+                (Some((paren, _)), None) => paren.span.close().end(),
+                (None, None) => x.name.end(),
+            },
+            Self::Text(x) => x.end(),
+            Self::Dynamic(paren, _) => paren.span.close().end(),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! A table DSL, in which the rows of a table are written as `tr { cell, .. }` & every cell is
+//! Yew's HTML, e.g.
+//!
+//! ```ignore
+//! table! {
+//!     tr { <b>{ "Name" }</b>, <b>{ "Age" }</b> }
+//!     tr { { &user.name }, { user.age } }
+//! }
+//! ```
+//!
+//! The dialect has no macro of its own, it's only used for the macros assigned to it with
+//! `yew.dialect_macros`. It's also meant as a template for adding a dialect: a dialect is a
+//! `Parse` or a `ParseWithCtx` type with a `Format` impl, registered in `Dialect` & `View` of
+//! the parent module, plus a variant of `DialectName` for it to be selectable in the config
+
+use crate::{
+    config::UseSmallHeuristics,
+    formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Spacing},
+    html::HtmlTree,
+    utils::{ParseBufferExt, ParseWithCtx, Result},
+};
+use proc_macro2::LineColumn;
+use syn::{
+    braced,
+    parse::ParseStream,
+    punctuated::{Pair, Punctuated},
+    token::Brace,
+    Ident, Token,
+};
+
+pub struct View {
+    pub rows: Vec<Row>,
+}
+
+/// `tr { cell, .. }`
+pub struct Row {
+    pub tr: Ident,
+    pub brace: Brace,
+    pub cells: Punctuated<HtmlTree, Token![,]>,
+}
+
+impl ParseWithCtx for View {
+    type Context = bool;
+
+    fn parse(input: ParseStream, ext: bool) -> syn::Result<Self> {
+        let mut rows = vec![];
+        while !input.is_empty() {
+            rows.push(input.parse_with_ctx(ext)?);
+        }
+        Ok(Self { rows })
+    }
+}
+
+impl ParseWithCtx for Row {
+    type Context = bool;
+
+    fn parse(input: ParseStream, ext: bool) -> syn::Result<Self> {
+        let tr: Ident = input.parse()?;
+        if tr != "tr" {
+            return Err(syn::Error::new(tr.span(), "expected a row, i.e. `tr { .. }`"));
+        }
+        let inner;
+        let brace = braced!(inner in input);
+        let mut cells = Punctuated::new();
+        while !inner.is_empty() {
+            cells.push_value(inner.parse_with_ctx(ext)?);
+            if inner.is_empty() {
+                break;
+            }
+            cells.push_punct(inner.parse()?);
+        }
+        Ok(Self { tr, brace, cells })
+    }
+}
+
+impl<'src> Format<'src> for View {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        // the rows are the roots of the macro, so they're only put on separate lines if they don't
+        // all fit on one
+        for (i, row) in self.rows.iter().enumerate() {
+            if let Some(prev) = i.checked_sub(1).map(|i| &self.rows[i]) {
+                // placed at the end of the previous row, for the comments before this one to stay
+                // on their own lines
+                block.add_sep(ctx, prev.end())?;
+            }
+            row.format(block, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'src> Format<'src> for Row {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.start())?;
+        if block.take_skip_request() || ctx.is_formatting_off(self.loc()) {
+            return block.add_source(ctx, self.loc());
+        }
+        block.add_source(ctx, &self.tr)?;
+        if self.cells.is_empty() {
+            block.add_source_with_space(ctx, self.brace.span.open())?;
+            return block.add_source(ctx, self.brace.span.close());
+        }
+        // the cells of a row are kept on one line if it fits, same as the nodes of a block
+        let spacing = match ctx.config.yew.use_small_heuristics {
+            UseSmallHeuristics::Off => None,
+            UseSmallHeuristics::Default | UseSmallHeuristics::Max => {
+                Some(Spacing { between: true, ..Spacing::AROUND })
+            }
+        };
+        block.add_delimited_block_with_space(
+            ctx,
+            self.brace.span.open(),
+            self.brace.span.close(),
+            spacing,
+            ChainingRule::Off,
+            |block, ctx| {
+                // a trailing comma is dropped, whether the cells end up on one line or not
+                let n_cells = self.cells.len();
+                for (i, (cell, comma)) in self.cells.pairs().map(Pair::into_tuple).enumerate() {
+                    cell.format(block, ctx)?;
+                    if let Some(comma) = comma.filter(|_| i + 1 < n_cells) {
+                        block.add_source(ctx, comma)?;
+                        block.add_sep(ctx, comma.end())?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl Located for Row {
+    fn start(&self) -> LineColumn {
+        self.tr.start()
+    }
+
+    fn end(&self) -> LineColumn {
+        self.brace.span.close().end()
+    }
+}
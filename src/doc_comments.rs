@@ -0,0 +1,138 @@
+//! Formatting of the HTML in the Rust code blocks of doc comments
+
+use crate::{config::Config, formatter::Formatter};
+
+/// Put in place of the `# ` that hides a line of a code block in the rendered docs while the code
+/// is being formatted, since rustdoc's syntax for it isn't valid Rust. Being a comment, it's left
+/// as is by the formatter
+const HIDDEN_MARKER: &str = "/*#*/";
+
+/// Splits a line of a doc comment into its indentation & its contents, returning `None` if the line
+/// isn't a `///` or a `//!` comment
+pub fn split_doc_line<'line>(line: &'line str, prefix: &str) -> Option<(&'line str, &'line str)> {
+    let contents = line.trim_start();
+    let contents = contents.strip_prefix(prefix)?;
+    if prefix == "///" && contents.starts_with('/') {
+        return None;
+    }
+    Some((&line[..line.len() - line.trim_start().len()], contents))
+}
+
+/// Whether the code block is tested as Rust code by rustdoc, which is the case for blocks with no
+/// language & for those marked with `rust` or with the attributes specific to Rust code blocks
+fn is_rust_block(info: &str) -> bool {
+    info.split([',', ' ', '\t']).filter(|attr| !attr.is_empty()).all(|attr| {
+        matches!(
+            attr,
+            "rust" | "ignore" | "no_run" | "should_panic" | "compile_fail" | "test_harness"
+        ) || attr.starts_with("edition")
+    })
+}
+
+/// Formats the HTML macros in the Rust code blocks of a group of consecutive doc comment lines, all
+/// starting with `prefix`, fitting the code into `config.yew.html_width` along with the comment's
+/// prefix. Returns the new lines of the group, or `None` if it's to be left as is.
+/// The code blocks that fail to be formatted, e.g. because they're deliberately invalid, are left
+/// as is too
+pub fn format_doc_comment(lines: &[&str], prefix: &str, config: &Config) -> Option<String> {
+    let mut res: Vec<String> = lines.iter().map(|&line| line.to_owned()).collect();
+    let mut changed = false;
+    let mut i = 0;
+    // the replacements are done from the end to keep the indices of the earlier lines valid
+    let mut blocks = vec![];
+    while i < lines.len() {
+        let (indent, contents) = split_doc_line(lines[i], prefix)?;
+        i += 1;
+        let trimmed = contents.trim_start();
+        let fence_len = trimmed.len() - trimmed.trim_start_matches('`').len();
+        if fence_len < 3 {
+            continue;
+        }
+        let fence = &trimmed[..fence_len];
+        let start = i;
+        while i < lines.len() && !split_doc_line(lines[i], prefix)?.1.trim().starts_with(fence) {
+            i += 1;
+        }
+        if i == lines.len() {
+            // the block is never closed
+            return None;
+        }
+        if is_rust_block(&trimmed[fence_len..]) {
+            blocks.push((indent, &contents[..contents.len() - trimmed.len()], start..i));
+        }
+        i += 1;
+    }
+
+    for (indent, code_indent, range) in blocks.into_iter().rev() {
+        let code_prefix = format!("{indent}{prefix}{code_indent}");
+        let width = code_prefix.chars().count();
+        let Some(code) =
+            format_code_block(&lines[range.clone()], prefix, code_indent, config, width)
+        else {
+            continue;
+        };
+        changed = true;
+        let code_lines = code.lines().map(|line| {
+            if line.is_empty() {
+                format!("{indent}{prefix}")
+            } else {
+                format!("{code_prefix}{line}")
+            }
+        });
+        res.splice(range, code_lines);
+    }
+    changed.then(|| res.join("\n"))
+}
+
+/// Formats the code of a code block, which is to be put after `prefix_width` characters of the
+/// comment's prefix, returning `None` if it's unchanged or can't be formatted
+fn format_code_block(
+    lines: &[&str],
+    prefix: &str,
+    code_indent: &str,
+    config: &Config,
+    prefix_width: usize,
+) -> Option<String> {
+    let mut code = String::new();
+    let mut n_hidden = 0;
+    for line in lines {
+        let (_, contents) = split_doc_line(line, prefix)?;
+        let line = contents.strip_prefix(code_indent).unwrap_or(contents.trim_start());
+        let line_indent = &line[..line.len() - line.trim_start().len()];
+        match line.trim_start() {
+            "#" => code.extend([line_indent, HIDDEN_MARKER]),
+            hidden if hidden.starts_with("# ") => {
+                code.extend([line_indent, HIDDEN_MARKER, &hidden[2..]])
+            }
+            _ => {
+                code.push_str(line);
+                code.push('\n');
+                continue;
+            }
+        }
+        n_hidden += 1;
+        code.push('\n');
+    }
+
+    let mut config = config.clone();
+    config.yew.html_width = config.yew.html_width.saturating_sub(prefix_width);
+    let formatted = Formatter::new(config).format_source("<doc comment>", &code).ok()?;
+    // the hidden lines can only be restored if their markers are still at the start of lines
+    let n_restorable =
+        formatted.lines().filter(|line| line.trim_start().starts_with(HIDDEN_MARKER)).count();
+    if formatted == code
+        || n_restorable != n_hidden
+        || formatted.matches(HIDDEN_MARKER).count() != n_hidden
+    {
+        return None;
+    }
+    let lines = formatted.lines().map(|line| {
+        let line_indent = &line[..line.len() - line.trim_start().len()];
+        match line.trim_start().strip_prefix(HIDDEN_MARKER) {
+            Some("") => format!("{line_indent}#"),
+            Some(rest) => format!("{line_indent}# {rest}"),
+            None => line.to_owned(),
+        }
+    });
+    Some(lines.collect::<Vec<_>>().join("\n"))
+}
@@ -0,0 +1,124 @@
+use crate::config::Config;
+use crate::formatter::{FormatResult, Formatter};
+use crate::utils::{LineRange, Result};
+use anyhow::Context;
+use std::{
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A source file to be formatted by a [`FormatDriver`]
+pub struct SourceFile {
+    pub name: String,
+    pub src: String,
+    /// If present, only the macros overlapping these lines are formatted
+    pub lines: Option<Vec<LineRange>>,
+    /// If present, the file is formatted with this config instead of the formatter's own, e.g.
+    /// the one found in the file's directory
+    pub config: Option<Config>,
+}
+
+/// A handle that can be shared with other threads to stop a [`FormatDriver`] run.
+/// The driver checks it before formatting each file, so cancellation never leaves a file half
+/// processed
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress of a [`FormatDriver`] run, reported before each file is formatted
+#[derive(Clone, Copy, Debug)]
+pub struct Progress<'src> {
+    /// Number of files already processed
+    pub done: usize,
+    pub total: usize,
+    /// Name of the file about to be formatted
+    pub current: &'src str,
+}
+
+/// How a [`FormatDriver::run`] call ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunEnd {
+    /// All the files have been processed
+    Completed,
+    /// The file handler requested to stop
+    Stopped,
+    /// The run was stopped by a [`CancellationToken`]
+    Cancelled,
+}
+
+type ProgressCallback<'cb> = Box<dyn FnMut(Progress<'_>) + 'cb>;
+
+/// Formats a sequence of files with a single [`Formatter`], reporting progress and allowing the
+/// run to be cancelled between files
+pub struct FormatDriver<'cb> {
+    formatter: Formatter,
+    on_progress: Option<ProgressCallback<'cb>>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl<'cb> FormatDriver<'cb> {
+    pub fn new(formatter: Formatter) -> Self {
+        Self { formatter, on_progress: None, cancellation: None }
+    }
+
+    /// Sets the callback called before each file is formatted
+    pub fn with_progress(mut self, f: impl FnMut(Progress<'_>) + 'cb) -> Self {
+        self.on_progress = Some(Box::new(f));
+        self
+    }
+
+    /// Makes the run stop before the next file once `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Formats `inputs` in order, passing the result for each one to `handle`, which decides
+    /// whether to continue
+    pub fn run<'src>(
+        &mut self,
+        inputs: &'src [SourceFile],
+        mut handle: impl FnMut(&'src SourceFile, FormatResult<'_, 'src>) -> Result<ControlFlow<()>>,
+    ) -> Result<RunEnd> {
+        for (done, input) in inputs.iter().enumerate() {
+            if self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+                return Ok(RunEnd::Cancelled);
+            }
+            if let Some(on_progress) = &mut self.on_progress {
+                on_progress(Progress { done, total: inputs.len(), current: &input.name });
+            }
+            let own_config =
+                input.config.clone().map(|config| self.formatter.replace_config(config));
+            let flow = self
+                .formatter
+                .format(&input.name, &input.src, input.lines.as_deref())
+                .with_context(|| format!("failed to parse {:?}", input.name))
+                .and_then(|res| handle(input, res));
+            // restored for the next files that have no config of their own, even if this one
+            // failed, as the driver can be run again
+            if let Some(config) = own_config {
+                self.formatter.replace_config(config);
+            }
+            if flow?.is_break() {
+                return Ok(RunEnd::Stopped);
+            }
+        }
+        Ok(RunEnd::Completed)
+    }
+}
@@ -0,0 +1,49 @@
+//! Parsing of Rust files by the rules of their edition, `syn` only knowing the keywords of the
+//! latest one
+
+use crate::config::Edition;
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
+use std::str::FromStr;
+use syn::File;
+
+/// The keywords introduced in Rust 2018, which are plain identifiers in Rust 2015
+const KEYWORDS_SINCE_2018: [&str; 3] = ["async", "await", "try"];
+
+/// Turns the keywords of later editions into raw identifiers, so that `syn` parses them as such,
+/// including in the bodies of macros, for the HTML in them to be parsed the same way. The raw
+/// identifiers keep the spans of the originals, so the code they're printed from is unchanged
+fn raw_keywords(tokens: TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Ident(ident) if KEYWORDS_SINCE_2018.iter().any(|kw| ident == kw) => {
+                Ident::new_raw(&ident.to_string(), ident.span()).into()
+            }
+            TokenTree::Group(group) => {
+                let mut new = Group::new(group.delimiter(), raw_keywords(group.stream()));
+                new.set_span(group.span());
+                new.into()
+            }
+            tt => tt,
+        })
+        .collect()
+}
+
+/// Parses a Rust file by the rules of `edition`: in Rust 2015, `async`, `await` & `try` are parsed
+/// as identifiers, since they only became keywords in Rust 2018
+pub fn parse_file(input: &str, edition: Edition) -> syn::Result<File> {
+    if edition > Edition::E2015 {
+        return syn::parse_file(input);
+    }
+    // the BOM is stripped, as `syn` does, & the shebang is blanked out, for the lines & columns of
+    // the tokens to be the same as those `syn::parse_file` would give
+    let mut src = String::with_capacity(input.len());
+    let mut rest = input.strip_prefix('\u{feff}').unwrap_or(input);
+    if rest.starts_with("#!") && !rest[2..].trim_start().starts_with('[') {
+        let line_end = rest.find('\n').unwrap_or(rest.len());
+        src.extend(rest[..line_end].chars().map(|_| ' '));
+        rest = &rest[line_end..];
+    }
+    src.push_str(rest);
+    syn::parse2(raw_keywords(TokenStream::from_str(&src)?))
+}
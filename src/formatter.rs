@@ -1,42 +1,387 @@
-use crate::config::Config;
+use crate::codes::{untag, EXT_SYNTAX, INVALID_INLINE_CONFIG};
+use crate::config::{Config, Widths};
+use crate::dialects::Dialect;
+use crate::doc_comments::{format_doc_comment, split_doc_line};
+use crate::edition::parse_file;
 use crate::html::*;
-use crate::utils::{default, parse2_with_ctx, Result, SliceExt, StrExt};
+use crate::props::{is_props_macro, PropsMacro};
+use crate::source_map::{indent_width, SourceMap};
+use crate::stylist::{format_css_literal, is_stylist_macro};
+use crate::utils::{
+    as_secondary_label, default, parse2_with_ctx, LineRange, Result, SliceExt, StrExt,
+};
 use anyhow::{bail, Context};
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::files::SimpleFile;
 use codespan_reporting::term;
-use codespan_reporting::term::termcolor::WriteColor;
-use proc_macro2::LineColumn;
+use codespan_reporting::term::termcolor::{NoColor, WriteColor};
+use proc_macro2::{LineColumn, Span, TokenStream, TokenTree};
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::cmp::min;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::iter::repeat_n;
 use std::mem::{replace, take};
+use std::ops::Range;
+use std::time::{Duration, Instant};
 use std::vec::Vec as StdVec;
+use syn::parse::Parser;
 use syn::punctuated::Punctuated;
 use syn::{spanned::Spanned, visit::Visit, Macro};
-use syn::{Attribute, Item, MacroDelimiter, Stmt};
+use syn::{
+    Attribute, Block, Expr, ExprStruct, File, Ident, Item, ItemUse, LitStr, MacroDelimiter, Path,
+    Stmt, Token, UseTree,
+};
 
 fn is_skipped(attrs: &[Attribute]) -> bool {
     attrs.iter().any(|attr| attr.path().segments.iter().map(|x| &x.ident).eq(["rustfmt", "skip"]))
 }
 
-fn print_break(out: &mut String, n_newlines: u8, indent: usize) {
+/// Returns the names of the macros listed in `#[rustfmt::skip::macros(...)]` attributes
+fn skipped_macros(attrs: &[Attribute]) -> impl Iterator<Item = String> + '_ {
+    attrs
+        .iter()
+        .filter(|attr| {
+            attr.path().segments.iter().map(|x| &x.ident).eq(["rustfmt", "skip", "macros"])
+        })
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated).ok()
+        })
+        .flatten()
+        .map(|name| name.to_string())
+}
+
+/// Returns the path of the macro as written, without the leading `::`
+fn macro_name(mac: &Macro) -> String {
+    let segments = mac.path.segments.iter().map(|s| s.ident.to_string());
+    segments.collect::<StdVec<_>>().join("::")
+}
+
+/// Returns whether a macro path matches a filter passed to [`Formatter::with_only`]: a filter of
+/// a single segment has to match the path exactly, otherwise only the first & the last segments are
+/// compared, so that re-exports like `yew::prelude::html` match `yew::html`
+fn path_matches(filter: &str, path: &[String]) -> bool {
+    let filter: StdVec<&str> = filter.trim_start_matches("::").split("::").collect();
+    match (&*filter, path) {
+        ([name], [segment]) => name == segment,
+        ([first, .., last], [path_first, .., path_last]) => {
+            first == path_first && last == path_last
+        }
+        _ => false,
+    }
+}
+
+/// The names brought into scope by the `use` items of a file. The module each item is in is
+/// ignored, since the paths of the macros are only resolved on a best-effort basis
+#[derive(Default)]
+struct Imports {
+    /// full paths of the imported items by the names under which they're imported
+    names: HashMap<String, StdVec<String>>,
+    /// paths of the modules whose items are all imported with `*`
+    globs: StdVec<StdVec<String>>,
+}
+
+impl Imports {
+    fn add(&mut self, prefix: &mut StdVec<String>, tree: &UseTree) {
+        match tree {
+            UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.add(prefix, &path.tree);
+                prefix.pop();
+            }
+            UseTree::Name(name) if name.ident == "self" => {
+                if let Some(last) = prefix.last() {
+                    self.names.insert(last.clone(), prefix.clone());
+                }
+            }
+            UseTree::Name(name) => {
+                let name = name.ident.to_string();
+                let path = prefix.iter().cloned().chain([name.clone()]).collect();
+                self.names.insert(name, path);
+            }
+            UseTree::Rename(rename) => {
+                let path = prefix.iter().cloned().chain([rename.ident.to_string()]).collect();
+                self.names.insert(rename.rename.to_string(), path);
+            }
+            UseTree::Glob(_) if !prefix.is_empty() => self.globs.push(prefix.clone()),
+            UseTree::Glob(_) => (),
+            UseTree::Group(group) => group.items.iter().for_each(|tree| self.add(prefix, tree)),
+        }
+    }
+
+    /// Returns the full paths the macro path possibly refers to
+    fn resolve(&self, path: &Path) -> StdVec<StdVec<String>> {
+        let segments: StdVec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        let Some((first, rest)) = segments.split_first().filter(|_| path.leading_colon.is_none())
+        else {
+            return vec![segments];
+        };
+        if let Some(full) = self.names.get(first) {
+            return vec![full.iter().chain(rest).cloned().collect()];
+        }
+        let mut res = vec![];
+        if rest.is_empty() {
+            res.extend(self.globs.iter().map(|glob| glob.iter().chain([first]).cloned().collect()));
+        }
+        res.push(segments);
+        res
+    }
+}
+
+impl Visit<'_> for Imports {
+    fn visit_item_use(&mut self, i: &ItemUse) {
+        self.add(&mut vec![], &i.tree)
+    }
+}
+
+/// If `tokens` end with the path of a macro & the `!` of its invocation, returns the index of the
+/// start of the path
+fn invocation_path_start(tokens: &[TokenTree]) -> Option<usize> {
+    let is_punct =
+        |i: usize, c: char| matches!(tokens.get(i), Some(TokenTree::Punct(p)) if p.as_char() == c);
+    let mut start = tokens.len().checked_sub(1).filter(|&i| is_punct(i, '!'))?;
+    loop {
+        start = start.checked_sub(1).filter(|&i| matches!(tokens[i], TokenTree::Ident(_)))?;
+        match start.checked_sub(2) {
+            Some(i) if is_punct(i, ':') && is_punct(i + 1, ':') => start = i,
+            _ => return Some(start),
+        }
+        if !matches!(start.checked_sub(1).map(|i| &tokens[i]), Some(TokenTree::Ident(_))) {
+            // a leading `::`
+            return Some(start);
+        }
+    }
+}
+
+/// Returns the lines enclosed in `// yew-fmt: off` & `// yew-fmt: on` comments, a region that's
+/// never turned back on extends to the end of the input
+fn off_regions(input: &str) -> StdVec<LineRange> {
+    let mut res = vec![];
+    let mut start = None;
+    for (i, line) in input.lines().enumerate() {
+        match line.trim().strip_prefix("//").map(str::trim) {
+            Some("yew-fmt: off") => _ = start.get_or_insert(i + 1),
+            Some("yew-fmt: on") => {
+                res.extend(start.take().map(|start| LineRange { start, end: i + 1 }))
+            }
+            _ => (),
+        }
+    }
+    res.extend(start.map(|start| LineRange { start, end: usize::MAX }));
+    res
+}
+
+pub(crate) fn is_html_macro(mac: &Macro) -> bool {
+    mac.path.segments.last().is_some_and(|name| name.ident == "html" || name.ident == "html_nested")
+}
+
+/// Returns the delimiters the macro's HTML will be printed with & the spacing of the root block
+fn html_macro_delimiters(mac: &Macro) -> (&'static str, &'static str, Spacing) {
+    match mac.delimiter {
+        MacroDelimiter::Paren(_) | MacroDelimiter::Bracket(_) => ("(", ")", default()),
+        MacroDelimiter::Brace(_) => ("{", "}", Spacing::AROUND),
+    }
+}
+
+/// Collects `html!` invocations found in a syntax tree in the order of their appearance, without
+/// descending into them
+#[derive(Default)]
+pub struct HtmlMacroFinder<'ast>(pub StdVec<&'ast Macro>);
+
+impl<'ast> Visit<'ast> for HtmlMacroFinder<'ast> {
+    fn visit_macro(&mut self, i: &'ast Macro) {
+        if is_html_macro(i) {
+            self.0.push(i)
+        }
+    }
+}
+
+/// Parses the statements of a snippet, which is a file that isn't valid Rust on its own, e.g. one
+/// that's meant to be `include!`d, if it consists of statements with HTML macros among them
+fn parse_snippet(input: &str) -> Option<StdVec<Stmt>> {
+    // the BOM is stripped as by `syn::parse_file`, which the line tables of the input assume
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let stmts = Block::parse_within.parse_str(input).ok()?;
+    let mut finder = HtmlMacroFinder::default();
+    stmts.iter().for_each(|stmt| finder.visit_stmt(stmt));
+    (!finder.0.is_empty()).then_some(stmts)
+}
+
+/// Returns whether `input` isn't a valid Rust file, but a snippet consisting of statements with
+/// HTML macros among them, which is formatted by [`Formatter::format`] statement by statement
+pub fn is_snippet(input: &str) -> bool {
+    syn::parse_file(input).is_err() && parse_snippet(input).is_some()
+}
+
+/// Pushes the indentation `indent` columns wide: with `hard_tabs`, as many tabs as fit in it,
+/// followed by spaces for the rest, same as rustfmt does
+fn print_indent(out: &mut String, indent: usize, cfg: &Config) {
+    let n_tabs = if cfg.hard_tabs { indent / cfg.tab_spaces.max(1) } else { 0 };
+    out.extend(repeat_n('\t', n_tabs));
+    out.extend(repeat_n(' ', indent - n_tabs * cfg.tab_spaces));
+}
+
+fn print_break(out: &mut String, n_newlines: u8, indent: usize, newline: &str, cfg: &Config) {
     if n_newlines == 0 {
         return;
     }
-    out.reserve(indent + 1);
+    out.reserve(indent + newline.len() * n_newlines as usize);
     for _ in 0..n_newlines {
-        out.push('\n')
+        out.push_str(newline)
+    }
+    print_indent(out, indent, cfg)
+}
+
+/// Makes all the line breaks in `text` be `newline`, the text copied from the source as is, e.g.
+/// the code outside of the macros, may use other ones
+fn normalise_newlines(text: &mut String, newline: &str) {
+    let line_break = |line: &str| {
+        if line.ends_with("\r\n") {
+            "\r\n"
+        } else if line.ends_with('\n') {
+            "\n"
+        } else {
+            newline
+        }
+    };
+    if text.split_inclusive('\n').all(|line| line_break(line) == newline) {
+        return;
     }
-    for _ in 0..indent {
-        out.push(' ')
+    let mut res = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        match line.strip_suffix(line_break(line)) {
+            Some(content) if line.ends_with('\n') => {
+                res.push_str(content);
+                res.push_str(newline);
+            }
+            _ => res.push_str(line),
+        }
     }
+    *text = res;
 }
 
 /// if `new` is 1 line, returns its length added to `prev`, otherwise returns the length of the
 /// last line of `new`.
-fn add_last_line_len(prev: usize, new: &str) -> usize {
-    new.last_line_len().unwrap_or(new.len() + prev)
+fn add_last_line_len(prev: usize, new: &str, widths: Widths) -> usize {
+    new.last_line_len(widths).unwrap_or(new.width(widths) + prev)
+}
+
+/// State of the scanner that determines which lines of verbatim text start inside a literal
+#[derive(Clone, Copy)]
+enum VerbatimState {
+    Code,
+    Str,
+    /// the contained integer is the number of `#`s
+    RawStr(usize),
+    /// the contained integer is the nesting depth
+    BlockComment(usize),
+}
+
+impl VerbatimState {
+    const fn in_literal(self) -> bool {
+        matches!(self, Self::Str | Self::RawStr(_))
+    }
+
+    /// Updates the state according to the contents of `line`, which musn't contain newlines
+    fn scan_line(&mut self, line: &str) {
+        let bytes = line.as_bytes();
+        let is_ident_byte =
+            |i: usize| bytes.get(i).is_some_and(|&b| b == b'_' || b.is_ascii_alphanumeric());
+        let mut i = 0;
+        while let Some(&b) = bytes.get(i) {
+            match *self {
+                Self::Code => match b {
+                    b'"' => *self = Self::Str,
+                    b'/' if bytes.get(i + 1) == Some(&b'/') => return,
+                    b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                        *self = Self::BlockComment(1);
+                        i += 1;
+                    }
+                    b'r' if i == 0
+                        || !is_ident_byte(i - 1)
+                        || bytes[i - 1] == b'b' && (i == 1 || !is_ident_byte(i - 2)) =>
+                    {
+                        let n_hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                        if bytes.get(i + 1 + n_hashes) == Some(&b'"') {
+                            *self = Self::RawStr(n_hashes);
+                            i += 1 + n_hashes;
+                        }
+                    }
+                    b'\'' => {
+                        let mut chars = line[i + 1..].chars();
+                        match chars.next() {
+                            Some('\\') => {
+                                i += line[i + 2..].find('\'').map_or(line.len(), |end| end + 2);
+                            }
+                            Some(c) if chars.next() == Some('\'') => i += c.len_utf8() + 1,
+                            // a lifetime
+                            _ => (),
+                        }
+                    }
+                    _ => (),
+                },
+                Self::Str => match b {
+                    b'\\' => i += 1,
+                    b'"' => *self = Self::Code,
+                    _ => (),
+                },
+                Self::RawStr(n_hashes) => {
+                    if b == b'"'
+                        && bytes[i + 1..].iter().take_while(|&&b| b == b'#').count() >= n_hashes
+                    {
+                        *self = Self::Code;
+                        i += n_hashes;
+                    }
+                }
+                Self::BlockComment(depth) => match (b, bytes.get(i + 1)) {
+                    (b'/', Some(b'*')) => {
+                        *self = Self::BlockComment(depth + 1);
+                        i += 1;
+                    }
+                    (b'*', Some(b'/')) => {
+                        *self = if depth > 1 { Self::BlockComment(depth - 1) } else { Self::Code };
+                        i += 1;
+                    }
+                    _ => (),
+                },
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Prints multiline text copied from the source, shifting the indentation of all of its lines but
+/// the first one by `new_indent - orig_indent`; lines that start inside a literal are left intact
+fn print_verbatim(
+    out: &mut String,
+    text: &str,
+    orig_indent: usize,
+    new_indent: usize,
+    cfg: &Config,
+) {
+    let mut state = VerbatimState::Code;
+    let mut lines = text.split('\n');
+    if let Some(first) = lines.next() {
+        out.push_str(first);
+        state.scan_line(first);
+    }
+    for line in lines {
+        out.push('\n');
+        if state.in_literal() {
+            out.push_str(line);
+        } else {
+            let content = line.trim_start_matches([' ', '\t']);
+            if !content.trim_end().is_empty() {
+                let indent = indent_width(&line[..line.len() - content.len()], cfg.tab_spaces);
+                print_indent(out, (indent + new_indent).saturating_sub(orig_indent), cfg);
+            }
+            out.push_str(content);
+        }
+        state.scan_line(line);
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -176,16 +521,22 @@ pub struct Formatter {
     config: Config,
     /// buffer for tokens stored in `FmtBlock`s
     tokens_buf: Bump,
-    /// maps line number to byte offset in `input`
-    offsets: StdVec<usize>,
     /// the formatted code
     output: String,
+    /// explanations of the layout of the formatted code, if enabled
+    annotations: Option<StdVec<Annotation>>,
+    /// the trees of the blocks of the formatted macros, if enabled
+    layouts: Option<StdVec<BlockLayout>>,
+    /// if not empty, only the macros whose paths match one of these are formatted
+    only: StdVec<String>,
 }
 
 /// Represents text that's not yet written: text, space, or a group of those
 #[derive(Debug)]
 enum FmtToken<'fmt, 'src> {
     Text(&'src str),
+    /// Multiline text copied from the source, along with the indentation of the line it started on
+    Verbatim(&'src str, usize),
     /// needs special handling of the newline
     LineComment(&'src str),
     /// The contained integer is the number of newlines to be put if the parent block is broken up.
@@ -230,6 +581,113 @@ impl ChainingRule {
     }
 }
 
+/// The cause of a block being broken into several lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakReason {
+    /// `yew.use_small_heuristics` doesn't allow the block to be on one line
+    Heuristics,
+    /// the block contains code spanning several lines
+    Multiline,
+    /// a block inside the block is broken
+    Nested,
+    /// the block doesn't fit into `yew.html_width`
+    Width { end: usize, limit: usize },
+    /// the line the block is on doesn't fit into `yew.html_width`
+    Overflow { limit: usize },
+    /// the opening tag of the element, the props of which are in the block, is wider than
+    /// `yew.max_element_width`
+    ElementWidth { width: usize, limit: usize },
+    /// the element, the props of which are in the block, has more than `yew.attr_count_threshold`
+    /// props
+    PropCount { count: usize, limit: usize },
+    /// a block comment in the block is on a line of its own, which it's kept on
+    OwnLineComment,
+    /// another block in the chain this block is in was broken
+    Chain,
+}
+
+impl Display for BreakReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Heuristics => f.write_str("block broken as set by `yew.use_small_heuristics`"),
+            Self::Multiline => f.write_str("block broken because it contains multiline code"),
+            Self::Nested => f.write_str("block broken because a block inside it is broken"),
+            Self::Width { end, limit } => write!(
+                f,
+                "block broken because it would end at column {end}, exceeding `yew.html_width` \
+                 ({limit})"
+            ),
+            Self::Overflow { limit } => write!(
+                f,
+                "block broken because the line it's on would exceed `yew.html_width` ({limit})"
+            ),
+            Self::ElementWidth { width, limit } => write!(
+                f,
+                "block broken because the opening tag would be {width} wide, exceeding \
+                 `yew.max_element_width` ({limit})"
+            ),
+            Self::PropCount { count, limit } => write!(
+                f,
+                "block broken because the element has {count} props, more than \
+                 `yew.attr_count_threshold` ({limit})"
+            ),
+            Self::OwnLineComment => {
+                f.write_str("block broken to keep a block comment on a line of its own")
+            }
+            Self::Chain => f.write_str("block broken along with the other blocks in its chain"),
+        }
+    }
+}
+
+/// The final layout of a block of the formatted HTML & of the blocks inside it, collected if enabled
+/// with [`Formatter::with_layouts`]
+#[derive(Clone, Debug)]
+pub struct BlockLayout {
+    /// start of the block in the formatted code, lines being 1-based & columns 0-based
+    pub start: LineColumn,
+    /// end of the block in the formatted code, exclusive
+    pub end: LineColumn,
+    /// the width of the block, `None` if it's broken into several lines
+    pub width: Option<usize>,
+    /// why the block was broken into several lines, `None` if it's on one line
+    pub break_reason: Option<BreakReason>,
+    pub children: StdVec<BlockLayout>,
+}
+
+/// Returns the position of a byte offset into `text`, with a 1-based line & a 0-based column
+fn position_in(text: &str, offset: usize) -> LineColumn {
+    let before = &text[..offset];
+    LineColumn {
+        line: before.matches('\n').count() + 1,
+        column: before.rsplit('\n').next().map_or(0, |l| l.chars().count()),
+    }
+}
+
+/// Returns the width of the item of a broken block made of `tokens` up to the first separator, or
+/// `None` if the item can't be put on one line
+fn item_width(tokens: &[FmtToken], widths: Widths) -> Option<usize> {
+    let mut width = 0;
+    for token in tokens {
+        match token {
+            FmtToken::Text(text) if !text.contains('\n') => width += text.width(widths),
+            FmtToken::Sep(_) => break,
+            FmtToken::Block(block) => width += block.one_line_width()?,
+            _ => return None,
+        }
+    }
+    Some(width)
+}
+
+/// Explanation of a layout decision, reported in the annotation mode
+#[derive(Clone, Copy, Debug)]
+pub struct Annotation {
+    /// 1-based line of the start of the block in the formatted code
+    pub line: usize,
+    /// 0-based column of the start of the block in the formatted code
+    pub column: usize,
+    pub reason: BreakReason,
+}
+
 #[derive(Debug)]
 pub struct FmtBlock<'fmt, 'src> {
     tokens: Vec<'fmt, FmtToken<'fmt, 'src>>,
@@ -239,6 +697,47 @@ pub struct FmtBlock<'fmt, 'src> {
     spacing: Option<Spacing>,
     /// offset into the source, useful for correct printing of comments
     cur_offset: usize,
+    /// offset into the output at which the block was printed, used for annotations
+    printed_at: Cell<usize>,
+    /// offset into the output at which the block's printing ended, used for layouts
+    printed_end: Cell<usize>,
+    /// why the block was broken, `None` if it isn't
+    break_reason: Option<BreakReason>,
+    /// if true, the block is inside a fold region and is only broken if it can't be printed on
+    /// one line at all
+    folded: bool,
+    /// whether a fold region, declared by `// yew-fmt: fold-start` & `// yew-fmt: fold-end`
+    /// comments, is open at the end of the block
+    folding: bool,
+    /// whether the last comment added was `// yew-fmt: skip`, requesting the next node to be copied
+    /// from the source as is
+    skip_requested: bool,
+    /// if set, the closing delimiter of the broken block, whose width is stored here, is allowed
+    /// to be put on the line of the last item if it fits there
+    compact_end: Option<usize>,
+    /// if set, the block, if broken only by the heuristics, is put back on one line with this
+    /// spacing when the block it's in is broken & the line it's on has room for it
+    collapsed_spacing: Option<Spacing>,
+    /// if set, the widest the block can be on one line along with the text around it, the width
+    /// of which is the second number, e.g. the opening tag of an element with the props in the block
+    width_limit: Option<(usize, usize)>,
+    /// how the widths of the tokens are measured, same for all the blocks of a macro
+    widths: Widths,
+    /// if set, the block, when broken, keeps its first item on the line it starts on & aligns the
+    /// rest under it, with the spacing it had before & after its items when not broken
+    visual_spacing: Option<Spacing>,
+    /// the column the block starts at, relative to the indentation of the line it's on, as of the
+    /// last time its breaking was determined
+    start_column: usize,
+    /// if set, the number of spaces the items of the block are indented by relative to the line
+    /// it starts on when it's broken, instead of `tab_spaces`
+    item_indent: Option<usize>,
+    /// if set, the closing delimiter of the broken block is put right after its last item, with a
+    /// space in between if the contained boolean is true
+    hugged_end: Option<bool>,
+    /// if true, the items of the broken block that fit on one line are put on as few lines as they
+    /// fit on, instead of on one line each
+    packed: bool,
 }
 
 impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
@@ -247,33 +746,109 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         spacing: Option<Spacing>,
         chaining: ChainingRule,
         start_offset: usize,
+        widths: Widths,
     ) -> Self {
         Self {
             tokens: Vec::new_in(alloc),
             width: 0,
             spacing,
             cur_offset: start_offset,
+            printed_at: Cell::new(0),
+            printed_end: Cell::new(0),
+            break_reason: spacing.is_none().then_some(BreakReason::Heuristics),
             chaining_rule: chaining,
+            folded: false,
+            folding: false,
+            skip_requested: false,
+            compact_end: None,
+            collapsed_spacing: None,
+            width_limit: None,
+            widths,
+            visual_spacing: None,
+            start_column: 0,
+            item_indent: None,
+            hugged_end: None,
+            packed: false,
         }
     }
 
+    /// Allows the closing delimiter following the block to be put on the line of the last item
+    /// when the block is broken, provided that item takes up a single line
+    pub fn allow_compact_end(&mut self, closing_width: usize) {
+        self.compact_end = Some(closing_width);
+    }
+
+    /// Allows the block, broken by the heuristics, to be put on one line with `spacing` if the
+    /// block it's in is broken, provided it fits there along with the blocks inside it
+    pub fn allow_collapse(&mut self, spacing: Spacing) {
+        self.collapsed_spacing = Some(spacing);
+    }
+
+    /// Makes the block, when broken, keep its first item on the line it starts on & align the rest
+    /// under it, instead of putting each on its own line one level deeper than the block's line
+    pub fn indent_visually(&mut self) {
+        self.visual_spacing = Some(self.spacing.unwrap_or_default());
+    }
+
+    /// Makes the items of the block, when broken, be indented by `width` spaces relative to the line
+    /// the block starts on, instead of by one level
+    pub fn indent_items_by(&mut self, width: usize) {
+        self.item_indent = Some(width);
+    }
+
+    /// Makes the closing delimiter following the block be put on the line of the last item when
+    /// the block is broken, spaced from it as when the block isn't broken
+    pub fn hug_end(&mut self) {
+        self.hugged_end = Some(self.spacing.unwrap_or_default().after);
+    }
+
+    /// Makes the items of the block, when broken, be put on the line of the item before them if
+    /// they fit there, provided neither spans several lines. Left out if a line comment follows
+    /// the last item before `until`, as the comment is put after it & its width isn't known here
+    pub fn pack_items(&mut self, ctx: &FormatCtx<'_, 'src>, until: LineColumn) -> Result {
+        let until = ctx.pos_to_byte_offset(until)?;
+        let range = self.cur_offset..until;
+        let rest = ctx
+            .input
+            .get(range.clone())
+            .with_context(|| format!("span {range:?} is out of bounds for the source"))?;
+        self.packed = !rest.contains("//");
+        Ok(())
+    }
+
+    /// Breaks the block regardless of the room left for it, for `reason`
+    pub fn break_unconditionally(&mut self, reason: BreakReason) {
+        self.set_broken(reason)
+    }
+
+    /// Breaks the block if it would be wider than `limit` on one line along with the `extra`
+    /// characters around it, regardless of the room left on the line
+    pub fn limit_width(&mut self, limit: usize, extra: usize) {
+        self.width_limit = Some((limit, extra));
+    }
+
     // Functions for adding all the token kinds directly; not to be exposed
 
     fn add_raw_text(&mut self, text: &'src str) {
         match text.bytes().filter(|&b| b == b'\n').count() {
-            0 => self.width += text.len(),
-            _ => self.spacing = None,
+            0 => self.width += text.width(self.widths),
+            _ => self.set_broken(BreakReason::Multiline),
         }
         self.tokens.push(FmtToken::Text(text))
     }
 
+    fn add_raw_verbatim(&mut self, text: &'src str, orig_indent: usize) {
+        self.set_broken(BreakReason::Multiline);
+        self.tokens.push(FmtToken::Verbatim(text, orig_indent))
+    }
+
     fn add_line_comment(&mut self, comment: &'src str) {
-        self.width += comment.len() + 4;
+        self.width += comment.width(self.widths) + 4;
         self.tokens.push(FmtToken::LineComment(comment))
     }
 
     fn add_raw_sep(&mut self, n_newlines: u8) {
-        self.width += self.spacing.map_or(false, |s| s.between) as usize;
+        self.width += self.spacing.is_some_and(|s| s.between) as usize;
         self.tokens.push(FmtToken::Sep(n_newlines))
     }
 
@@ -283,7 +858,7 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         }
         match block.spacing {
             Some(_) => self.width += block.width,
-            None => self.spacing = None,
+            None => self.set_broken(BreakReason::Nested),
         }
         self.cur_offset = block.cur_offset;
         self.tokens.push(FmtToken::Block(block))
@@ -291,6 +866,11 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
 
     // Utilities
 
+    fn set_broken(&mut self, reason: BreakReason) {
+        self.spacing = None;
+        self.break_reason.get_or_insert(reason);
+    }
+
     fn add_comments_with_sep(
         &mut self,
         ctx: &FormatCtx<'_, 'src>,
@@ -306,22 +886,52 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         self.cur_offset = until;
 
         let mut comment_added = false;
-        for comment in CommentParser(comment) {
+        // whether the last comment is a block comment on its own line, in which case it's owned by
+        // the code after it and stays on the line above it
+        let mut owned_by_next = false;
+        let mut parser = CommentParser(comment);
+        while let Some(comment) = parser.next() {
             if replace(&mut comment_added, true) {
                 sep(self);
             }
+            let rest = parser.0;
+            // line comments end with a line break anyway
+            owned_by_next = matches!(comment, Comment::Multi(_))
+                && rest[..rest.len() - rest.trim_start().len()].contains('\n');
             match comment {
-                Comment::Line(line) => self.add_line_comment(line),
-                Comment::Multi(inner) => self.add_raw_text(inner),
+                Comment::Line(line) => {
+                    self.skip_requested = false;
+                    match line.trim() {
+                        "yew-fmt: fold-start" => self.folding = true,
+                        "yew-fmt: fold-end" => self.folding = false,
+                        "yew-fmt: skip" => self.skip_requested = true,
+                        _ => (),
+                    }
+                    self.add_line_comment(line)
+                }
+                Comment::Multi(inner) => {
+                    self.skip_requested = false;
+                    self.add_raw_text(inner)
+                }
             }
         }
 
-        if comment_added && self.spacing.map_or(false, |s| s.between) {
+        if owned_by_next {
+            // on one line, the comment would be put before the code after it
+            self.set_broken(BreakReason::OwnLineComment);
+            self.add_raw_sep(1);
+        } else if comment_added && self.spacing.is_some_and(|s| s.between) {
             sep(self);
         }
         Ok(())
     }
 
+    /// Returns whether the node to be added next was preceded by a `// yew-fmt: skip` comment,
+    /// resetting the request
+    pub fn take_skip_request(&mut self) -> bool {
+        take(&mut self.skip_requested)
+    }
+
     pub fn add_comments(&mut self, ctx: &FormatCtx<'_, 'src>, until: LineColumn) -> Result {
         self.add_comments_with_sep(ctx, until, |b| b.add_raw_sep(0))
     }
@@ -338,7 +948,11 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         at: LineColumn,
     ) -> Result {
         self.add_comments(ctx, at)?;
-        self.add_raw_text(text);
+        if text.contains('\n') {
+            self.add_raw_verbatim(text, ctx.line_indent(at.line)?);
+        } else {
+            self.add_raw_text(text);
+        }
         self.cur_offset += text.len();
         Ok(())
     }
@@ -380,7 +994,9 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         chaining: ChainingRule,
         f: impl FnOnce(&mut Self) -> R,
     ) -> R {
-        let mut block = Self::new(self.tokens.bump(), spacing, chaining, self.cur_offset);
+        let mut block =
+            Self::new(self.tokens.bump(), spacing, chaining, self.cur_offset, self.widths);
+        block.folded = self.folded || self.folding;
         let res = f(&mut block);
         self.add_raw_block(block);
         res
@@ -442,6 +1058,80 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         self.add_source(ctx, loc)
     }
 
+    /// Same as `add_source`, but the provided `html!` invocations, which must be located inside
+    /// `at` and be sorted by their position, get formatted instead of being copied verbatim
+    pub fn add_source_with_nested_html<'ast>(
+        &mut self,
+        ctx: &mut FormatCtx<'_, 'src>,
+        at: impl Located,
+        nested: impl IntoIterator<Item = &'ast Macro>,
+    ) -> Result {
+        let Location { mut start, end } = at.loc();
+        // the code between the macros is reindented as a whole, relative to the line it starts on
+        let orig_indent = ctx.line_indent(start.line)?;
+        for mac in nested {
+            self.add_source_with_indent(ctx, Location { start, end: mac.start() }, orig_indent)?;
+            self.add_html_macro(ctx, mac)?;
+            start = mac.end();
+        }
+        self.add_source_with_indent(ctx, Location { start, end }, orig_indent)
+    }
+
+    /// Same as `add_source`, but if the source spans several lines, they're reindented relative
+    /// to `orig_indent` instead of the indentation of the line the source starts on
+    fn add_source_with_indent(
+        &mut self,
+        ctx: &FormatCtx<'_, 'src>,
+        at: Location,
+        orig_indent: usize,
+    ) -> Result {
+        let text = ctx.source_code(at).context("failed to get a token's source code")?;
+        self.add_comments(ctx, at.start)?;
+        if text.contains('\n') {
+            self.add_raw_verbatim(text, orig_indent);
+        } else {
+            self.add_raw_text(text);
+        }
+        self.cur_offset += text.len();
+        Ok(())
+    }
+
+    /// Formats an `html!` invocation nested inside another one; if the HTML fails to parse, the
+    /// invocation is copied verbatim
+    pub fn add_html_macro(&mut self, ctx: &mut FormatCtx<'_, 'src>, mac: &Macro) -> Result {
+        let html = if mac.tokens.is_empty() {
+            None
+        } else {
+            match parse2_with_ctx::<Html>(mac.tokens.clone(), ctx.config.yew.ext) {
+                Ok(html) => Some(html),
+                Err(_) => return self.add_source(ctx, mac),
+            }
+        };
+        let (opening, closing, root_spacing) = html_macro_delimiters(mac);
+        let span = mac.delimiter.span();
+        let (opening_span, closing_span) = (span.open(), span.close());
+
+        self.add_source(ctx, Location { start: mac.path.start(), end: mac.bang_token.end() })?;
+        if root_spacing.before {
+            self.add_space(ctx, opening_span.start())?;
+        }
+        self.add_text(ctx, opening, opening_span.start())?;
+        if let Some(html) = html {
+            self.add_block(Some(root_spacing), ChainingRule::Off, |block| {
+                html.format(block, ctx)?;
+                block.add_comments(ctx, closing_span.start())
+            })?;
+            // the spaces inside the braces are only printed along with the block, so they're not
+            // in its width
+            if let Some(FmtToken::Block(block)) = self.tokens.last() {
+                if block.spacing.is_some() && !block.tokens.is_empty() {
+                    self.width += root_spacing.before as usize + root_spacing.after as usize;
+                }
+            }
+        }
+        self.add_text(ctx, closing, closing_span.start())
+    }
+
     pub fn add_source_iter(
         &mut self,
         ctx: &FormatCtx<'_, 'src>,
@@ -453,6 +1143,8 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         Ok(())
     }
 
+    /// Adds the items & the punctuation with nothing in between, so that e.g. dashed prop names
+    /// are always printed as a single unbreakable word
     pub fn add_source_punctuated<T, P>(
         &mut self,
         ctx: &FormatCtx<'_, 'src>,
@@ -470,20 +1162,95 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         Ok(())
     }
 
+    /// Puts the block back on one line if it's allowed to by [`FmtBlock::allow_collapse`] & is
+    /// broken only by the heuristics, either its own or those of the blocks inside it.
+    /// Returns whether the block is on one line, the width of which is yet to be checked.
+    /// Must only be called once the block it's in is broken
+    fn collapse(&mut self) -> bool {
+        if self.spacing.is_some() {
+            return true;
+        }
+        let Some(spacing) = self.collapsed_spacing else { return false };
+        if !matches!(self.break_reason, Some(BreakReason::Heuristics | BreakReason::Nested)) {
+            return false;
+        }
+        let mut width = 0;
+        for token in &mut self.tokens {
+            match token {
+                FmtToken::Text(text) if !text.contains('\n') => width += text.width(self.widths),
+                FmtToken::Sep(_) => width += spacing.between as usize,
+                FmtToken::Block(block) => {
+                    if !block.collapse() {
+                        return false;
+                    }
+                    width += block.width
+                }
+                _ => return false,
+            }
+        }
+        self.spacing = Some(spacing);
+        self.width = width;
+        self.break_reason = None;
+        true
+    }
+
+    /// Returns why the block is to be broken if it, or one of the blocks inside it, is wider on one
+    /// line than allowed by [`FmtBlock::limit_width`]. The widths are counted from the tokens
+    /// since the separators removed from the ends of the blocks are still counted in their widths
+    fn width_limit_break(&self) -> Option<BreakReason> {
+        let spacing = self.spacing?;
+        let mut width = 0;
+        for token in &self.tokens {
+            match token {
+                FmtToken::Text(text) => width += text.width(self.widths),
+                FmtToken::Sep(_) => width += spacing.between as usize,
+                FmtToken::LineComment(comment) => width += comment.width(self.widths) + 4,
+                FmtToken::Verbatim(..) => (),
+                FmtToken::Block(block) => {
+                    if block.width_limit_break().is_some() {
+                        return Some(BreakReason::Nested);
+                    }
+                    width += block.width
+                }
+            }
+        }
+        let (limit, extra) = self.width_limit.filter(|_| !self.tokens.is_empty())?;
+        let width = extra + width + spacing.before as usize + spacing.after as usize;
+        (width > limit).then_some(BreakReason::ElementWidth { width, limit })
+    }
+
+    /// same as `force_breaking`, but also records the reason if the block wasn't already broken
+    fn break_for(&mut self, ctx: &FormatCtx<'_, 'src>, indent: usize, reason: BreakReason) {
+        if self.folded && self.spacing.is_some() {
+            return;
+        }
+        self.break_reason.get_or_insert(reason);
+        self.force_breaking(ctx, indent)
+    }
+
     fn force_breaking(&mut self, ctx: &FormatCtx<'_, 'src>, indent: usize) {
+        if self.folded && self.spacing.is_some() {
+            return;
+        }
         self.spacing = None;
         self.width = 0;
         let mut offset = 0;
-        let indent = indent + ctx.config.tab_spaces;
+        let indent = match self.visual_spacing {
+            Some(spacing) => indent + self.start_column + spacing.before as usize,
+            None => indent + self.item_indent.unwrap_or(ctx.config.tab_spaces),
+        };
         let mut chain_broken = false;
         let mut tokens_iter = self.tokens.iter_with_prev_mut();
         while let Some((token, prev_tokens)) = tokens_iter.next() {
             match token {
-                FmtToken::Text(text) => offset = add_last_line_len(offset, text),
+                FmtToken::Text(text) | FmtToken::Verbatim(text, _) => {
+                    offset = add_last_line_len(offset, text, self.widths)
+                }
                 FmtToken::Sep(_) | FmtToken::LineComment(_) => offset = 0,
                 FmtToken::Block(block) => {
+                    block.start_column = offset;
                     if chain_broken {
-                        block.force_breaking(ctx, indent);
+                        block.break_for(ctx, indent, BreakReason::Chain);
                         chain_broken = block.chaining_rule.is_on()
                     } else if block.determine_breaking(ctx, offset, indent) {
                         if block.chaining_rule.is_on() {
@@ -492,7 +1259,7 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
                                 if !block.chaining_rule.is_on() {
                                     break;
                                 };
-                                block.force_breaking(ctx, indent)
+                                block.break_for(ctx, indent, BreakReason::Chain)
                             }
                             chain_broken = true
                         }
@@ -507,16 +1274,19 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
                 offset = 0;
                 for token in prev_tokens.iter_mut().rev() {
                     match token {
-                        FmtToken::Text(text) => offset = add_last_line_len(offset, text),
-                        FmtToken::LineComment(comment) => offset += comment.len() + 4,
+                        FmtToken::Text(text) | FmtToken::Verbatim(text, _) => {
+                            offset = add_last_line_len(offset, text, self.widths)
+                        }
+                        FmtToken::LineComment(comment) => offset += comment.width(self.widths) + 4,
                         FmtToken::Sep(_) => break,
                         FmtToken::Block(block) => {
                             if take(&mut first) {
                                 chain_broken = block.chaining_rule.is_on();
-                                block.force_breaking(ctx, indent);
+                                let limit = ctx.config.yew.html_width;
+                                block.break_for(ctx, indent, BreakReason::Overflow { limit });
                                 offset = 0
                             } else if chain_broken {
-                                block.force_breaking(ctx, indent);
+                                block.break_for(ctx, indent, BreakReason::Chain);
                                 offset = 0
                             } else {
                                 offset += block.width
@@ -535,25 +1305,77 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
         offset: usize,
         indent: usize,
     ) -> bool {
+        self.start_column = offset;
+        self.collapse();
         let Some(spacing) = self.spacing else {
             self.force_breaking(ctx, indent);
             return true;
         };
+        if self.folded {
+            return false;
+        }
 
-        if offset
+        let end = offset
             + indent
             + self.width
-            + ((spacing.before || spacing.after) && !self.tokens.is_empty()) as usize
-            >= ctx.config.yew.html_width
+            + ((spacing.before || spacing.after) && !self.tokens.is_empty()) as usize;
+        let limit = ctx.config.yew.html_width;
+        if end >= limit {
+            self.break_for(ctx, indent, BreakReason::Width { end, limit });
+            return true;
+        }
+        if let Some(reason) =
+            ctx.config.yew.max_element_width.and_then(|_| self.width_limit_break())
         {
-            self.force_breaking(ctx, indent);
+            self.break_for(ctx, indent, reason);
             return true;
         }
 
         false
     }
 
-    fn print(&self, indent: usize, cfg: &Config, out: &mut String) {
+    /// The width of the block printed on one line, with the spaces around its items, or `None` if
+    /// it's broken
+    fn one_line_width(&self) -> Option<usize> {
+        let spacing = self.spacing?;
+        Some(if self.tokens.is_empty() {
+            (spacing.before && spacing.after) as usize
+        } else {
+            self.width + spacing.before as usize + spacing.after as usize
+        })
+    }
+
+    /// collects the reasons for breaking the blocks, excluding the blocks broken only because of
+    /// their contents
+    /// must be called after the block is printed to `output`
+    fn annotate(&self, output: &str, dst: &mut StdVec<Annotation>) {
+        if let Some(reason) = self.break_reason.filter(|&r| r != BreakReason::Nested) {
+            let LineColumn { line, column } = position_in(output, self.printed_at.get());
+            dst.push(Annotation { line, column, reason });
+        }
+        for token in &self.tokens {
+            if let FmtToken::Block(block) = token {
+                block.annotate(output, dst)
+            }
+        }
+    }
+
+    /// must be called after the block is printed to `output`
+    fn layout(&self, output: &str) -> BlockLayout {
+        let children = self.tokens.iter().filter_map(|token| match token {
+            FmtToken::Block(block) => Some(block.layout(output)),
+            _ => None,
+        });
+        BlockLayout {
+            start: position_in(output, self.printed_at.get()),
+            end: position_in(output, self.printed_end.get()),
+            width: self.spacing.map(|_| self.width),
+            break_reason: self.spacing.is_none().then_some(self.break_reason).flatten(),
+            children: children.collect(),
+        }
+    }
+
+    fn print(&self, indent: usize, cfg: &Config, newline: &str, out: &mut String) {
         #[derive(Clone, Copy)]
         enum Sep {
             None,
@@ -565,11 +1387,14 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
 
         let print_token = |token: &FmtToken, out: &mut String, indent, sep| match token {
             FmtToken::Text(text) => out.push_str(text),
+            FmtToken::Verbatim(text, orig_indent) => {
+                print_verbatim(out, text, *orig_indent, indent, cfg)
+            }
             FmtToken::LineComment(comment) => {
                 if let Sep::Newline = sep {
                     out.push_str("//");
                     out.push_str(comment);
-                    print_break(out, 1, indent)
+                    print_break(out, 1, indent, newline, cfg)
                 } else {
                     out.push_str("/*");
                     out.push_str(comment);
@@ -579,13 +1404,30 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
             FmtToken::Sep(n_newlines) => match sep {
                 Sep::None => (),
                 Sep::Space => out.push(' '),
-                Sep::Newline => print_break(out, *n_newlines, indent),
+                Sep::Newline => print_break(out, *n_newlines, indent, newline, cfg),
             },
-            FmtToken::Block(block) => block.print(indent, cfg, out),
+            FmtToken::Block(block) => block.print(indent, cfg, newline, out),
+        };
+
+        // line comments keep the items on their own lines
+        let packed =
+            self.packed && !self.tokens.iter().any(|t| matches!(t, FmtToken::LineComment(_)));
+        // whether the separator at `i` is to be printed as a space, putting the item after it on
+        // the line of the one before it, which starts at `last_item_start`
+        let packs = |i: usize, out: &str, last_item_start: usize| {
+            packed
+                && matches!(self.tokens[i], FmtToken::Sep(1))
+                && !out[last_item_start..].contains('\n')
+                && item_width(&self.tokens[i + 1..], self.widths).is_some_and(|width| {
+                    let line =
+                        out.last_line_len(self.widths).unwrap_or_else(|| out.width(self.widths));
+                    line + 1 + width <= cfg.yew.html_width
+                })
         };
 
+        self.printed_at.set(out.len());
         if self.tokens.is_empty() {
-            if self.spacing.map_or(false, |s| s.before && s.after) {
+            if self.spacing.is_some_and(|s| s.before && s.after) {
                 out.push(' ');
             }
         } else if let Some(spacing) = self.spacing {
@@ -598,39 +1440,108 @@ impl<'fmt, 'src> FmtBlock<'fmt, 'src> {
             if spacing.after {
                 out.push(' ');
             }
+        } else if let Some(spacing) = self.visual_spacing {
+            if spacing.before {
+                out.push(' ');
+            }
+            let new_indent =
+                out.last_line_len(self.widths).unwrap_or_else(|| out.width(self.widths));
+            let mut last_item_start = out.len();
+            for (i, token) in self.tokens.iter().enumerate() {
+                if packs(i, out, last_item_start) {
+                    out.push(' ');
+                    last_item_start = out.len();
+                    continue;
+                }
+                print_token(token, out, new_indent, Sep::Newline);
+                if let FmtToken::Sep(_) = token {
+                    last_item_start = out.len();
+                }
+            }
+            if let Some(FmtToken::LineComment(_)) = self.tokens.last() {
+                out.truncate(out.rfind('\n').map_or(0, |i| i + 1));
+                print_indent(out, indent, cfg)
+            } else if spacing.after {
+                out.push(' ');
+            }
         } else {
-            let new_indent = indent + cfg.tab_spaces;
-            print_break(out, 1, new_indent);
-            for token in &self.tokens {
+            let new_indent = indent + self.item_indent.unwrap_or(cfg.tab_spaces);
+            print_break(out, 1, new_indent, newline, cfg);
+            let mut last_item_start = out.len();
+            for (i, token) in self.tokens.iter().enumerate() {
+                if packs(i, out, last_item_start) {
+                    out.push(' ');
+                    last_item_start = out.len();
+                    continue;
+                }
                 print_token(token, out, new_indent, Sep::Newline);
+                if let FmtToken::Sep(_) = token {
+                    last_item_start = out.len();
+                }
             }
             if let Some(FmtToken::LineComment(_)) = self.tokens.last() {
-                out.truncate(out.len() - 4)
-            } else {
-                print_break(out, 1, indent)
+                // the line comment is followed by a break indented for the next item
+                out.truncate(out.rfind('\n').map_or(0, |i| i + 1));
+                print_indent(out, indent, cfg)
+            } else if let Some(space) = self.hugged_end {
+                if space {
+                    out.push(' ');
+                }
+            } else if !self.compact_end.is_some_and(|closing_width| {
+                let last_line = out.rsplit('\n').next().unwrap_or_default();
+                !out[last_item_start..].contains('\n')
+                    && last_line.width(self.widths) + closing_width <= cfg.yew.html_width
+            }) {
+                print_break(out, 1, indent, newline, cfg)
             }
         }
+        self.printed_end.set(out.len());
     }
 }
 
 pub struct FormatCtx<'fmt, 'src> {
-    pub config: &'fmt Config,
+    /// may be overridden for a single macro by a `// yew-fmt: key=value, ...` comment above it
+    pub config: Cow<'fmt, Config>,
     /// buffer for tokens stored in `FmtBlock`s
     alloc: &'fmt Bump,
     /// for error reporting purposes
     filename: &'src str,
-    /// maps line number to byte offset in `input`
-    offsets: &'fmt mut StdVec<usize>,
+    /// the line tables of `input`, through which all the positions of the tokens are looked up
+    source_map: SourceMap<'src>,
+    /// the line break the formatted code is written with, as set by `newline_style`
+    newline: &'static str,
     /// the formatted code
     output: &'fmt mut String,
     /// the source code
     input: &'src str,
-    /// to return errors from within AST traversal
-    err: Result<Option<Diagnostic<()>>>,
+    /// to return errors from within AST traversal, only the first one is kept
+    err: Result,
+    /// the syntax errors in the macros, which are left as is, reported once the whole file is
+    /// formatted
+    diagnostics: StdVec<Diagnostic<()>>,
     /// the end of `output` represented as byte offset into `input`
     cur_offset: usize,
     /// the end of `output` represented as position in `input`
     cur_pos: LineColumn,
+    /// if provided, only macros overlapping these lines will be formatted & the rest of the input
+    /// will be left intact
+    lines: Option<&'src [LineRange]>,
+    /// if provided, the reasons for breaking blocks are collected here
+    annotations: Option<&'fmt mut StdVec<Annotation>>,
+    /// if provided, the layouts of the root blocks of the macros are collected here
+    layouts: Option<&'fmt mut StdVec<BlockLayout>>,
+    /// names of the macros listed in `#[rustfmt::skip::macros(...)]` attributes of the items
+    /// that are being visited
+    skipped_macros: StdVec<String>,
+    /// if not empty, only the macros whose paths, resolved with `imports`, match one of these are
+    /// formatted
+    only: &'fmt [String],
+    /// the `use` items of the file, to resolve the paths of the macros
+    imports: Imports,
+    /// lines inside `// yew-fmt: off` & `// yew-fmt: on` regions
+    off_regions: StdVec<LineRange>,
+    stats: MacroStats,
+    timings: Timings,
 }
 
 impl<'fmt, 'src: 'fmt> Visit<'_> for FormatCtx<'fmt, 'src> {
@@ -654,181 +1565,574 @@ impl<'fmt, 'src: 'fmt> Visit<'_> for FormatCtx<'fmt, 'src> {
             _ => return,
         };
         if !is_skipped(attrs) {
-            syn::visit::visit_item(self, i)
+            let n_skipped = self.skipped_macros.len();
+            self.skipped_macros.extend(skipped_macros(attrs));
+            syn::visit::visit_item(self, i);
+            self.skipped_macros.truncate(n_skipped);
         }
     }
 
+    fn visit_file(&mut self, i: &'_ File) {
+        self.skipped_macros.extend(skipped_macros(&i.attrs));
+        syn::visit::visit_file(self, i)
+    }
+
     fn visit_stmt(&mut self, i: &'_ Stmt) {
         let attrs = match i {
             Stmt::Local(x) => &x.attrs,
             Stmt::Macro(x) => &x.attrs,
-            Stmt::Item(i) => return syn::visit::visit_item(self, i),
-            Stmt::Expr(e, _) => return syn::visit::visit_expr(self, e),
+            Stmt::Item(i) => return self.visit_item(i),
+            Stmt::Expr(e, _) => return self.visit_expr(e),
         };
         if !is_skipped(attrs) {
             syn::visit::visit_stmt(self, i);
         }
     }
 
+    fn visit_expr(&mut self, i: &'_ Expr) {
+        let attrs: &[Attribute] = match i {
+            Expr::Array(x) => &x.attrs,
+            Expr::Assign(x) => &x.attrs,
+            Expr::Async(x) => &x.attrs,
+            Expr::Await(x) => &x.attrs,
+            Expr::Binary(x) => &x.attrs,
+            Expr::Block(x) => &x.attrs,
+            Expr::Break(x) => &x.attrs,
+            Expr::Call(x) => &x.attrs,
+            Expr::Cast(x) => &x.attrs,
+            Expr::Closure(x) => &x.attrs,
+            Expr::Const(x) => &x.attrs,
+            Expr::Continue(x) => &x.attrs,
+            Expr::Field(x) => &x.attrs,
+            Expr::ForLoop(x) => &x.attrs,
+            Expr::Group(x) => &x.attrs,
+            Expr::If(x) => &x.attrs,
+            Expr::Index(x) => &x.attrs,
+            Expr::Infer(x) => &x.attrs,
+            Expr::Let(x) => &x.attrs,
+            Expr::Lit(x) => &x.attrs,
+            Expr::Loop(x) => &x.attrs,
+            Expr::Macro(x) => &x.attrs,
+            Expr::Match(x) => &x.attrs,
+            Expr::MethodCall(x) => &x.attrs,
+            Expr::Paren(x) => &x.attrs,
+            Expr::Path(x) => &x.attrs,
+            Expr::Range(x) => &x.attrs,
+            Expr::Reference(x) => &x.attrs,
+            Expr::Repeat(x) => &x.attrs,
+            Expr::Return(x) => &x.attrs,
+            Expr::Struct(x) => &x.attrs,
+            Expr::Try(x) => &x.attrs,
+            Expr::TryBlock(x) => &x.attrs,
+            Expr::Tuple(x) => &x.attrs,
+            Expr::Unary(x) => &x.attrs,
+            Expr::Unsafe(x) => &x.attrs,
+            Expr::While(x) => &x.attrs,
+            Expr::Yield(x) => &x.attrs,
+            _ => &[],
+        };
+        if !is_skipped(attrs) {
+            syn::visit::visit_expr(self, i);
+        }
+    }
+
+    fn visit_attribute(&mut self, i: &Attribute) {
+        if self.config.yew.format_doc_comments {
+            let res = self.format_doc_comment(i).map(|()| None);
+            self.record(res);
+        }
+        syn::visit::visit_attribute(self, i)
+    }
+
     // TODO: rewrite with a `try` block when those get stabilised
     fn visit_macro(&mut self, i: &Macro) {
-        self.err = (|| -> Result<Option<Diagnostic<()>>> {
-            let Some(name) = i.path.segments.last() else {
-                return Ok(None);
-            };
-            if name.ident != "html" && name.ident != "html_nested" {
-                return Ok(None);
+        if is_props_macro(i) {
+            let res = self.format_props_macro(i);
+            return self.record(res);
+        }
+        if self.config.yew.format_stylist && is_stylist_macro(i) {
+            let res = self.format_stylist_macro(i);
+            return self.record(res);
+        }
+        let dialect = self.dialect(i);
+        if dialect.is_none() && !self.is_html(i) {
+            if !self.is_macro_skipped(i)
+                && (self.config.yew.format_macro_rules || !i.path.is_ident("macro_rules"))
+            {
+                self.visit_foreign_tokens(i.tokens.clone());
             }
-
-            let (opening, closing, root_spacing) = match i.delimiter {
-                MacroDelimiter::Paren(_) | MacroDelimiter::Bracket(_) => ("(", ")", default()),
-                MacroDelimiter::Brace(_) => ("{", "}", Spacing::AROUND),
-            };
-            let span = i.delimiter.span();
-            let (opening_span, closing_span) = (span.open(), span.close());
-            self.print_source(opening_span.start())?;
-
-            let html_start = opening_span.end();
-            if i.tokens.is_empty() {
-                self.print_text(opening, html_start)?;
-                self.print_text(closing, closing_span.end())?;
+            return;
+        }
+        let outer_config = match self.inline_config(i) {
+            Ok(config) => config.map(|config| replace(&mut self.config, Cow::Owned(config))),
+            Err(diagnostic) => return self.diagnostics.push(diagnostic),
+        };
+        let res = (|| -> Result<Option<Diagnostic<()>>> {
+            if dialect.is_none() && !self.is_html(i)
+                || !self.in_lines(i)
+                || self.is_formatting_off(i)
+                || self.is_macro_skipped(i)
+            {
+                self.stats.skipped += 1;
                 return Ok(None);
             }
+            if let Some(dialect) = dialect {
+                let ext = self.config.yew.ext;
+                return self.format_view_macro(i, true, |tokens| dialect.parse(tokens, ext));
+            }
 
-            let html = match parse2_with_ctx::<Html>(i.tokens.clone(), self.config.yew.ext) {
-                Ok(html) => html,
-                Err(e) => {
-                    let span = e.span();
-                    let start = self.pos_to_byte_offset(span.start())?;
-                    let end = self.pos_to_byte_offset(span.end())?;
-                    return Ok(Some(
-                        Diagnostic::error()
-                            .with_message(e.to_string())
-                            .with_labels(vec![Label::primary((), start..end)]),
-                    ));
+            let ext = self.config.yew.ext;
+            let diagnostic =
+                self.format_view_macro(i, false, |tokens| parse2_with_ctx::<Html>(tokens, ext))?;
+            Ok(diagnostic.map(|diagnostic| {
+                if ext {
+                    return diagnostic;
                 }
-            };
-            let mut block = FmtBlock::new(
-                self.alloc,
-                Some(root_spacing),
-                ChainingRule::Off,
-                self.pos_to_byte_offset(html_start)?,
-            );
-            html.format(&mut block, self)?;
-
-            self.print_text(opening, html_start)?;
-            self.print_fmt_block(block, closing_span.start())?;
-            self.print_text(closing, closing_span.end())?;
-            Ok(None)
+                if parse2_with_ctx::<Html>(i.tokens.clone(), true).is_ok() {
+                    return diagnostic.with_code(EXT_SYNTAX.code).with_notes(vec![
+                        "this syntax is only supported by `yew-html-ext`, to format it, set \
+                         `yew.html_flavor` to \"Ext\""
+                            .to_owned(),
+                    ]);
+                }
+                // the macro may have other errors, so the syntax of `yew-html-ext` is also
+                // recognised by the keyword the error is at
+                let error_start = diagnostic.labels.first().map_or(0, |label| label.range.start);
+                let keyword =
+                    self.input[error_start..].split(|c: char| !c.is_alphanumeric()).next();
+                match keyword.filter(|kw| ["for", "match", "let"].contains(kw)) {
+                    Some(kw) => diagnostic.with_code(EXT_SYNTAX.code).with_notes(vec![format!(
+                        "`{kw}` nodes are only supported by `yew-html-ext`, did you mean to set \
+                         `yew.html_flavor` to \"Ext\"?"
+                    )]),
+                    None => diagnostic,
+                }
+            }))
         })();
+        self.record(res);
+        if let Some(config) = outer_config {
+            self.config = config;
+        }
     }
 }
 
 impl Formatter {
     pub fn new(config: Config) -> Self {
-        Self { config, tokens_buf: Bump::new(), offsets: vec![], output: String::new() }
+        Self {
+            config,
+            tokens_buf: Bump::new(),
+            output: String::new(),
+            annotations: None,
+            layouts: None,
+            only: vec![],
+        }
+    }
+
+    /// Replaces the config the files are formatted with, returns the previous one
+    pub fn replace_config(&mut self, config: Config) -> Config {
+        replace(&mut self.config, config)
+    }
+
+    /// Restricts formatting to the macros whose paths match one of `filters`, e.g. `yew::html`.
+    /// The paths are resolved with the `use` items of the file on a best-effort basis
+    pub fn with_only(mut self, filters: StdVec<String>) -> Self {
+        self.only = filters;
+        self
+    }
+
+    /// Enables or disables collecting explanations of why the blocks in the formatted code were
+    /// broken, see [`FormatResult::annotations`]
+    pub fn with_annotations(mut self, enabled: bool) -> Self {
+        self.annotations = enabled.then(StdVec::new);
+        self
     }
 
+    /// Enables or disables collecting the trees of the blocks of the formatted macros, see
+    /// [`FormatResult::layouts`]
+    pub fn with_layouts(mut self, enabled: bool) -> Self {
+        self.layouts = enabled.then(StdVec::new);
+        self
+    }
+
+    /// If `lines` are provided, only the macros overlapping them are formatted and the rest of the
+    /// input is left intact
     pub fn format<'fmt, 'src: 'fmt>(
         &'fmt mut self,
         filename: &'src str,
         input: &'src str,
+        lines: Option<&'src [LineRange]>,
     ) -> Result<FormatResult<'fmt, 'src>> {
         self.output.clear();
-        self.offsets.clear();
         self.tokens_buf.reset();
+        if let Some(annotations) = &mut self.annotations {
+            annotations.clear();
+        }
+        if let Some(layouts) = &mut self.layouts {
+            layouts.clear();
+        }
         let mut ctx = FormatCtx {
             alloc: &self.tokens_buf,
-            config: &self.config,
-            offsets: &mut self.offsets,
+            config: Cow::Borrowed(&self.config),
+            source_map: SourceMap::new(input),
+            newline: self.config.newline_style.newline(input),
             output: &mut self.output,
             filename,
             input,
-            err: Ok(None),
+            err: Ok(()),
+            diagnostics: vec![],
             cur_offset: 0,
             cur_pos: LineColumn { line: 1, column: 0 },
+            lines,
+            annotations: self.annotations.as_mut(),
+            layouts: self.layouts.as_mut(),
+            skipped_macros: vec![],
+            only: &self.only,
+            imports: Imports::default(),
+            off_regions: off_regions(input),
+            stats: MacroStats::default(),
+            timings: Timings::default(),
         };
-        let file = syn::parse_file(input)?;
-        ctx.offsets.push(0);
-        ctx.offsets.extend(input.char_indices().filter_map(|(i, c)| (c == '\n').then_some(i + 1)));
-
-        ctx.visit_file(&file);
+        let parsing_start = Instant::now();
+        let file = parse_file(input, self.config.edition);
+        ctx.timings.syn_parsing = parsing_start.elapsed();
+        match file {
+            Ok(file) => {
+                ctx.imports.visit_file(&file);
+                ctx.visit_file(&file);
+            }
+            Err(err) => {
+                let parsing_start = Instant::now();
+                let Some(stmts) = parse_snippet(input) else { return Err(err.into()) };
+                ctx.timings.syn_parsing += parsing_start.elapsed();
+                stmts.iter().for_each(|stmt| ctx.imports.visit_stmt(stmt));
+                stmts.iter().for_each(|stmt| ctx.visit_stmt(stmt));
+            }
+        }
         ctx.finalise()
     }
-}
 
-impl<'fmt, 'src> FormatCtx<'fmt, 'src> {
-    fn pos_to_byte_offset(&self, LineColumn { line, column }: LineColumn) -> Result<usize> {
-        let line_start = *self
-            .offsets
-            .get(line.saturating_sub(1))
-            .with_context(|| format!("line {line} doesn't exist in the source file"))?;
-        let column = self.input[line_start..].chars().take(column).map(char::len_utf8).sum();
+    /// Formats the HTML macros in `input`, returning the reformatted code, any syntax error in
+    /// the macros is returned as an error with the diagnostic rendered into its message
+    pub fn format_source(&mut self, filename: &str, input: &str) -> Result<String> {
+        let mut diagnostic = NoColor::new(vec![]);
+        match self.format(filename, input, None)?.emit_error(&mut diagnostic)? {
+            Some(out) => Ok(out.to_owned()),
+            None => bail!("{}", String::from_utf8_lossy(diagnostic.get_ref()).trim_end()),
+        }
+    }
+}
 
-        line_start.checked_add(column).with_context(|| {
-            format!("source position {line}:{column} can't be converted to a byte offset")
-        })
+impl<'fmt, 'src: 'fmt> FormatCtx<'fmt, 'src> {
+    /// Stores the outcome of formatting a macro: the first error is returned in place of the
+    /// formatted file, while the syntax errors are collected to be reported at the end
+    fn record(&mut self, res: Result<Option<Diagnostic<()>>>) {
+        match res {
+            Ok(Some(diagnostic)) => self.diagnostics.push(diagnostic),
+            Ok(None) => (),
+            Err(e) => {
+                if self.err.is_ok() {
+                    self.err = Err(e);
+                }
+            }
+        }
     }
 
-    pub fn source_code(&self, loc: Location) -> Result<&'src str> {
-        let start =
-            self.pos_to_byte_offset(loc.start).context("failed to find the start of the span")?;
-        let end = self.pos_to_byte_offset(loc.end).context("failed to find the end of the span")?;
-        self.input
-            .get(start..end)
-            .with_context(|| format!("byte range {start}..{end} is invalid for the source code"))
+    /// Formats a `props!` invocation by the same rules as the HTML, the invocations that fail to
+    /// parse are left as is, since they might belong to a macro other than Yew's
+    fn format_props_macro(&mut self, mac: &Macro) -> Result<Option<Diagnostic<()>>> {
+        if !self.in_lines(mac) || self.is_formatting_off(mac) || self.is_macro_skipped(mac) {
+            return Ok(None);
+        }
+        let Ok(props) = syn::parse2::<ExprStruct>(mac.tokens.clone()).map(PropsMacro) else {
+            return Ok(None);
+        };
+
+        let (opening, closing, root_spacing) = html_macro_delimiters(mac);
+        let span = mac.delimiter.span();
+        let (opening_span, closing_span) = (span.open(), span.close());
+        self.print_source(opening_span.start())?;
+        let props_start = opening_span.end();
+        let mut block = FmtBlock::new(
+            self.alloc,
+            Some(root_spacing),
+            ChainingRule::Off,
+            self.pos_to_byte_offset(props_start)?,
+            self.config.widths(),
+        );
+        props.format(&mut block, self)?;
+
+        self.print_text(opening, props_start)?;
+        self.print_fmt_block(block, closing_span.start())?;
+        self.print_text(closing, closing_span.end())?;
+        Ok(None)
     }
 
-    fn line_indent(&self, line: usize) -> Result<usize> {
-        enum State {
-            Space,
-            CommentStart,
-            Comment,
-            CommentEnd,
-        }
-        let mut state = State::Space;
-
-        let &start = self
-            .offsets
-            .get(line - 1)
-            .with_context(|| format!("line {line} doesn't exist in the source file"))?;
-        let line = unsafe { self.input.get_unchecked(start..) };
-        for (i, ch) in line.char_indices() {
-            match ch {
-                ' ' => {
-                    state = match state {
-                        State::Space => continue,
-                        State::CommentStart => State::Space,
-                        State::Comment => continue,
-                        State::CommentEnd => State::Space,
-                    }
-                }
-                '/' => {
-                    state = match state {
-                        State::Space => State::CommentStart,
-                        State::CommentStart => bail!("line {line} of the source file is empty"),
-                        State::Comment => continue,
-                        State::CommentEnd => State::Space,
-                    }
-                }
-                '*' => {
-                    state = match state {
-                        State::Space => continue,
-                        State::CommentStart => State::Comment,
-                        State::Comment => State::CommentEnd,
-                        State::CommentEnd => continue,
+    /// Formats a view macro, e.g. `html!`, whose contents are parsed with `parse`, returning the
+    /// error of parsing them as a diagnostic. `several_roots` tells whether the contents can consist
+    /// of several nodes, which are then separated by spaces, unlike the single root of Yew's HTML
+    fn format_view_macro<T: Format<'src>>(
+        &mut self,
+        mac: &Macro,
+        several_roots: bool,
+        parse: impl FnOnce(TokenStream) -> syn::Result<T>,
+    ) -> Result<Option<Diagnostic<()>>> {
+        let (opening, closing, mut root_spacing) = html_macro_delimiters(mac);
+        root_spacing.between |= several_roots;
+        let span = mac.delimiter.span();
+        let (opening_span, closing_span) = (span.open(), span.close());
+        self.print_source(opening_span.start())?;
+
+        let contents_start = opening_span.end();
+        if mac.tokens.is_empty() {
+            self.print_text(opening, contents_start)?;
+            self.print_text(closing, closing_span.end())?;
+            self.stats.formatted += 1;
+            return Ok(None);
+        }
+
+        let parsing_start = Instant::now();
+        let contents = parse(mac.tokens.clone());
+        self.timings.html_parsing += parsing_start.elapsed();
+        let contents = match contents {
+            Ok(contents) => contents,
+            Err(e) => {
+                let contents_offset = self.pos_to_byte_offset(contents_start)?;
+                let closing = self.pos_to_byte_offset(closing_span.start())?
+                    ..self.pos_to_byte_offset(closing_span.end())?;
+                // the errors at the end of the input are reported at the call site, i.e. at the
+                // start of the file, so they're moved onto the closing delimiter of the macro
+                let range = |span: Span| -> Result<Range<usize>> {
+                    let start = self.pos_to_byte_offset(span.start())?;
+                    if start < contents_offset {
+                        return Ok(closing.clone());
                     }
+                    Ok(start..self.pos_to_byte_offset(span.end())?)
+                };
+                let mut labels = vec![Label::primary((), range(e.span())?)];
+                // the errors combined with the first one are labeled with their messages
+                for e in e.clone().into_iter().skip(1) {
+                    let msg = e.to_string();
+                    labels.push(match as_secondary_label(&msg) {
+                        Some(msg) => Label::secondary((), range(e.span())?).with_message(msg),
+                        None => Label::primary((), range(e.span())?).with_message(untag(&msg).1),
+                    });
                 }
-                '\n' => bail!("line {line} of the source file is empty"),
-                _ => match state {
-                    State::Space => return Ok(i),
-                    State::CommentStart => return Ok(i - 1),
-                    State::Comment => continue,
-                    State::CommentEnd => continue,
-                },
+                let msg = e.to_string();
+                let (code, msg) = untag(&msg);
+                self.stats.failed += 1;
+                return Ok(Some(
+                    Diagnostic::error().with_code(code.code).with_message(msg).with_labels(labels),
+                ));
+            }
+        };
+        let layout_start = Instant::now();
+        let mut block = FmtBlock::new(
+            self.alloc,
+            Some(root_spacing),
+            ChainingRule::Off,
+            self.pos_to_byte_offset(contents_start)?,
+            self.config.widths(),
+        );
+        contents.format(&mut block, self)?;
+        self.timings.layout += layout_start.elapsed();
+
+        self.print_text(opening, contents_start)?;
+        self.print_fmt_block(block, closing_span.start())?;
+        self.print_text(closing, closing_span.end())?;
+        self.stats.formatted += 1;
+        Ok(None)
+    }
+
+    /// Formats the CSS in the string literal passed to a `css!` or `style!` invocation, any other
+    /// arguments as well as invocations written with CSS tokens instead of a literal are left as is
+    fn format_stylist_macro(&mut self, mac: &Macro) -> Result<Option<Diagnostic<()>>> {
+        if !self.in_lines(mac) || self.is_formatting_off(mac) || self.is_macro_skipped(mac) {
+            return Ok(None);
+        }
+        let Some(lit) = mac.tokens.clone().into_iter().next() else { return Ok(None) };
+        if syn::parse2::<LitStr>(lit.clone().into()).is_err() {
+            return Ok(None);
+        }
+        let loc = Location { start: lit.span().start(), end: lit.span().end() };
+        let indent = self.line_indent(loc.start.line)?;
+        let src = self.source_code(loc)?;
+        let Some(css) = format_css_literal(src, indent, self.config.tab_spaces) else {
+            return Ok(None);
+        };
+        self.print_source(loc.start)?;
+        self.print_text(&css, loc.end)?;
+        Ok(None)
+    }
+
+    /// Formats the HTML in the code blocks of the group of consecutive `///` or `//!` comments that
+    /// starts with the doc attribute, the attributes of the rest of the group being already printed
+    /// by the time they're visited
+    fn format_doc_comment(&mut self, attr: &Attribute) -> Result {
+        let start = attr.pound_token.span.start();
+        if !attr.path().is_ident("doc") || start.line == 0 {
+            return Ok(());
+        }
+        let offset = self.pos_to_byte_offset(start)?;
+        if offset < self.cur_offset {
+            return Ok(());
+        }
+        let rest = &self.input[offset..];
+        let prefix = if rest.starts_with("///") {
+            "///"
+        } else if rest.starts_with("//!") {
+            "//!"
+        } else {
+            // `#[doc = "..."]` & `/** ... */`
+            return Ok(());
+        };
+
+        let mut lines = vec![];
+        while let Some(text) = self.source_map.line(start.line + lines.len()) {
+            if split_doc_line(text, prefix).is_none() {
+                break;
+            }
+            lines.push(text);
+        }
+        let Some(&last) = lines.last() else { return Ok(()) };
+        let loc = Location {
+            start: LineColumn { line: start.line, column: 0 },
+            end: LineColumn { line: start.line + lines.len() - 1, column: last.chars().count() },
+        };
+        if !self.in_lines(loc) || self.is_formatting_off(loc) {
+            return Ok(());
+        }
+        let Some(formatted) = format_doc_comment(&lines, prefix, &self.config) else {
+            return Ok(());
+        };
+        self.print_source(loc.start)?;
+        self.print_text(&formatted, loc.end)
+    }
+
+    /// Formats the HTML macros found in the tokens of a macro with unknown syntax, e.g. `vec![]`.
+    /// Since such tokens aren't necessarily Rust code, the HTML macros that fail to parse are
+    /// left as is instead of being reported
+    fn visit_foreign_tokens(&mut self, tokens: TokenStream) {
+        let tokens: StdVec<TokenTree> = tokens.into_iter().collect();
+        for (i, token) in tokens.iter().enumerate() {
+            let TokenTree::Group(group) = token else { continue };
+            let Some(path_start) = invocation_path_start(&tokens[..i]) else {
+                self.visit_foreign_tokens(group.stream());
+                continue;
+            };
+            let Ok(mac) = syn::parse2::<Macro>(tokens[path_start..=i].iter().cloned().collect())
+            else {
+                self.visit_foreign_tokens(group.stream());
+                continue;
+            };
+            if !self.is_html(&mac)
+                || mac.tokens.is_empty()
+                || parse2_with_ctx::<Html>(mac.tokens.clone(), self.config.yew.ext).is_ok()
+            {
+                self.visit_macro(&mac)
             }
         }
-        bail!("line {line} of the source file is empty")
+    }
+
+    /// Returns the dialect of the macro if it's assigned one with `yew.dialect_macros`, either as
+    /// written or after resolving its path, or if it's a view macro of another framework, either
+    /// the one selected with `yew.view_flavor` or Maud's `html!`, recognised by its path
+    /// regardless of the flavor since it shares its name with Yew's
+    fn dialect(&self, mac: &Macro) -> Option<Dialect> {
+        let assigned = &self.config.yew.dialect_macros;
+        let assigned = assigned.get(&macro_name(mac)).or_else(|| {
+            self.imports.resolve(&mac.path).iter().find_map(|path| assigned.get(&path.join("::")))
+        });
+        if let Some(&name) = assigned {
+            return Some(Dialect::named(name));
+        }
+        Dialect::of(self.config.yew.view_flavor).filter(|d| d.is_view_macro(mac)).or_else(|| {
+            let is_maud = |path: &StdVec<String>| {
+                matches!(&**path, [krate, .., name] if krate == "maud" && name == "html")
+            };
+            self.imports.resolve(&mac.path).iter().any(is_maud).then_some(Dialect::Maud)
+        })
+    }
+
+    /// Returns whether the macro is `html!` or `html_nested!`, either as written, after resolving
+    /// its path with the `use` items of the file, or through `yew.macro_aliases`
+    fn is_html(&self, mac: &Macro) -> bool {
+        let aliases = &self.config.yew.macro_aliases;
+        is_html_macro(mac)
+            || aliases.contains_key(&macro_name(mac))
+            || self.imports.resolve(&mac.path).iter().any(|path| {
+                path.last().is_some_and(|name| name == "html" || name == "html_nested")
+                    || aliases.contains_key(&path.join("::"))
+            })
+    }
+
+    fn is_macro_skipped(&self, mac: &Macro) -> bool {
+        let name = macro_name(mac);
+        self.config.skip_macro_invocations.iter().any(|skipped| skipped == "*" || *skipped == name)
+            || self.skipped_macros.contains(&name)
+            || !self.only.is_empty()
+                && !self
+                    .imports
+                    .resolve(&mac.path)
+                    .iter()
+                    .any(|path| self.only.iter().any(|filter| path_matches(filter, path)))
+    }
+
+    /// Returns the config with the overrides from a `// yew-fmt: key=value, ...` comment on the line
+    /// right above the HTML macro applied, or `None` if there's no such comment
+    fn inline_config(&self, mac: &Macro) -> Result<Option<Config>, Diagnostic<()>> {
+        let prev_line = mac.start().line.wrapping_sub(1);
+        let (Some(line), Ok(start)) =
+            (self.source_map.line(prev_line), self.source_map.line_start(prev_line))
+        else {
+            return Ok(None);
+        };
+        let comment = line.trim();
+        let comment_start = start + line.len() - line.trim_start().len();
+        let Some(overrides) = comment
+            .strip_prefix("//")
+            .and_then(|c| c.trim_start().strip_prefix("yew-fmt:"))
+            .filter(|c| c.contains('=') && self.is_html(mac))
+        else {
+            return Ok(None);
+        };
+
+        let mut config = self.config.clone().into_owned();
+        let overrides = overrides
+            .split(',')
+            .map(|kv| kv.split_once('=').map_or((kv.trim(), ""), |(k, v)| (k.trim(), v.trim())));
+        match config.yew.apply_overrides(overrides) {
+            Ok(()) => Ok(Some(config)),
+            Err(e) => Err(Diagnostic::error()
+                .with_code(INVALID_INLINE_CONFIG.code)
+                .with_message(format!("invalid config override: {e:#}"))
+                .with_labels(vec![Label::primary(
+                    (),
+                    comment_start..comment_start + comment.len(),
+                )])),
+        }
+    }
+
+    /// Returns whether `at` starts inside a `// yew-fmt: off` region
+    pub fn is_formatting_off(&self, at: impl Located) -> bool {
+        let line = at.start().line;
+        self.off_regions.iter().any(|r| r.overlaps(line, line))
+    }
+
+    fn in_lines(&self, at: impl Located) -> bool {
+        let Location { start, end } = at.loc();
+        self.lines.is_none_or(|lines| lines.iter().any(|r| r.overlaps(start.line, end.line)))
+    }
+
+    fn pos_to_byte_offset(&self, pos: LineColumn) -> Result<usize> {
+        self.source_map.byte_offset(pos)
+    }
+
+    pub fn source_code(&self, loc: Location) -> Result<&'src str> {
+        self.source_map.source_code(loc)
+    }
+
+    fn line_indent(&self, line: usize) -> Result<usize> {
+        self.source_map.line_indent(line, self.config.tab_spaces)
     }
 
     fn print_source(&mut self, until: LineColumn) -> Result {
@@ -855,9 +2159,23 @@ impl<'fmt, 'src> FormatCtx<'fmt, 'src> {
     // `end` is the position in the source file asssumed to be the end of the formatted sequence
     fn print_fmt_block(&mut self, mut block: FmtBlock<'fmt, 'src>, end: LineColumn) -> Result {
         let indent = self.line_indent(self.cur_pos.line)?;
-        block.determine_breaking(self, self.cur_pos.column - indent, indent);
+        // the code before the block on the same line may have been reformatted, so the block's
+        // column is measured in the output rather than taken from the source
+        let widths = self.config.widths();
+        let column = self.output.last_line_len(widths).unwrap_or_else(|| self.output.width(widths));
+        let layout_start = Instant::now();
+        block.determine_breaking(self, column.saturating_sub(indent), indent);
+        self.timings.layout += layout_start.elapsed();
         //panic!("{block:#?}");
-        block.print(indent, self.config, self.output);
+        let printing_start = Instant::now();
+        block.print(indent, &self.config, self.newline, self.output);
+        self.timings.printing += printing_start.elapsed();
+        if let Some(annotations) = &mut self.annotations {
+            block.annotate(self.output, annotations);
+        }
+        if let Some(layouts) = &mut self.layouts {
+            layouts.push(block.layout(self.output));
+        }
         self.cur_pos = end;
         let off = self.pos_to_byte_offset(end)?;
         self.cur_offset = off;
@@ -867,40 +2185,123 @@ impl<'fmt, 'src> FormatCtx<'fmt, 'src> {
     fn finalise(self) -> Result<FormatResult<'fmt, 'src>> {
         let rest = unsafe { self.input.get_unchecked(self.cur_offset..) };
         self.output.push_str(rest);
-        let new_len = self.output.trim_end().len();
-        self.output.truncate(new_len);
-        self.output.push('\n');
-        self.err.map(|diagnostic| FormatResult {
+        if self.lines.is_none() && self.config.yew.newline_at_eof {
+            let new_len = self.output.trim_end().len();
+            self.output.truncate(new_len);
+            self.output.push_str(self.newline);
+        }
+        normalise_newlines(self.output, self.newline);
+        self.err.map(|()| FormatResult {
             filename: self.filename,
             source: self.input,
-            output: match diagnostic {
-                Some(diagnostic) => Err(diagnostic),
-                None => Ok(self.output.as_str()),
-            },
+            annotations: self.annotations.map_or(&[], |a| a.as_slice()),
+            layouts: self.layouts.map_or(&[], |l| l.as_slice()),
+            output: self.output.as_str(),
+            diagnostics: self.diagnostics,
+            stats: self.stats,
+            timings: self.timings,
         })
     }
 }
 
+/// The time spent formatting a file, by the phase
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    /// parsing the Rust code of the file
+    pub syn_parsing: Duration,
+    /// parsing the input of the view macros; the HTML macros nested in the HTML are parsed as part
+    /// of the layout
+    pub html_parsing: Duration,
+    /// building the blocks of the macros & deciding which of them to break
+    pub layout: Duration,
+    /// writing the blocks out
+    pub printing: Duration,
+}
+
+/// The number of the view macros, i.e. the HTML macros & those of the dialects, in a file by
+/// what's been done with them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MacroStats {
+    pub formatted: usize,
+    /// left as is because of `skip_macro_invocations`, `#[rustfmt::skip::macros]`,
+    /// `// yew-fmt: off` or the macros & lines the formatting is limited to
+    pub skipped: usize,
+    /// left as is because of syntax errors, each reported with a diagnostic
+    pub failed: usize,
+}
+
 pub struct FormatResult<'fmt, 'src> {
     filename: &'src str,
     source: &'src str,
-    output: Result<&'fmt str, Diagnostic<()>>,
+    annotations: &'fmt [Annotation],
+    layouts: &'fmt [BlockLayout],
+    /// the macros that failed to parse are left as is in it
+    output: &'fmt str,
+    diagnostics: StdVec<Diagnostic<()>>,
+    stats: MacroStats,
+    timings: Timings,
 }
 
 impl<'fmt, 'src> FormatResult<'fmt, 'src> {
-    /// if the result is an error, write it into stderr, if it's successfully formatted code,
+    /// Returns the explanations of the layout, empty unless enabled with
+    /// [`Formatter::with_annotations`]
+    pub fn annotations(&self) -> &'fmt [Annotation] {
+        self.annotations
+    }
+
+    /// Returns the layouts of the root blocks of the formatted macros, in the order of the macros,
+    /// empty unless enabled with [`Formatter::with_layouts`]
+    pub fn layouts(&self) -> &'fmt [BlockLayout] {
+        self.layouts
+    }
+
+    /// Returns how many view macros of the file were formatted & left as is
+    pub fn stats(&self) -> MacroStats {
+        self.stats
+    }
+
+    pub fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    /// Returns the syntax errors in the macros of the file, in the order of the macros
+    pub fn diagnostics(&self) -> &[Diagnostic<()>] {
+        &self.diagnostics
+    }
+
+    /// Returns the code that was formatted, into which the ranges of the diagnostics point
+    pub fn source(&self) -> &'src str {
+        self.source
+    }
+
+    /// Returns the formatted code, in which the macros that failed to parse are left as is
+    pub fn output(&self) -> &'fmt str {
+        self.output
+    }
+
+    /// Writes one of the syntax errors returned by [`FormatResult::diagnostics`] into `writer`
+    pub fn emit_diagnostic(
+        &self,
+        diagnostic: &Diagnostic<()>,
+        writer: &mut dyn WriteColor,
+    ) -> Result {
+        let file = SimpleFile::new(self.filename, self.source);
+        Ok(term::emit(writer, &term::Config::default(), &file, diagnostic)?)
+    }
+
+    /// Writes all the syntax errors into `writer` & returns the formatted code, in which the macros
+    /// that failed to parse are left as is
+    pub fn emit_diagnostics(&self, writer: &mut dyn WriteColor) -> Result<&'fmt str> {
+        for diagnostic in &self.diagnostics {
+            self.emit_diagnostic(diagnostic, writer)?;
+        }
+        Ok(self.output)
+    }
+
+    /// if the result has errors, write them into `writer`, if it's successfully formatted code,
     /// return it
     pub fn emit_error(self, writer: &mut dyn WriteColor) -> Result<Option<&'fmt str>> {
-        let diagnostic = match self.output {
-            Ok(out) => return Ok(Some(out)),
-            Err(x) => x,
-        };
-        term::emit(
-            writer,
-            &term::Config::default(),
-            &SimpleFile::new(self.filename, self.source),
-            &diagnostic,
-        )?;
-        Ok(None)
+        let out = self.emit_diagnostics(writer)?;
+        Ok(self.diagnostics.is_empty().then_some(out))
     }
 }
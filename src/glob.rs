@@ -0,0 +1,40 @@
+//! Matching of paths against patterns with wildcards: `*` & `?` match within a component of a
+//! path, `**` matches any number of components. `/` & `\` are both separators on every platform
+
+/// Splits a path into its components, dropping the empty ones & `.`
+pub fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split(['/', '\\']).filter(|c| !c.is_empty() && *c != ".")
+}
+
+/// Whether a component of a path matches a component of a pattern
+pub fn component_matches(pattern: &str, component: &str) -> bool {
+    fn matches(pattern: &[u8], component: &[u8]) -> bool {
+        match (pattern, component) {
+            ([], []) => true,
+            ([b'*', rest @ ..], _) => (0..=component.len()).any(|i| matches(rest, &component[i..])),
+            ([b'?', rest @ ..], [_, component @ ..]) => matches(rest, component),
+            ([p, rest @ ..], [c, component @ ..]) => p == c && matches(rest, component),
+            _ => false,
+        }
+    }
+    matches(pattern.as_bytes(), component.as_bytes())
+}
+
+/// Whether the components of `pattern` match those of `path`, or, if `prefix` is set, only its
+/// first components, i.e. whether the path is in the directory matched by the pattern
+pub fn path_matches(pattern: &[impl AsRef<str>], path: &[&str], prefix: bool) -> bool {
+    match pattern {
+        [] => prefix || path.is_empty(),
+        [any, rest @ ..] if any.as_ref() == "**" => {
+            (0..=path.len()).any(|i| path_matches(rest, &path[i..], prefix))
+        }
+        [first, rest @ ..] => path.split_first().is_some_and(|(component, path)| {
+            component_matches(first.as_ref(), component) && path_matches(rest, path, prefix)
+        }),
+    }
+}
+
+/// Whether a pattern has any wildcards, as opposed to being a plain path
+pub fn is_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
@@ -1,13 +1,17 @@
 use crate::{
-    config::UseSmallHeuristics,
-    formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Location, Spacing},
+    codes::{MISMATCHED_TAG, STRAY_PROP_PUNCT, UNCLOSED_TAG},
+    config::{IndentStyle, UseSmallHeuristics},
+    formatter::{
+        BreakReason, ChainingRule, FmtBlock, Format, FormatCtx, HtmlMacroFinder, Located, Location,
+        Spacing,
+    },
     utils::{
-        default, OptionExt, ParseBufferExt, ParseWithCtx, PunctuatedExt, Result, TokenIter,
-        TokenTreeExt,
+        default, secondary_label, OptionExt, ParseBufferExt, ParseWithCtx, PunctuatedExt, Result,
+        StrExt, TokenIter, TokenTreeExt,
     },
 };
 use anyhow::Context;
-use proc_macro2::{Delimiter, LineColumn, TokenStream, TokenTree};
+use proc_macro2::{extra::DelimSpan, Delimiter, LineColumn, TokenStream, TokenTree};
 use quote::ToTokens;
 use std::{iter::from_fn, ops::Deref};
 use syn::{
@@ -19,7 +23,9 @@ use syn::{
     punctuated::{Pair, Punctuated},
     spanned::Spanned,
     token::Brace,
-    Block, Expr, Ident, Lit, Local, LocalInit, Pat, PatType, Stmt, Token, Type,
+    visit::Visit,
+    Block, Expr, ExprMacro, Ident, Lit, Local, LocalInit, Macro, Pat, PatType, Stmt, StmtMacro,
+    Token, Type,
 };
 
 /// Overrides `Ident`'s default `Parse` behaviour by accepting Rust keywords
@@ -76,6 +82,7 @@ pub enum HtmlBlockContent {
     Iterable(Token![for], Expr),
 }
 
+#[allow(clippy::large_enum_variant)] // always stored boxed in `HtmlTree`
 pub enum HtmlElement {
     Fragment(HtmlFragment),
     Dynamic(HtmlDynamicElement),
@@ -310,10 +317,7 @@ impl ParseWithCtx for HtmlDynamicElement {
         let at_token = input.parse()?;
         let name = input.parse()?;
 
-        let mut props = vec![];
-        while input.cursor().punct().is_none() {
-            props.push(input.parse()?)
-        }
+        let props = parse_props(input)?;
 
         let (children, closing_tag, div_token) = if input.peek(Token![>]) {
             let gt_token = input.parse()?;
@@ -343,7 +347,7 @@ impl ParseWithCtx for HtmlLiteralElement {
     type Context = bool;
 
     fn parse(input: ParseStream, ext: bool) -> syn::Result<Self> {
-        fn prop_base_collector(input: ParseStream) -> impl Iterator<Item = TokenTree> + '_ {
+        fn prop_base_collector(input: ParseStream<'_>) -> impl Iterator<Item = TokenTree> + '_ {
             from_fn(move || {
                 (!input.peek(Token![>]) && !input.peek(Token![/])).then(|| input.parse().ok())?
             })
@@ -361,10 +365,7 @@ impl ParseWithCtx for HtmlLiteralElement {
         let lt_token = input.parse()?;
         let name = get_name(input)?;
 
-        let mut props = vec![];
-        while input.cursor().punct().is_none() {
-            props.push(input.parse()?)
-        }
+        let props = parse_props(input)?;
         let prop_base = if input.peek(Token![..]) {
             Some((input.parse()?, parse2(prop_base_collector(input).collect())?))
         } else {
@@ -374,9 +375,26 @@ impl ParseWithCtx for HtmlLiteralElement {
         let (children, closing_tag, div_token) = if input.peek(Token![>]) {
             let gt_token = input.parse()?;
             let children = HtmlTree::parse_children(input, ext)?;
+            if input.is_empty() {
+                let msg = format!("unexpected end of input, expected `</{}>`", tag_name(&name));
+                let mut e = syn::Error::new(input.span(), UNCLOSED_TAG.tag(msg));
+                e.combine(secondary_label(&name, "unclosed tag"));
+                return Err(e);
+            }
             let closing_lt_token = input.parse()?;
             let div_token = input.parse()?;
             let closing_name = get_name(input)?;
+            if tag_name(&closing_name) != tag_name(&name) {
+                let mut e = syn::Error::new_spanned(
+                    &closing_name,
+                    MISMATCHED_TAG.tag(format_args!(
+                        "mismatched closing tag, expected `</{}>`",
+                        tag_name(&name)
+                    )),
+                );
+                e.combine(secondary_label(&name, "the tag being closed"));
+                return Err(e);
+            }
             (children, Some((gt_token, closing_lt_token, closing_name)), div_token)
         } else {
             (vec![], None, input.parse()?)
@@ -395,6 +413,57 @@ impl ParseWithCtx for HtmlLiteralElement {
     }
 }
 
+/// Returns the name of an element as it's written in its tags, ignoring the whitespace
+fn tag_name(name: &TokenStream) -> String {
+    name.to_string().split_whitespace().collect()
+}
+
+/// Checks if the next token can start a prop, i.e. whether it's either not punctuation or an
+/// access specifier
+fn peek_prop(input: ParseStream) -> bool {
+    input.peek(Token![~]) || input.cursor().punct().is_none()
+}
+
+/// Parses the props of an element up to the end of its opening tag or its base props.
+/// Stray punctuation among the props is skipped, so that the props after it still get parsed &
+/// every stray token gets reported, all the errors being combined into one
+fn parse_props(input: ParseStream) -> syn::Result<Vec<HtmlProp>> {
+    let mut props = vec![];
+    let mut errors: Option<syn::Error> = None;
+    loop {
+        if peek_prop(input) {
+            match input.parse() {
+                Ok(prop) => props.push(prop),
+                Err(e) => return Err(combine_errors(errors, e)),
+            }
+        } else if input.is_empty()
+            || input.peek(Token![..])
+            || input.peek(Token![>])
+            || input.peek(Token![/])
+        {
+            break;
+        } else {
+            let stray: TokenTree = input.parse()?;
+            let e = syn::Error::new(
+                stray.span(),
+                STRAY_PROP_PUNCT.tag("expected prop name, `..`, `>` or `/>`"),
+            );
+            errors = Some(combine_errors(errors, e));
+        }
+    }
+    errors.map_or(Ok(props), Err)
+}
+
+fn combine_errors(errors: Option<syn::Error>, e: syn::Error) -> syn::Error {
+    match errors {
+        Some(mut errors) => {
+            errors.combine(e);
+            errors
+        }
+        None => e,
+    }
+}
+
 impl Parse for HtmlProp {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let access_spec = input.parse().ok();
@@ -590,6 +659,46 @@ pub const fn props_spacing(self_closing: bool) -> Spacing {
     Spacing { before: true, between: true, after: self_closing }
 }
 
+/// Applies the options on the layout of the props of an element to the block holding them, the
+/// element having `n_props` props & its opening tag being ended by the token at `end`
+pub fn props_layout<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &FormatCtx<'_, 'src>,
+    n_props: usize,
+    end: LineColumn,
+) -> Result {
+    // the spacing of the unbroken block is read by `indent_visually` & `hug_end`, so the breaking
+    // comes after
+    match ctx.config.yew.attr_indent_style {
+        IndentStyle::Block => {
+            block.indent_items_by(ctx.config.yew.attr_indent);
+            if ctx.config.yew.closing_bracket_same_line {
+                block.hug_end();
+            }
+        }
+        IndentStyle::Visual => block.indent_visually(),
+    }
+    match ctx.config.yew.attr_count_threshold.filter(|&limit| n_props > limit) {
+        // the props are put on one line each anyway
+        Some(limit) => {
+            block.break_unconditionally(BreakReason::PropCount { count: n_props, limit })
+        }
+        None if !ctx.config.yew.single_attribute_per_line => block.pack_items(ctx, end)?,
+        None => (),
+    }
+    Ok(())
+}
+
+/// Returns the width of the delimiter ending the opening tag of an element, which goes after the
+/// props
+const fn opening_tag_end(self_closing: bool) -> usize {
+    if self_closing {
+        "/>".len()
+    } else {
+        ">".len()
+    }
+}
+
 pub fn element_children_spacing(ctx: &FormatCtx, children: &[HtmlTree]) -> Option<Spacing> {
     match ctx.config.yew.use_small_heuristics {
         UseSmallHeuristics::Off => None,
@@ -615,6 +724,10 @@ impl<'src> Format<'src> for Html {
 
 impl<'src> Format<'src> for HtmlTree {
     fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        block.add_comments(ctx, self.start())?;
+        if block.take_skip_request() || ctx.is_formatting_off(self.loc()) {
+            return block.add_source(ctx, self.loc());
+        }
         match self {
             HtmlTree::Element(e) => e.format(block, ctx),
             HtmlTree::Block(b) => b.format(block, ctx),
@@ -654,6 +767,12 @@ impl<'src> Format<'src> for HtmlFragment {
                     child.format(block, ctx)?;
                     block.add_sep(ctx, child.end())?;
                 }
+                if ctx.config.yew.compact_closing_tags {
+                    block.allow_compact_end("</>".len());
+                }
+                if ctx.config.yew.collapse_children {
+                    block.allow_collapse(default());
+                }
                 Ok(())
             },
         )?;
@@ -678,7 +797,16 @@ impl<'src> Format<'src> for HtmlDynamicElement {
                 prop.format(block, ctx)?;
                 block.add_sep(ctx, prop.end())?;
             }
-            anyhow::Ok(())
+            if let Some(limit) = ctx.config.yew.max_element_width {
+                // the name is measured as written, it's only reformatted if it spans several lines,
+                // in which case the element is broken anyway
+                let name = ctx.source_code(self.name.loc())?.width(ctx.config.widths());
+                block
+                    .limit_width(limit, "<@".len() + name + opening_tag_end(closing_tag.is_none()));
+            }
+            let end =
+                self.closing_tag.as_ref().map_or(self.div_token.start(), |(gt, ..)| gt.start());
+            props_layout(block, ctx, self.props.len(), end)
         })?;
 
         if let Some((gt, closing_lt, closing_at)) = closing_tag {
@@ -693,6 +821,12 @@ impl<'src> Format<'src> for HtmlDynamicElement {
                         child.format(block, ctx)?;
                         block.add_sep(ctx, child.end())?;
                     }
+                    if ctx.config.yew.compact_closing_tags {
+                        block.allow_compact_end("</@>".len());
+                    }
+                    if ctx.config.yew.collapse_children {
+                        block.allow_collapse(default());
+                    }
                     Ok(())
                 },
             )?;
@@ -728,7 +862,16 @@ impl<'src> Format<'src> for HtmlLiteralElement {
                     block.add_source(ctx, prop_base)?;
                     block.add_sep(ctx, prop_base.end())?;
                 }
-                anyhow::Ok(())
+                if let Some(limit) = ctx.config.yew.max_element_width {
+                    let name = tag_name(&self.name).width(ctx.config.widths());
+                    block.limit_width(
+                        limit,
+                        "<".len() + name + opening_tag_end(closing_tag.is_none()),
+                    );
+                }
+                let end =
+                    self.closing_tag.as_ref().map_or(self.div_token.start(), |(gt, ..)| gt.start());
+                props_layout(block, ctx, self.props.len(), end)
             },
         )?;
 
@@ -740,10 +883,17 @@ impl<'src> Format<'src> for HtmlLiteralElement {
                 element_children_spacing(ctx, &self.children),
                 ChainingRule::End,
                 |block, ctx| {
-                    Ok(for child in &self.children {
+                    for child in &self.children {
                         child.format(block, ctx)?;
                         block.add_sep(ctx, child.end())?;
-                    })
+                    }
+                    if ctx.config.yew.compact_closing_tags {
+                        block.allow_compact_end("</>".len() + tag_name(closing_name).len());
+                    }
+                    if ctx.config.yew.collapse_children {
+                        block.allow_collapse(default());
+                    }
+                    Ok(())
                 },
             )?;
             block.add_source(ctx, self.div_token)?;
@@ -758,7 +908,12 @@ impl<'src> Format<'src> for HtmlLiteralElement {
 
 impl<'src> Format<'src> for HtmlProp {
     fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
-        block.add_source_iter(ctx, self.access_spec)?;
+        if let Some(tilde) = self.access_spec {
+            block.add_source(ctx, tilde)?;
+            if ctx.config.yew.space_after_access_spec {
+                block.add_space(ctx, self.kind.start())?;
+            }
+        }
         match &self.kind {
             HtmlPropKind::Shortcut(brace, name) => {
                 block.add_source(ctx, brace.span.open())?;
@@ -793,20 +948,108 @@ impl<'src> Format<'src> for HtmlProp {
     }
 }
 
+/// Returns the arguments of a `classes!` invocation, if `mac` is one
+fn classes_args(mac: &Macro) -> Option<Punctuated<Expr, Token![,]>> {
+    if mac.path.segments.last().is_none_or(|name| name.ident != "classes") {
+        return None;
+    }
+    mac.parse_body_with(Punctuated::parse_terminated).ok()
+}
+
+/// Adds the arguments of a call, which get broken up one per line if they don't fit onto one
+fn add_args<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    delim: DelimSpan,
+    args: &Punctuated<Expr, Token![,]>,
+) -> Result {
+    block.add_delimited_block(
+        ctx,
+        delim.open(),
+        delim.close(),
+        Some(Spacing { between: true, ..default() }),
+        ChainingRule::Off,
+        |block, ctx| {
+            for (arg, comma) in args.pairs().map(Pair::into_tuple) {
+                arg.format(block, ctx)?;
+                if let Some(comma) = comma {
+                    block.add_source(ctx, comma)?;
+                    block.add_sep(ctx, comma.end())?;
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
 impl<'src> Format<'src> for Block {
     fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
         block.add_source(ctx, self.brace_token.span.open())?;
-        match &*self.stmts {
-            [] => (),
-            [only] => block.add_source(ctx, only)?,
-            [first, .., last] => {
-                block.add_source(ctx, Location { start: first.start(), end: last.end() })?
+        let classes = match &*self.stmts {
+            [Stmt::Macro(StmtMacro { mac, semi_token: None, .. })]
+            | [Stmt::Expr(Expr::Macro(ExprMacro { mac, .. }), None)] => {
+                classes_args(mac).map(|args| (mac, args))
             }
+            _ => None,
+        };
+        if let Some((mac, args)) = classes {
+            block.add_source(ctx, &mac.path)?;
+            block.add_source(ctx, mac.bang_token)?;
+            add_args(block, ctx, *mac.delimiter.span(), &args)?;
+        } else if let [first, .., last] | [first @ last] = &*self.stmts {
+            let mut nested = HtmlMacroFinder::default();
+            self.stmts.iter().for_each(|stmt| nested.visit_stmt(stmt));
+            block.add_source_with_nested_html(
+                ctx,
+                Location { start: first.start(), end: last.end() },
+                nested.0,
+            )?;
         }
         block.add_source(ctx, self.brace_token.span.close())
     }
 }
 
+/// Only function & method calls and `classes!` invocations are formatted, their arguments get broken
+/// up if they don't fit onto one line; every other expression is copied verbatim, save for the HTML
+/// macros nested in it
+impl<'src> Format<'src> for Expr {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        match self {
+            Self::Call(call) if !call.args.is_empty() => {
+                call.func.format(block, ctx)?;
+                add_args(block, ctx, call.paren_token.span, &call.args)
+            }
+            Self::MethodCall(call) if !call.args.is_empty() => {
+                call.receiver.format(block, ctx)?;
+                block.add_source(ctx, call.dot_token)?;
+                block.add_source(ctx, &call.method)?;
+                block.add_source_iter(ctx, &call.turbofish)?;
+                add_args(block, ctx, call.paren_token.span, &call.args)
+            }
+            Self::Macro(ExprMacro { mac, .. }) => match classes_args(mac) {
+                Some(args) if !args.is_empty() => {
+                    block.add_source(ctx, &mac.path)?;
+                    block.add_source(ctx, mac.bang_token)?;
+                    add_args(block, ctx, *mac.delimiter.span(), &args)
+                }
+                _ => add_expr_source(block, ctx, self),
+            },
+            _ => add_expr_source(block, ctx, self),
+        }
+    }
+}
+
+/// Copies the expression verbatim, except for the HTML macros nested in it, which get formatted
+fn add_expr_source<'src>(
+    block: &mut FmtBlock<'_, 'src>,
+    ctx: &mut FormatCtx<'_, 'src>,
+    expr: &Expr,
+) -> Result {
+    let mut nested = HtmlMacroFinder::default();
+    nested.visit_expr(expr);
+    block.add_source_with_nested_html(ctx, expr, nested.0)
+}
+
 impl<'src> Format<'src> for HtmlBlock {
     fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
         block.add_source(ctx, self.brace.span.open())?;
@@ -818,10 +1061,10 @@ impl<'src> Format<'src> for HtmlBlock {
 impl<'src> Format<'src> for HtmlBlockContent {
     fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
         match self {
-            Self::Expr(e) => block.add_source(ctx, e),
+            Self::Expr(e) => e.format(block, ctx),
             Self::Iterable(r#for, e) => {
                 block.add_source(ctx, r#for)?;
-                block.add_source_with_space(ctx, e)
+                e.format_with_space(block, ctx)
             }
         }
     }
@@ -1079,13 +1322,22 @@ impl Located for HtmlLiteralElement {
 
 impl Located for HtmlProp {
     fn start(&self) -> LineColumn {
-        if let Some(tilde) = &self.access_spec {
-            return tilde.span.start();
+        match &self.access_spec {
+            Some(tilde) => tilde.span.start(),
+            None => self.kind.start(),
         }
+    }
 
-        match &self.kind {
-            HtmlPropKind::Shortcut(brace, _) => brace.span.open().start(),
-            HtmlPropKind::Literal(name, ..) | HtmlPropKind::Block(name, ..) => unsafe {
+    fn end(&self) -> LineColumn {
+        self.kind.end()
+    }
+}
+
+impl Located for HtmlPropKind {
+    fn start(&self) -> LineColumn {
+        match self {
+            Self::Shortcut(brace, _) => brace.span.open().start(),
+            Self::Literal(name, ..) | Self::Block(name, ..) => unsafe {
                 // Safety: the name of the prop is guaranteed to be non-empty by
                 // `Punctuated::parse_terminated(_nonempty)`
                 name.first().unwrap_unchecked().span().start()
@@ -1094,10 +1346,10 @@ impl Located for HtmlProp {
     }
 
     fn end(&self) -> LineColumn {
-        match &self.kind {
-            HtmlPropKind::Shortcut(brace, _) => brace.span.close().end(),
-            HtmlPropKind::Literal(_, _, lit) => lit.span().end(),
-            HtmlPropKind::Block(_, _, expr) => expr.brace_token.span.close().end(),
+        match self {
+            Self::Shortcut(brace, _) => brace.span.close().end(),
+            Self::Literal(_, _, lit) => lit.span().end(),
+            Self::Block(_, _, expr) => expr.brace_token.span.close().end(),
         }
     }
 }
@@ -0,0 +1,43 @@
+//! The formatting engine behind the `yew-fmt` CLI, for use in other tools.
+//!
+//! Unlike the CLI, the library only formats the HTML macros in the code it's given, leaving
+//! formatting of the rest of the code to `rustfmt`.
+//!
+//! ```no_run
+//! use yew_fmt::{Config, Formatter};
+//!
+//! let src = std::fs::read_to_string("src/main.rs")?;
+//! let mut formatter = Formatter::new(Config::default());
+//! let formatted = formatter.format_source("src/main.rs", &src)?;
+//! # anyhow::Ok(())
+//! ```
+
+// the modules only the CLI builds on aren't part of the library's API
+#[doc(hidden)]
+pub mod audit;
+#[doc(hidden)]
+pub mod changes;
+#[doc(hidden)]
+pub mod codes;
+pub mod config;
+mod dialects;
+mod doc_comments;
+pub mod driver;
+#[doc(hidden)]
+pub mod edition;
+pub mod formatter;
+#[doc(hidden)]
+pub mod glob;
+mod html;
+#[doc(hidden)]
+pub mod lint;
+#[doc(hidden)]
+pub mod paste;
+mod props;
+mod source_map;
+mod stylist;
+mod utils;
+
+pub use config::Config;
+pub use formatter::Formatter;
+pub use utils::{FileLines, KVPairs, LineRange, Result};
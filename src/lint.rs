@@ -0,0 +1,300 @@
+//! Suggestions for restructuring the HTML that the formatter never applies on its own
+
+use crate::audit::{prop_name, Location};
+use crate::formatter::{is_html_macro, Located};
+use crate::html::*;
+use crate::utils::{parse2_with_ctx, Result};
+use proc_macro2::LineColumn;
+use std::fmt::{self, Display, Formatter};
+use std::rc::Rc;
+use std::slice;
+use syn::{visit::Visit, Expr, Ident, Macro, Stmt};
+
+/// A replacement of the text between 2 positions of the linted file, `start` & `end` being equal
+/// for insertions. Lines are 1-based, columns are 0-based & counted in chars
+#[derive(Clone, Debug)]
+pub struct Edit {
+    pub start: LineColumn,
+    pub end: LineColumn,
+    pub new_text: String,
+}
+
+/// A prop whose value is a closure that spans too many lines and could be hoisted into a `let`
+/// binding before the HTML macro
+#[derive(Clone, Debug)]
+pub struct HoistSuggestion {
+    pub location: Location,
+    pub prop: String,
+    /// number of lines the closure spans
+    pub n_lines: usize,
+    /// name of the suggested binding
+    pub binding: String,
+    /// edits that hoist the closure, sorted by their position
+    pub edits: Vec<Edit>,
+}
+
+impl Display for HoistSuggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the closure passed to `{}` spans {} lines, consider hoisting it into \
+             `let {} = ...;` before the HTML",
+            self.prop, self.n_lines, self.binding
+        )
+    }
+}
+
+/// A file that doesn't end with exactly one line break, as required by `yew.newline_at_eof`
+#[derive(Clone, Debug)]
+pub struct FinalNewlineSuggestion {
+    /// the start of the whitespace at the end of the file
+    pub location: Location,
+    /// number of line breaks the file ends with
+    pub n_newlines: usize,
+    /// the edit replacing the whitespace at the end of the file with a single line break
+    pub edit: Edit,
+}
+
+impl Display for FinalNewlineSuggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.n_newlines {
+            0 => f.write_str("the file doesn't end with a line break"),
+            1 => f.write_str("the file ends with whitespace after its last line break"),
+            n => write!(f, "the file ends with {n} line breaks instead of 1"),
+        }
+    }
+}
+
+/// Any of the suggestions made by the linter
+#[derive(Clone, Debug)]
+pub enum Suggestion {
+    Hoist(HoistSuggestion),
+    FinalNewline(FinalNewlineSuggestion),
+}
+
+impl Suggestion {
+    pub fn location(&self) -> &Location {
+        match self {
+            Self::Hoist(s) => &s.location,
+            Self::FinalNewline(s) => &s.location,
+        }
+    }
+
+    /// The edits that apply the suggestion, sorted by their position
+    pub fn edits(&self) -> &[Edit] {
+        match self {
+            Self::Hoist(s) => &s.edits,
+            Self::FinalNewline(s) => slice::from_ref(&s.edit),
+        }
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hoist(s) => s.fmt(f),
+            Self::FinalNewline(s) => s.fmt(f),
+        }
+    }
+}
+
+/// Returns a suggestion to end `src` with a single line break, unless it already does or is empty
+pub fn final_newline_suggestion(filename: &str, src: &str) -> Option<FinalNewlineSuggestion> {
+    let code = src.trim_end();
+    let trailing = &src[code.len()..];
+    if code.is_empty() || matches!(trailing, "\n" | "\r\n") {
+        return None;
+    }
+    let position = |text: &str| LineColumn {
+        line: text.matches('\n').count() + 1,
+        column: text.rsplit('\n').next().map_or(0, |line| line.chars().count()),
+    };
+    let start = position(code);
+    Some(FinalNewlineSuggestion {
+        location: Location { file: filename.into(), line: start.line, column: start.column },
+        n_newlines: trailing.matches('\n').count(),
+        edit: Edit { start, end: position(src), new_text: "\n".to_owned() },
+    })
+}
+
+/// Returns the suggestions to hoist the closures spanning more than `max_lines` lines out of the
+/// props in the HTML macros of `src`; the macros that fail to be parsed are skipped
+pub fn hoisting_suggestions(
+    filename: &str,
+    src: &str,
+    ext: bool,
+    max_lines: usize,
+) -> Result<Vec<HoistSuggestion>> {
+    let file = syn::parse_file(src)?;
+    let mut finder = StmtMacroFinder::default();
+    finder.visit_file(&file);
+
+    let mut linter =
+        Linter { file: filename.into(), src, max_lines, stmt_start: None, res: vec![] };
+    for (mac, stmt_start) in finder.macros {
+        if let Ok(html) = parse2_with_ctx::<Html>(mac.tokens.clone(), ext) {
+            linter.stmt_start = Some(stmt_start);
+            linter.html(&html);
+        }
+    }
+    Ok(linter.res)
+}
+
+/// Collects the HTML macros along with the start of the innermost statement containing each one,
+/// before which the closures can be hoisted
+#[derive(Default)]
+struct StmtMacroFinder<'ast> {
+    macros: Vec<(&'ast Macro, LineColumn)>,
+    stmt_start: Option<LineColumn>,
+}
+
+impl<'ast> Visit<'ast> for StmtMacroFinder<'ast> {
+    fn visit_stmt(&mut self, i: &'ast Stmt) {
+        let outer = self.stmt_start.replace(i.start());
+        syn::visit::visit_stmt(self, i);
+        self.stmt_start = outer;
+    }
+
+    fn visit_macro(&mut self, i: &'ast Macro) {
+        if is_html_macro(i) {
+            self.macros.push((i, self.stmt_start.unwrap_or_else(|| i.start())))
+        }
+    }
+}
+
+struct Linter<'src> {
+    file: Rc<str>,
+    src: &'src str,
+    max_lines: usize,
+    /// the start of the statement that contains the macro being linted
+    stmt_start: Option<LineColumn>,
+    res: Vec<HoistSuggestion>,
+}
+
+impl Linter<'_> {
+    fn html(&mut self, html: &Html) {
+        if let Html::Tree(tree) = html {
+            self.tree(tree)
+        }
+    }
+
+    fn trees(&mut self, trees: &[HtmlTree]) {
+        trees.iter().for_each(|tree| self.tree(tree))
+    }
+
+    fn tree(&mut self, tree: &HtmlTree) {
+        match tree {
+            HtmlTree::Element(element) => self.element(element),
+            HtmlTree::If(r#if) => self.r#if(r#if),
+            HtmlTree::For(r#for) => self.trees(&r#for.body),
+            HtmlTree::Match(r#match) => r#match.arms.iter().for_each(|arm| self.html(&arm.body)),
+            HtmlTree::Block(_) | HtmlTree::Let(_) => (),
+        }
+    }
+
+    fn element(&mut self, element: &HtmlElement) {
+        let (props, children) = match element {
+            HtmlElement::Fragment(fragment) => return self.trees(&fragment.children),
+            HtmlElement::Dynamic(element) => (&element.props, &element.children),
+            HtmlElement::Literal(element) => (&element.props, &element.children),
+        };
+        self.props(props);
+        self.trees(children)
+    }
+
+    fn r#if(&mut self, r#if: &HtmlIf) {
+        self.trees(&r#if.then_branch);
+        match &r#if.else_branch {
+            Some(HtmlElse::If(_, r#if)) => self.r#if(r#if),
+            Some(HtmlElse::Tree(_, _, trees)) => self.trees(trees),
+            None => (),
+        }
+    }
+
+    fn props(&mut self, props: &[HtmlProp]) {
+        for prop in props {
+            let HtmlPropKind::Block(name, _, block) = &prop.kind else { continue };
+            let [Stmt::Expr(closure @ Expr::Closure(_), None)] = &*block.stmts else { continue };
+            let n_lines = closure.end().line - closure.start().line + 1;
+            if n_lines > self.max_lines {
+                let prop_name = prop_name(&prop.kind);
+                let is_single_ident = name.len() == 1;
+                let mut binding = prop_name.replace('-', "_");
+                if syn::parse_str::<Ident>(&binding).is_err() {
+                    binding.push('_');
+                }
+                let edits = self.edits(&prop.kind, closure, &prop_name, &binding, is_single_ident);
+                self.res.push(HoistSuggestion {
+                    location: Location {
+                        file: self.file.clone(),
+                        line: prop.kind.start().line,
+                        column: prop.kind.start().column,
+                    },
+                    prop: prop_name,
+                    n_lines,
+                    binding,
+                    edits,
+                });
+            }
+        }
+    }
+
+    fn edits(
+        &self,
+        prop: &HtmlPropKind,
+        closure: &Expr,
+        prop_name: &str,
+        binding: &str,
+        is_single_ident: bool,
+    ) -> Vec<Edit> {
+        let Some(stmt_start) = self.stmt_start else { return vec![] };
+        let (Some(stmt_line), Some(closure_line)) =
+            (self.line(stmt_start.line), self.line(closure.start().line))
+        else {
+            return vec![];
+        };
+        let indent = &stmt_line[..stmt_line.len() - stmt_line.trim_start().len()];
+        let closure_indent = closure_line.len() - closure_line.trim_start().len();
+        // the closure's lines are shifted left to be indented relative to the statement
+        let shift = closure_indent.saturating_sub(indent.len());
+        let closure_src = (closure.start().line..=closure.end().line)
+            .filter_map(|line| {
+                let text = self.line(line)?;
+                let start = if line == closure.start().line { closure.start().column } else { 0 };
+                let end = if line == closure.end().line {
+                    closure.end().column
+                } else {
+                    text.chars().count()
+                };
+                let text: String = text.chars().take(end).skip(start).collect();
+                Some(if line == closure.start().line {
+                    text
+                } else {
+                    let indent = text.chars().take(shift).take_while(|c| c.is_whitespace());
+                    text[indent.map(char::len_utf8).sum()..].to_owned()
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let insertion_point = LineColumn { line: stmt_start.line, column: 0 };
+        let replacement = if is_single_ident && binding == prop_name {
+            format!("{{{binding}}}")
+        } else {
+            format!("{prop_name}={{{binding}}}")
+        };
+        vec![
+            Edit {
+                start: insertion_point,
+                end: insertion_point,
+                new_text: format!("{indent}let {binding} = {closure_src};\n"),
+            },
+            Edit { start: prop.start(), end: prop.end(), new_text: replacement },
+        ]
+    }
+
+    /// Returns the contents of the 1-based line `n` without the line break
+    fn line(&self, n: usize) -> Option<&str> {
+        self.src.lines().nth(n.checked_sub(1)?)
+    }
+}
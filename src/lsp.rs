@@ -0,0 +1,259 @@
+//! A minimal language server over stdio, providing formatting of the documents open in an editor,
+//! including unsaved ones
+
+use crate::{run_rustfmt_on_stdin, Cli};
+use anyhow::{bail, Context};
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+use yew_fmt::{formatter::is_snippet, Formatter, LineRange, Result};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Reads a message in the format of the base protocol, returns `None` at the end of the input
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut len = None;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if input.read_line(&mut header).context("failed to read a message header")? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            len = Some(value.trim().parse::<usize>().context("invalid `Content-Length` header")?);
+        }
+    }
+    let Some(len) = len else { bail!("message has no `Content-Length` header") };
+    let mut content = vec![0; len];
+    input.read_exact(&mut content).context("failed to read a message")?;
+    serde_json::from_slice(&content).context("failed to parse a message").map(Some)
+}
+
+fn write_message(out: &mut impl Write, msg: &Value) -> Result {
+    let content = msg.to_string();
+    write!(out, "Content-Length: {}\r\n\r\n{content}", content.len())
+        .and_then(|_| out.flush())
+        .context("failed to write a message")
+}
+
+/// Converts a `file://` URI to a path, returns `None` for other schemes
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut iter = encoded.iter();
+    while let Some(&b) = iter.next() {
+        if b != b'%' {
+            decoded.push(b);
+            continue;
+        }
+        let hex = [*iter.next()?, *iter.next()?];
+        decoded.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+    }
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// Converts an LSP range, with 0-based lines, to the 1-based range of the lines it covers, returns
+/// `None` if it's malformed
+fn range_to_lines(range: &Value) -> Option<LineRange> {
+    let start = range["start"]["line"].as_u64()? as usize + 1;
+    let mut end = range["end"]["line"].as_u64()? as usize + 1;
+    // a range ending at the start of a line doesn't include it
+    if range["end"]["character"].as_u64() == Some(0) && end > start {
+        end -= 1;
+    }
+    Some(LineRange { start, end })
+}
+
+struct Server<'args> {
+    args: &'args Cli,
+    /// contents of the open documents by their URIs
+    documents: HashMap<String, String>,
+    shutdown_requested: bool,
+}
+
+impl Server<'_> {
+    fn on_notification(&mut self, method: &str, params: &Value) {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_owned();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri, text.to_owned());
+            }
+            "textDocument/didChange" => {
+                // the server only supports full document sync
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) {
+                    self.documents.insert(uri, text.to_owned());
+                }
+            }
+            "textDocument/didClose" => drop(self.documents.remove(&uri)),
+            _ => (),
+        }
+    }
+
+    /// Returns the result of the request or a JSON-RPC error code & message
+    fn on_request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "documentFormattingProvider": true,
+                    "documentRangeFormattingProvider": true,
+                },
+                "serverInfo": { "name": "yew-fmt", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/formatting" => self.format(params, None),
+            "textDocument/rangeFormatting" => {
+                let Some(lines) = range_to_lines(&params["range"]) else {
+                    return Err((INVALID_PARAMS, "invalid range".to_owned()));
+                };
+                self.format(params, Some(lines))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unsupported method `{method}`"))),
+        }
+    }
+
+    /// Returns the edits to be applied to the document, `null` if it couldn't be formatted
+    fn format(&self, params: &Value, lines: Option<LineRange>) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let Some(src) = self.documents.get(uri) else {
+            return Err((INVALID_PARAMS, format!("document {uri} isn't open")));
+        };
+        match self.format_document(uri, src, lines) {
+            Ok(Some(out)) if out == *src => Ok(json!([])),
+            Ok(Some(out)) => {
+                let end = json!({ "line": src.lines().count() + 1, "character": 0 });
+                let range = json!({ "start": { "line": 0, "character": 0 }, "end": end });
+                Ok(json!([{ "range": range, "newText": out }]))
+            }
+            Ok(None) => Ok(Value::Null),
+            Err(e) => {
+                eprintln!("Error: failed to format {uri}: {e:?}");
+                Ok(Value::Null)
+            }
+        }
+    }
+
+    /// Returns `None` if the document has syntax errors, in which case they've been reported
+    fn format_document(
+        &self,
+        uri: &str,
+        src: &str,
+        lines: Option<LineRange>,
+    ) -> Result<Option<String>> {
+        let path = uri_to_path(uri);
+        // the document may not be saved yet, or may not belong to a directory at all
+        let config_dir = path.as_deref().and_then(|path| path.parent()).filter(|dir| dir.is_dir());
+        let config = self.args.fetch_config(config_dir).context("failed to fetch the config")?;
+        // like in the CLI, rustfmt can't be limited to specific lines, so it's not run at all then,
+        // nor is it run on snippets, which it would reject
+        let src = if lines.is_some() || is_snippet(src) {
+            src.to_owned()
+        } else {
+            let Some(out) = run_rustfmt_on_stdin(self.args, config_dir, src)? else {
+                return Ok(None);
+            };
+            out
+        };
+
+        let lines = lines.map(|lines| [lines]);
+        let name = path.as_deref().and_then(|path| path.to_str()).unwrap_or(uri);
+        let mut formatter = Formatter::new(config).with_only(self.args.only.clone());
+        let res = formatter.format(name, &src, lines.as_ref().map(|l| l.as_slice()))?;
+        let out = res.emit_error(&mut StandardStream::stderr(ColorChoice::Never))?;
+        Ok(out.map(str::to_owned))
+    }
+}
+
+/// Runs the server until the client asks it to exit or closes the connection
+pub fn run(args: &Cli) -> Result<ExitCode> {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut server = Server { args, documents: HashMap::new(), shutdown_requested: false };
+
+    while let Some(msg) = read_message(&mut stdin)? {
+        // responses to the server's requests are ignored, since it doesn't send any
+        let Some(method) = msg["method"].as_str() else { continue };
+        let params = &msg["params"];
+        if method == "exit" {
+            break;
+        }
+        let Some(id) = msg.get("id") else {
+            server.on_notification(method, params);
+            continue;
+        };
+        let response = match server.on_request(method, params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+        write_message(&mut stdout, &response)?;
+    }
+
+    Ok(if server.shutdown_requested { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_read() {
+        let mut input = "Content-Length: 14\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n\
+                         {\"id\":1,\"a\":2}Content-Length: 2\r\n\r\n{}"
+            .as_bytes();
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "id": 1, "a": 2 })));
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({})));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_messages_rejected() {
+        assert!(read_message(&mut "Content-Type: a\r\n\r\n{}".as_bytes()).is_err());
+        assert!(read_message(&mut "Content-Length: x\r\n\r\n{}".as_bytes()).is_err());
+        assert!(read_message(&mut "Content-Length: 5\r\n\r\n{}".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn uris_converted() {
+        assert_eq!(uri_to_path("file:///src/main.rs"), Some(PathBuf::from("/src/main.rs")));
+        assert_eq!(
+            uri_to_path("file:///my%20app/l%C3%A9.rs"),
+            Some(PathBuf::from("/my app/lé.rs"))
+        );
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+        assert_eq!(uri_to_path("file:///a%2"), None);
+        assert_eq!(uri_to_path("file:///a%zz"), None);
+    }
+
+    #[test]
+    fn ranges_converted() {
+        let range = |start: (u64, u64), end: (u64, u64)| {
+            let pos = |(line, character)| json!({ "line": line, "character": character });
+            range_to_lines(&json!({ "start": pos(start), "end": pos(end) }))
+        };
+        assert_eq!(range((0, 0), (2, 5)), Some(LineRange { start: 1, end: 3 }));
+        // the line the range ends at the start of is excluded, unless it's the only one
+        assert_eq!(range((0, 4), (2, 0)), Some(LineRange { start: 1, end: 2 }));
+        assert_eq!(range((3, 0), (3, 0)), Some(LineRange { start: 4, end: 4 }));
+        assert_eq!(range_to_lines(&json!({ "start": { "line": 0 } })), None);
+    }
+}
@@ -1,25 +1,118 @@
-mod config;
-mod formatter;
-mod html;
-mod utils;
+mod lsp;
+mod modules;
+mod reports;
+mod staged;
+mod walk;
+mod watch;
 
-use anyhow::Context;
-use clap::{ColorChoice as ColorWhen, Parser, ValueEnum};
+use anyhow::{anyhow, bail, Context};
+use clap::{ColorChoice as ColorWhen, Parser, Subcommand, ValueEnum};
 use codespan_reporting::term::termcolor::{
     BufferWriter, Color, ColorChoice, ColorSpec, StandardStream, WriteColor,
 };
-use config::Config;
 use diffy::{create_patch, Line};
-use formatter::Formatter;
+use modules::child_modules;
+use proc_macro2::LineColumn;
+use reports::{
+    diagnostic_message, file_message, github_diagnostic, github_file, print_checkstyle,
+    relative_path, JunitReport, SarifLog, CHECKSTYLE_FOOTER, CHECKSTYLE_HEADER,
+};
+use serde_json::{json, Value};
 use std::{
-    collections::HashMap,
-    fs::write,
-    io::Write,
+    collections::{BTreeSet, HashMap},
+    env,
+    fmt::{self, Display, Formatter as FmtFormatter},
+    fs::{read_to_string, write, File},
+    io::{self, IsTerminal, Read, Seek, Write},
+    ops::ControlFlow,
     path::{Component, Path, PathBuf},
     process::{Command, ExitCode, Stdio},
     str::from_utf8,
+    thread,
+    time::{Duration, Instant},
+};
+use walk::{collect_rust_files, list_rust_files, YewfmtIgnore};
+use yew_fmt::{
+    audit::Audit,
+    changes::{changes_since, ChangeKind, Version},
+    codes,
+    config::{closest_option, crate_edition, Edition, Unknown, OPTIONS},
+    driver::{FormatDriver, RunEnd, SourceFile},
+    formatter::{is_snippet, BlockLayout, MacroStats, Timings},
+    glob,
+    lint::{final_newline_suggestion, hoisting_suggestions, Suggestion},
+    paste::{apply_paste_fixes, paste_fixes},
+    Config, FileLines, Formatter, KVPairs, LineRange, Result,
 };
-use utils::{read_into, write_with_backup, KVPairs, Result};
+
+/// The prefix of the environment variables setting the `yew.*` options
+const ENV_PREFIX: &str = "YEW_FMT_";
+
+/// The classes of failures, each reported with its own exit code for scripts to branch on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// `--check` found code that isn't formatted
+    DiffFound = 1,
+    /// the input couldn't be parsed, either by rustfmt or by `yew-fmt`; the errors have been
+    /// already reported
+    Parse = 2,
+    /// the config files or the command line options are invalid
+    Config = 3,
+    /// reading or writing the files failed
+    Io = 4,
+}
+
+impl Failure {
+    /// Classifies an error returned from the CLI. Errors not marked with [`ConfigError`] are
+    /// considered I/O errors, since all the parse errors are reported without returning them
+    fn of(err: &anyhow::Error) -> Self {
+        match err.downcast_ref::<ConfigError>() {
+            Some(_) => Self::Config,
+            None => Self::Io,
+        }
+    }
+}
+
+impl From<Failure> for ExitCode {
+    fn from(failure: Failure) -> Self {
+        Self::from(failure as u8)
+    }
+}
+
+/// Context of the errors caused by the configuration, which makes them be reported with
+/// [`Failure::Config`]
+#[derive(Debug)]
+struct ConfigError(String);
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut FmtFormatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// like `std::fs::write`, but will also create a `.bk` file
+fn write_with_backup(filename: &str, new_text: impl AsRef<[u8]>) -> Result {
+    let new_text = new_text.as_ref();
+    let mut file =
+        File::options().read(true).write(true).open(filename).context("failed to open the file")?;
+    let mut old_text = vec![];
+    file.read_to_end(&mut old_text).context("failed to read the file")?;
+    if &*old_text != new_text {
+        let backup = Path::new(filename).with_extension("bk");
+        write(&backup, old_text)
+            .with_context(|| format!("failed to create a backup file {:?}", backup.as_os_str()))?;
+        file.rewind().context("failed to rewind the file handle")?;
+        file.set_len(0).context("failed to clear the file")?;
+        file.write_all(new_text).context("failed to write new data to the file")?;
+    }
+    Ok(())
+}
+
+/// like `fs::read`, but allows for reusing allocations
+fn read_into(file: impl AsRef<Path>, dst: &mut Vec<u8>) -> io::Result<()> {
+    dst.clear();
+    File::open(file)?.read_to_end(dst).map(drop)
+}
 
 fn parse_rustfmt_output<'src>(src: &'src str) -> Result<HashMap<&'src str, &'src str>> {
     fn path_like(src: &str) -> bool {
@@ -53,21 +146,96 @@ fn parse_rustfmt_output<'src>(src: &'src str) -> Result<HashMap<&'src str, &'src
     Ok(res)
 }
 
-// `buf` must be passed into the function empty and is guaranteed to be empty after it
-fn print_diff_for_file(
-    buf: &mut Vec<u8>,
+/// Returns the suffix of the plural form of a noun for `n` of it
+const fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// The totals of a run printed with `--verbose`
+#[derive(Default)]
+struct RunSummary {
+    files: usize,
+    /// the files that were reformatted, or would be in `--check` mode
+    changed: usize,
+    macros: MacroStats,
+}
+
+impl RunSummary {
+    fn add(&mut self, stats: MacroStats, changed: bool) {
+        self.files += 1;
+        self.changed += usize::from(changed);
+        self.macros.formatted += stats.formatted;
+        self.macros.skipped += stats.skipped;
+        self.macros.failed += stats.failed;
+    }
+
+    fn print(&self, elapsed: Duration) {
+        let Self { files, changed, macros } = self;
+        eprintln!(
+            "Processed {files} file{} in {:.2}s, {changed} of them changed",
+            plural(*files),
+            elapsed.as_secs_f64()
+        );
+        eprintln!(
+            "View macros: {} formatted, {} skipped, {} left as is due to syntax errors",
+            macros.formatted, macros.skipped, macros.failed
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffStyle {
+    /// `Diff in <file> at line <n>:` before every hunk, as printed by rustfmt
+    Rustfmt,
+    /// `---` & `+++` lines before the hunks of a file & `@@ -l,n +l,n @@` before every hunk, as
+    /// expected by `patch` & `git apply`
+    Unified,
+    /// only the name of the file
+    NamesOnly,
+}
+
+/// Prints the difference between the source and the formatted text in `--check` mode, or only the
+/// name of the file if there's any difference with [`DiffStyle::NamesOnly`]
+fn print_diff(
     out: &mut impl WriteColor,
     file: &str,
+    src: &str,
     new_text: &str,
+    style: DiffStyle,
 ) -> Result {
-    read_into(file, buf).context("failed to read contents of the source file")?;
-    let src = from_utf8(buf).context("the source file is not UTF-8")?;
-    let patch = create_patch(src, new_text);
+    if src == new_text {
+        return Ok(());
+    }
+    if style == DiffStyle::NamesOnly {
+        return writeln!(out, "{file}").context("failed to write a filename");
+    }
 
+    let patch = create_patch(src, new_text);
     let mut color_spec = ColorSpec::new();
+    if style == DiffStyle::Unified {
+        out.set_color(ColorSpec::new().set_bold(true)).context("failed to embolden the header")?;
+        // the paths relative to the current directory get the prefixes added by `git diff`, which
+        // `git apply` strips by default
+        let (old, new) = match relative_path(file) {
+            Some(path) => (format!("a/{path}"), format!("b/{path}")),
+            None => (file.to_owned(), file.to_owned()),
+        };
+        writeln!(out, "--- {old}\n+++ {new}").context("failed to write a diff header")?;
+    }
     for hunk in patch.hunks() {
-        writeln!(out, "Diff in {file} at line {}:", hunk.old_range().start())
-            .context("failed to write a diff header")?;
+        if style == DiffStyle::Unified {
+            out.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)))
+                .context("failed to change diff buffer's color")?;
+            writeln!(out, "@@ -{} +{} @@", hunk.old_range(), hunk.new_range())
+                .context("failed to write a hunk header")?;
+        } else {
+            writeln!(out, "Diff in {file} at line {}:", hunk.old_range().start())
+                .context("failed to write a diff header")?;
+        }
         for line in hunk.lines() {
             let (prefix, color, line) = match *line {
                 Line::Context(line) => (' ', None, line),
@@ -88,24 +256,129 @@ fn print_diff_for_file(
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 enum EmitTarget {
+    /// Overwrite the input files with the formatted code
     Files,
+    /// Print the formatted code to stdout
     Stdout,
+    /// Print the difference between the input and the formatted code
+    Diff,
+    /// Print the lines to be changed in the input as a checkstyle XML report
+    Checkstyle,
+    /// Accepted for compatibility with rustfmt, which prints a JSON report of the changes; falls
+    /// back to `files` with a warning
+    #[value(hide = true)]
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    /// Report the errors to stderr & the differences in the way rustfmt does
+    Human,
+    /// Print a JSON object on its own line to stdout for every diagnostic & for every file that
+    /// is, or would be in `--check` mode, reformatted
+    Json,
+    /// Print a SARIF 2.1 log of the diagnostics & of the lines to be reformatted to stdout, for
+    /// code scanning dashboards
+    Sarif,
+    /// Print GitHub Actions workflow commands to stdout, annotating the code pointed at by the
+    /// diagnostics & the lines to be reformatted on pull requests
+    Github,
+    /// Print a JUnit XML report with a test case for every file to stdout, for CI systems that
+    /// only show test reports, the files to be reformatted failing with the diffs reformatting them
+    Junit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PrintConfig {
+    Default,
+    Minimal,
+    Current,
+}
+
+#[derive(Subcommand)]
+enum Action {
+    /// Print the tree of the blocks of every HTML macro as laid out by the formatter, with their
+    /// widths & positions in the formatted code & why they were broken into several lines
+    Analyze {
+        /// Files to analyze, which are left as they are
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Print the trees as a JSON array with an object for every macro
+        #[arg(long)]
+        json: bool,
+    },
+    /// Report the components that are invoked with differently ordered or differently written
+    /// props across the files, exits with 1 if any are found
+    Audit {
+        /// Files & directories to search for Rust files recursively
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Suggest hoisting the closures that span too many lines out of the props into `let`
+    /// bindings before the HTML & ending the files with a single line break, without changing
+    /// the files; exits with 1 if any suggestions are made
+    Lint {
+        /// Files & directories to search for Rust files recursively
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Maximum number of lines a closure in a prop may span
+        #[arg(long, default_value_t = 5, value_name = "n")]
+        max_closure_lines: usize,
+        /// Print the suggestions as a JSON array, including the edits that apply them
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rewrite the JSX-isms in the HTML macros, like `className=` or `{/* comments */}`, into
+    /// Yew's syntax & format the HTML, for porting the markup of React components
+    FixPaste {
+        /// Files to fix in place
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// List the changes to the formatting made since an older version & the files formatted
+    /// differently by the current version; exits with 1 if any such files are found
+    StyleDiff {
+        /// The version upgraded from, e.g. `0.5.1`
+        #[arg(value_name = "old-version")]
+        since: String,
+        /// Files & directories to search for Rust files recursively
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+        /// Print the changes & the affected files as a JSON object
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Parser)]
 #[command(name = "yew-fmt", author, version, about)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Action>,
+    /// Format only the macros whose path, resolved from the `use` items of the file on a
+    /// best-effort basis, matches this one, e.g. `yew::html`; can be passed several times
+    #[arg(long, next_line_help = true, value_name = "path")]
+    only: Vec<String>,
+    /// Report why each block in the HTML was broken into several lines to stderr
+    #[arg(long, next_line_help = true)]
+    annotate: bool,
     /// Backup any modified files
     #[arg(long, next_line_help = true, requires = "files", conflicts_with = "check")]
     backup: bool,
     /// Run in 'check' mode. Exits with 0 if input is formatted correctly. Exits with 1 and prints
-    /// a diff if formatting is required.
-    #[arg(long, next_line_help = true, requires = "files")]
+    /// a diff if formatting is required. Other failures exit with 2 for parse errors, 3 for config
+    /// errors & 4 for I/O errors.
+    #[arg(long, next_line_help = true)]
     check: bool,
+    /// Print the changes as a unified diff, to be inspected or applied with `git apply`, instead of
+    /// writing the files. Exits with 0 regardless of the changes, unless `--check` is passed too
+    #[arg(long, next_line_help = true, conflicts_with_all = ["emit", "backup"])]
+    diff: bool,
     /// Use colored output (if supported)
     #[arg(long, next_line_help = true, default_value_t, value_name = "when")]
     color: ColorWhen,
-    /// Set options from command line. These settings take priority over .rustfmt.toml
+    /// Set options from command line. These settings take priority over .rustfmt.toml & over the
+    /// `YEW_FMT_*` environment variables
     #[arg(
         long,
         next_line_help = true,
@@ -114,13 +387,17 @@ struct Cli {
         hide_default_value = true
     )]
     config: KVPairs,
-    /// Recursively searches the given path for the rustfmt.toml config file.
-    /// If not found reverts to the input file path
+    /// Read the config from this file, or from the config files directly in this directory,
+    /// instead of looking it up from the directories of the files
     #[arg(long, next_line_help = true, value_name = "path")]
     config_path: Option<PathBuf>,
-    /// Rust edition to use
+    /// Rust edition to use, deciding e.g. whether `async` is a keyword. By default, the edition
+    /// of the crate the files are in, as with `cargo fmt`
+    #[arg(long, next_line_help = true, value_name = "edition")]
+    edition: Option<Edition>,
+    /// The edition of the Style Guide, passed on to rustfmt
     #[arg(long, next_line_help = true, value_name = "edition")]
-    edition: Option<usize>,
+    style_edition: Option<usize>,
     /// What data to emit and how
     #[arg(long, next_line_help = true, default_value = "files", value_name = "what")]
     emit: EmitTarget,
@@ -128,26 +405,218 @@ struct Cli {
     /// Prints the names of files that would be formatted when used with `--check` mode.
     #[arg(long, next_line_help = true, short = 'l')]
     files_with_diff: bool,
+    /// Format only the macros overlapping the specified lines of the input, leaving the rest of
+    /// it intact. The lines are specified in the format of rustfmt's `--file-lines` option:
+    /// `[{"file":"src/lib.rs","range":[7,13]},...]`
+    #[arg(long, next_line_help = true, value_name = "JSON", conflicts_with = "lines")]
+    file_lines: Option<FileLines>,
+    /// Apply the options of the `[profile.<name>]` tables of the config files on top of the others
+    #[arg(long, next_line_help = true, value_name = "name", conflicts_with = "frozen_config")]
+    profile: Option<String>,
+    /// Only warn if the version of yew-fmt doesn't satisfy `yew.required_version`, instead of
+    /// refusing to format
+    #[arg(long, next_line_help = true)]
+    ignore_version: bool,
+    /// Report the unknown `yew.*` options in the config files as errors instead of warnings
+    #[arg(long, next_line_help = true)]
+    strict_config: bool,
+    /// Use only the config provided on the command line, erroring if any config file would be read
+    #[arg(long, next_line_help = true, conflicts_with = "config_path")]
+    frozen_config: bool,
+    /// Format only the macros overlapping the specified lines of every input, leaving the rest of
+    /// it intact
+    #[arg(long, next_line_help = true, value_name = "start:end", value_delimiter = ',')]
+    lines: Option<Vec<LineRange>>,
+    /// How to report the diagnostics & the reformatted files. With `json`, nothing but the
+    /// messages is printed to stdout, e.g. no diffs or formatted code
+    #[arg(
+        long,
+        visible_alias = "output-format",
+        next_line_help = true,
+        default_value = "human",
+        value_name = "fmt"
+    )]
+    message_format: MessageFormat,
+    /// Print a detailed explanation of the errors reported with a code, e.g. `Y0001`
+    #[arg(long, next_line_help = true, value_name = "code", conflicts_with_all = ["files", "check"])]
+    explain: Option<String>,
+    /// List the options that can be set in the config files & with `--config`, with the types of
+    /// their values, their defaults & what they do
+    #[arg(long, next_line_help = true, conflicts_with_all = ["files", "check", "print_config"])]
+    help_config: bool,
+    /// Run a language server over stdio, providing formatting of whole documents & line ranges
+    #[arg(long, next_line_help = true, conflicts_with_all = ["files", "check", "backup"])]
+    lsp: bool,
+    /// Reformat the Rust files in the given files & directories, or in the current directory, as
+    /// they change, until stopped
+    #[arg(
+        long,
+        next_line_help = true,
+        conflicts_with_all = ["check", "lsp", "backup", "lines", "file_lines", "emit"]
+    )]
+    watch: bool,
+    /// Dumps a default or minimal config to the path given as the only file, or to stdout. A
+    /// minimal config only has the options whose values differ from the defaults. `current`
+    /// writes to stdout current config as if formatting the file at the path, after the lookup &
+    /// the merging of the config files. The `yew.*` options follow those of rustfmt
+    #[arg(long, next_line_help = true, value_name = "default|minimal|current")]
+    print_config: Option<PrintConfig>,
+    /// Print the time spent running rustfmt & in every phase of formatting each file to stderr
+    #[arg(long, next_line_help = true)]
+    timings: bool,
     /// Show less output
-    #[arg(long, short, next_line_help = true)]
+    #[arg(long, short, next_line_help = true, conflicts_with = "verbose")]
     quiet: bool,
+    /// Print the names of the files being formatted & the totals of the run to stderr
+    #[arg(long, short, next_line_help = true)]
+    verbose: bool,
+    /// Accepted for compatibility with rustfmt's unstable options, has no effect
+    #[arg(long, hide = true)]
+    unstable_features: bool,
+    /// Format only the given files, leaving out the files of the modules declared in them with
+    /// `mod name;`, which are otherwise formatted along with them
+    #[arg(long, next_line_help = true, conflicts_with = "follow_mods")]
+    skip_children: bool,
+    /// Also format the files of the modules declared with `mod name;` when formatting line
+    /// ranges, in which case they're otherwise left out, since rustfmt isn't run
+    #[arg(long, next_line_help = true)]
+    follow_mods: bool,
+    /// Accepted for compatibility with rustfmt's unstable options, has no effect
+    #[arg(long, hide = true)]
+    error_on_unformatted: bool,
+    /// The path of the file the source read from stdin belongs to, used for config discovery &
+    /// diagnostics; the file itself is never read or written
+    #[arg(long, next_line_help = true, value_name = "path", conflicts_with = "files")]
+    stdin_filename: Option<PathBuf>,
+    /// Format only the Rust files in the current directory that differ from their state at this
+    /// git revision, including the uncommitted changes & the untracked files
+    #[arg(
+        long,
+        next_line_help = true,
+        value_name = "ref",
+        conflicts_with_all = ["files", "stdin_filename", "lsp"]
+    )]
+    changed_since: Option<String>,
+    /// Format the staged contents of the Rust files in the current directory, writing the result
+    /// back to the git index & leaving the unstaged changes as they are, for pre-commit hooks
+    #[arg(
+        long,
+        next_line_help = true,
+        conflicts_with_all = ["files", "stdin_filename", "changed_since", "lsp", "watch", "backup"]
+    )]
+    staged: bool,
+
+    /// Format the Rust files in the subdirectories of the directories given as the inputs too
+    #[arg(long, short, next_line_help = true)]
+    recursive: bool,
 
+    /// Don't skip the hidden directories, `target` & the paths matched by the `.gitignore` &
+    /// `.ignore` files when searching directories for Rust files
+    #[arg(long, next_line_help = true)]
+    no_ignore: bool,
+
+    /// Files to format; directories stand for the Rust files in them & glob patterns, e.g.
+    /// `src/**/*.rs`, for the Rust files they match. If none are provided, the source is read from
+    /// stdin and the formatted code is written to stdout
     files: Vec<PathBuf>,
 }
 
-pub fn main() -> anyhow::Result<ExitCode> {
-    let args = Cli::parse();
-    let color_choice = match args.color {
-        ColorWhen::Auto => ColorChoice::Auto,
-        ColorWhen::Always => ColorChoice::Always,
-        ColorWhen::Never => ColorChoice::Never,
-    };
-    let actual_stdout = BufferWriter::stdout(color_choice);
-    let mut stdout = actual_stdout.buffer();
-    let mut stderr = StandardStream::stderr(color_choice);
-    // for reading files to get the source
-    let mut src_buf = vec![];
+impl Cli {
+    fn color_choice(&self) -> ColorChoice {
+        match self.color {
+            ColorWhen::Auto => ColorChoice::Auto,
+            ColorWhen::Always => ColorChoice::Always,
+            ColorWhen::Never => ColorChoice::Never,
+        }
+    }
+
+    /// termcolor only checks the environment, so the diffs piped into files or other programs,
+    /// like `git apply`, would still be colored with [`Self::color_choice`]
+    fn stdout_color_choice(&self) -> ColorChoice {
+        match self.color_choice() {
+            ColorChoice::Auto if !io::stdout().is_terminal() => ColorChoice::Never,
+            choice => choice,
+        }
+    }
 
+    /// Returns the name under which the source read from stdin is reported
+    fn stdin_name(&self) -> Result<&str> {
+        let Some(path) = &self.stdin_filename else { return Ok("<stdin>") };
+        path.to_str().with_context(|| format!("invalid path: {path:?}"))
+    }
+
+    /// Returns the existing directory from which to start searching for the config of the source
+    /// read from stdin, if a filename for it was provided
+    fn stdin_config_dir(&self) -> Option<&Path> {
+        let dir = self.stdin_filename.as_deref()?.parent()?;
+        Some(if dir.as_os_str().is_empty() { ".".as_ref() } else { dir }).filter(|d| d.is_dir())
+    }
+
+    /// Returns the options set with the `YEW_FMT_*` environment variables, e.g. `yew.html_width`
+    /// for `YEW_FMT_HTML_WIDTH`, followed by those passed with `--config` & `--edition`, which take
+    /// priority. The variables not named after an option are left out, see [`Cli::unknown_env`]
+    fn overrides(&self) -> Vec<(Box<str>, Box<str>)> {
+        let mut overrides: Vec<(Box<str>, Box<str>)> = env::vars_os()
+            .filter_map(|(key, value)| {
+                let name =
+                    format!("yew.{}", key.to_str()?.strip_prefix(ENV_PREFIX)?.to_lowercase());
+                OPTIONS.iter().any(|option| option.name == name).then_some(())?;
+                Some((name.into(), value.into_string().ok()?.into()))
+            })
+            .collect();
+        overrides.sort();
+        overrides.extend(self.config.iter().cloned());
+        if let Some(edition) = self.edition {
+            overrides.push(("edition".into(), edition.to_string().into()));
+        }
+        overrides
+    }
+
+    /// Returns the names of the `yew.*` options, without the prefix, the `YEW_FMT_*` environment
+    /// variables that don't set any option would set, which, like the unknown options of the config
+    /// files, are only reported as errors with `--strict-config`
+    fn unknown_env(&self) -> Vec<String> {
+        env::vars_os()
+            .filter_map(|(key, _)| {
+                let name = key.to_str()?.strip_prefix(ENV_PREFIX)?.to_lowercase();
+                let known = OPTIONS.iter().any(|option| option.name == format!("yew.{name}"));
+                (!known).then_some(name)
+            })
+            .collect()
+    }
+
+    /// Whether the edition is passed on the command line, with `--edition` or `--config`
+    fn edition_passed(&self) -> bool {
+        self.edition.is_some() || self.config.iter().any(|(key, _)| &**key == "edition")
+    }
+
+    /// Reads the config from `--config-path` if it's passed, bypassing the lookup, otherwise looks
+    /// it up starting from `dir`, or from the current directory. With `--frozen-config`, only the
+    /// options passed on the command line apply. Unless passed on the command line, the edition is
+    /// that of the crate `dir` is in, if any, overriding the one set in the config, as with
+    /// `cargo fmt`
+    fn fetch_config(&self, dir: Option<&Path>) -> Result<Config> {
+        let mut config = if self.frozen_config {
+            Config::parse("", &self.overrides())
+        } else if let Some(path) = &self.config_path {
+            Config::load(path, self.profile.as_deref(), &self.overrides())
+        } else {
+            Config::fetch(dir, self.profile.as_deref(), &self.overrides())
+        }?;
+        config.yew.unknown.extend(self.unknown_env().into_iter().map(|name| (name, Unknown)));
+        if !self.edition_passed() {
+            if let Some(edition) = crate_edition(dir.unwrap_or(".".as_ref()))? {
+                config.edition = edition;
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Returns a rustfmt command with the options that apply to it passed on from `args`; `stdin_dir`
+/// is where to search for the config of the input read from stdin, if it is read from stdin, since
+/// rustfmt would search for it in the current directory
+fn rustfmt_command(args: &Cli, stdin_dir: Option<&Path>) -> Result<Command> {
     let mut rustfmt = Command::new("rustfmt");
     rustfmt.arg("--color").arg(args.color.to_string());
     if !args.config.is_empty() {
@@ -164,82 +633,1014 @@ pub fn main() -> anyhow::Result<ExitCode> {
         }
     }
     if let Some(config_path) = &args.config_path {
-        rustfmt.arg("--config-path").arg(config_path);
+        // rustfmt only reads its own config files & fails on a directory without one
+        let rustfmt_config = if config_path.is_dir() {
+            ["rustfmt.toml", ".rustfmt.toml"]
+                .map(|name| config_path.join(name))
+                .into_iter()
+                .find(|path| path.is_file())
+        } else {
+            Some(config_path.clone())
+                .filter(|path| !path.ends_with("yew-fmt.toml") && !path.ends_with("Cargo.toml"))
+        };
+        if let Some(rustfmt_config) = rustfmt_config {
+            rustfmt.arg("--config-path").arg(rustfmt_config);
+        }
+    } else if let Some(dir) = stdin_dir {
+        if let Some(config_path) = Config::find(Some(dir))? {
+            rustfmt.arg("--config-path").arg(config_path);
+        }
     }
     if let Some(edition) = args.edition {
         rustfmt.arg("--edition").arg(edition.to_string());
     }
+    if let Some(style_edition) = args.style_edition {
+        rustfmt.arg("--style-edition").arg(style_edition.to_string());
+    }
+    Ok(rustfmt)
+}
+
+/// Returns rustfmt's output for `files`, or for `stdin_src` if there are none, or `None` if it
+/// failed, in which case its errors have been already reported
+fn run_rustfmt(args: &Cli, files: &[&Path], stdin_src: &str) -> Result<Option<String>> {
+    if files.is_empty() {
+        return run_rustfmt_on_stdin(args, args.stdin_config_dir(), stdin_src);
+    }
+    if args.edition_passed() {
+        return run_rustfmt_with_edition(args, None, files, stdin_src, None);
+    }
+    // as with `cargo fmt`, the files of each crate are formatted with the edition of the crate
+    let mut editions = HashMap::new();
+    let mut groups: Vec<(Option<Edition>, Vec<&Path>)> = vec![];
+    for &file in files {
+        let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(".".as_ref());
+        let edition = match editions.get(dir) {
+            Some(&edition) => edition,
+            None => {
+                let edition = crate_edition(dir)?;
+                editions.insert(dir, edition);
+                edition
+            }
+        };
+        match groups.iter_mut().find(|(group, _)| *group == edition) {
+            Some((_, group)) => group.push(file),
+            None => groups.push((edition, vec![file])),
+        }
+    }
+    let mut out = String::new();
+    for (edition, files) in groups {
+        let Some(stdout) = run_rustfmt_with_edition(args, None, &files, stdin_src, edition)? else {
+            return Ok(None);
+        };
+        out.push_str(&stdout);
+    }
+    Ok(Some(out))
+}
+
+/// Returns rustfmt's output for `src`, formatted with the config & the edition of the crate found
+/// from `dir` instead of from `--stdin-filename`, or from the current directory if there's none,
+/// or `None` if rustfmt failed, in which case its errors have been already reported
+fn run_rustfmt_on_stdin(args: &Cli, dir: Option<&Path>, src: &str) -> Result<Option<String>> {
+    let edition =
+        if args.edition_passed() { None } else { crate_edition(dir.unwrap_or(".".as_ref()))? };
+    run_rustfmt_with_edition(args, dir, &[], src, edition)
+}
+
+/// Same as [`run_rustfmt`], passing `edition`, if any, to rustfmt & searching for the config of
+/// the source read from stdin from `stdin_dir`
+fn run_rustfmt_with_edition(
+    args: &Cli,
+    stdin_dir: Option<&Path>,
+    files: &[&Path],
+    stdin_src: &str,
+    edition: Option<Edition>,
+) -> Result<Option<String>> {
+    let mut rustfmt = rustfmt_command(args, stdin_dir.filter(|_| files.is_empty()))?;
+    if let Some(edition) = edition {
+        rustfmt.arg("--edition").arg(edition.to_string());
+    }
     rustfmt.args(["--emit", "stdout"]);
     if args.quiet {
         rustfmt.arg("-q");
     }
-    let rustfmt = rustfmt
-        .args(&args.files)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .context("failed to run rustfmt")?;
+    let start = Instant::now();
+    let rustfmt = thread::scope(|s| {
+        let mut rustfmt = rustfmt
+            .args(files)
+            .stdin(if files.is_empty() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut input) = rustfmt.stdin.take() {
+            // written from another thread to avoid a deadlock in case rustfmt's output doesn't
+            // fit into the pipe's buffer; errors will be reported by rustfmt itself
+            s.spawn(move || input.write_all(stdin_src.as_bytes()));
+        }
+        rustfmt.wait_with_output()
+    })
+    .context("failed to run rustfmt")?;
+    if args.timings {
+        // rustfmt formats all the files in one run, so its time can't be split between them
+        let n_files = files.len().max(1);
+        eprintln!("rustfmt: {:.2?} for {n_files} file{}", start.elapsed(), plural(n_files));
+    }
 
     from_utf8(&rustfmt.stderr)
         .context("failed to parse rustfmt's stderr")?
         .lines()
-        .skip_while(|&l| l == "Warning: Unknown configuration option `yew`")
+        .skip_while(|&l| {
+            l == "Warning: Unknown configuration option `yew`"
+                || l == "Warning: Unknown configuration option `profile`"
+        })
         .for_each(|l| eprintln!("{l}"));
 
     if !rustfmt.status.success() {
-        return Ok(ExitCode::FAILURE);
+        return Ok(None);
     }
+    String::from_utf8(rustfmt.stdout).context("failed to parse rustfmt's output").map(Some)
+}
 
-    let config = Config::fetch(args.config_path.as_deref(), &*args.config)
-        .context("failed to fetch the config")?;
-    for key in config.yew.unknown.keys() {
-        eprintln!("Warning: Unknown configuration option `yew.{key}`");
+/// Hands `--print-config` over to rustfmt, exiting with its exit code
+fn print_config(args: &Cli, kind: PrintConfig) -> Result<ExitCode> {
+    let kind_name = match kind {
+        PrintConfig::Default => "default",
+        PrintConfig::Minimal => "minimal",
+        PrintConfig::Current => "current",
+    };
+    // rustfmt prints its own options, or writes them to the path given as the only file
+    let stdin_dir = args.stdin_config_dir().filter(|_| args.files.is_empty());
+    let rustfmt = rustfmt_command(args, stdin_dir)?
+        .args(["--print-config", kind_name])
+        .args(&args.files)
+        .stderr(Stdio::inherit())
+        .output()
+        .context("failed to run rustfmt")?;
+    if !rustfmt.status.success() {
+        return Ok(rustfmt
+            .status
+            .code()
+            .map_or(ExitCode::FAILURE, |code| ExitCode::from(code.clamp(0, 255) as u8)));
     }
-    let mut formatter = Formatter::new(config);
     let rustfmt_stdout = from_utf8(&rustfmt.stdout).context("failed to parse rustfmt's output")?;
+    print!("{rustfmt_stdout}");
 
-    if args.files.is_empty() {
-        let Some(out) = formatter
-            .format("<stdin>", rustfmt_stdout)
-            .context("failed to parse the input")?
-            .emit_error(&mut stderr)
-            .context("failed to print a syntax error in the input")?
-        else {
-            return Ok(ExitCode::FAILURE);
+    let default = Config::default().yew.options();
+    let options = match (kind, args.files.first()) {
+        (PrintConfig::Default, _) => default,
+        (PrintConfig::Current, Some(file)) => FileConfigs::default().get(args, file)?.yew.options(),
+        (PrintConfig::Current, None) => args.fetch_config(args.stdin_config_dir())?.yew.options(),
+        (PrintConfig::Minimal, _) => {
+            let mut options = args.fetch_config(None)?.yew.options();
+            options.retain(|option| !default.contains(option));
+            options
+        }
+    };
+    let mut table = String::new();
+    if !options.is_empty() {
+        // separated from rustfmt's options if they're printed too
+        if !rustfmt_stdout.trim().is_empty() || !args.files.is_empty() {
+            table.push('\n');
+        }
+        table.push_str("[yew]\n");
+        for (key, value) in options {
+            table.push_str(&format!("{key} = {value}\n"));
+        }
+    }
+    match (kind, args.files.first()) {
+        (PrintConfig::Default | PrintConfig::Minimal, Some(path)) => {
+            let mut file = File::options()
+                .append(true)
+                .open(path)
+                .with_context(|| format!("failed to open {path:?}"))?;
+            file.write_all(table.as_bytes())
+                .with_context(|| format!("failed to write to {path:?}"))?
+        }
+        _ => print!("{table}"),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Lists the options in [`OPTIONS`], each with its default in the config of an empty directory
+fn help_config() -> Result<ExitCode> {
+    let default = Config::default();
+    let yew_defaults: HashMap<&str, String> = default.yew.options().into_iter().collect();
+    for option in OPTIONS {
+        let default = match option.name.strip_prefix("yew.") {
+            Some(name) => yew_defaults.get(name).map_or("none", |value| value).to_owned(),
+            None => match option.name {
+                "tab_spaces" => default.tab_spaces.to_string(),
+                "hard_tabs" => default.hard_tabs.to_string(),
+                "edition" => format!("\"{}\"", default.edition),
+                "newline_style" => format!("\"{:?}\"", default.newline_style),
+                "skip_macro_invocations" => format!("{:?}", default.skip_macro_invocations),
+                // the rest are only read as the defaults of the `yew.*` options
+                "max_width" => yew_defaults["html_width"].clone(),
+                "use_field_init_shorthand" => yew_defaults["use_prop_init_shorthand"].clone(),
+                "format_code_in_doc_comments" => yew_defaults["format_doc_comments"].clone(),
+                name => yew_defaults[name].clone(),
+            },
+        };
+        println!("{}: {}, default: {default}", option.name, option.kind);
+        println!("    {}", option.description);
+    }
+    println!("\nThe other options of rustfmt are handed over to it, see `rustfmt --help=config`");
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Returns the output of a git command
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).output().context("failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("`git {}` failed: {}", args.join(" "), stderr.trim_end());
+    }
+    String::from_utf8(output.stdout).context("failed to parse git's output")
+}
+
+/// Returns the Rust files in the current directory that were changed since the git revision
+/// `since`, including the untracked ones, leaving out the deleted ones. The failures of git, e.g.
+/// because of an unknown revision, are reported as a [`ConfigError`]
+fn changed_files(since: &str) -> Result<Vec<PathBuf>> {
+    let git = |args: &[&str]| git(args).map_err(|e| anyhow!(ConfigError(e.to_string())));
+    let changed = git(&["diff", "--name-only", "--relative", "--diff-filter=d", since, "--"])?;
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+    let mut files: Vec<PathBuf> = changed
+        .lines()
+        .chain(untracked.lines())
+        .filter(|file| file.ends_with(".rs"))
+        .map(PathBuf::from)
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Replaces the directories among the inputs with the Rust files in them, those in their
+/// subdirectories too if `recursive` is set, & the glob patterns with the Rust files they match,
+/// leaving out the ignored files if `ignores` is set. A directory or a pattern left with no files
+/// is reported as a [`ConfigError`]
+fn expand_inputs(inputs: &[PathBuf], recursive: bool, ignores: bool) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for input in inputs {
+        let start = files.len();
+        match input.to_str().filter(|input| glob::is_pattern(input)) {
+            Some(pattern) => {
+                let wildcard = pattern.find(['*', '?']).unwrap_or(pattern.len());
+                let base_len = pattern[..wildcard].rfind(['/', '\\']).map_or(0, |i| i + 1);
+                let base = Path::new(match &pattern[..base_len] {
+                    "" => ".",
+                    base => base,
+                });
+                let pattern: Vec<&str> = glob::components(&pattern[base_len..]).collect();
+                let mut candidates = vec![];
+                if base.is_dir() {
+                    collect_rust_files(base, &mut candidates, ignores)?;
+                }
+                files.extend(candidates.into_iter().filter(|file| {
+                    let Ok(relative) = file.strip_prefix(base) else { return false };
+                    let relative = relative.to_string_lossy();
+                    let components: Vec<&str> = glob::components(&relative).collect();
+                    glob::path_matches(&pattern, &components, false)
+                }));
+                if files.len() == start {
+                    bail!(ConfigError(format!("no Rust files match {input:?}")));
+                }
+            }
+            None if input.is_dir() => {
+                if recursive {
+                    collect_rust_files(input, &mut files, ignores)?;
+                } else {
+                    list_rust_files(input, &mut files, ignores)?;
+                }
+                // with no files, the source would be read from stdin
+                if files.len() == start {
+                    bail!(ConfigError(format!("no Rust files in {input:?}")));
+                }
+            }
+            None => files.push(input.clone()),
+        }
+        files[start..].sort();
+    }
+    Ok(files)
+}
+
+fn audit(args: &Cli, paths: &[PathBuf]) -> Result<ExitCode> {
+    let ext = args
+        .fetch_config(None)
+        .context(ConfigError("failed to fetch the config".to_owned()))?
+        .yew
+        .ext;
+    let mut files = vec![];
+    for path in paths {
+        collect_rust_files(path, &mut files, !args.no_ignore)?;
+    }
+    files.sort();
+
+    let mut audit = Audit::new();
+    for file in &files {
+        let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+        let src = read_to_string(file)
+            .with_context(|| format!("failed to read the contents of {file:?}"))?;
+        if let Err(e) = audit.add_file(name, &src, ext) {
+            eprintln!("Warning: skipping {file:?}: {e}");
+        }
+    }
+
+    let findings = audit.findings();
+    for finding in &findings {
+        println!("{}: {finding}", finding.location());
+    }
+    Ok(if findings.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn lint(args: &Cli, paths: &[PathBuf], max_closure_lines: usize, json: bool) -> Result<ExitCode> {
+    let config =
+        args.fetch_config(None).context(ConfigError("failed to fetch the config".to_owned()))?;
+    let mut files = vec![];
+    for path in paths {
+        collect_rust_files(path, &mut files, !args.no_ignore)?;
+    }
+    files.sort();
+
+    let mut suggestions = vec![];
+    for file in &files {
+        let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+        let src = read_to_string(file)
+            .with_context(|| format!("failed to read the contents of {file:?}"))?;
+        match hoisting_suggestions(name, &src, config.yew.ext, max_closure_lines) {
+            Ok(found) => suggestions.extend(found.into_iter().map(Suggestion::Hoist)),
+            Err(e) => eprintln!("Warning: skipping {file:?}: {e}"),
+        }
+        if config.yew.newline_at_eof {
+            suggestions.extend(final_newline_suggestion(name, &src).map(Suggestion::FinalNewline));
+        }
+    }
+
+    if json {
+        let position = |pos: LineColumn| json!({ "line": pos.line, "column": pos.column });
+        let suggestions: Vec<_> = suggestions
+            .iter()
+            .map(|s| {
+                let edits = s.edits().iter().map(|edit| {
+                    json!({
+                        "start": position(edit.start),
+                        "end": position(edit.end),
+                        "newText": edit.new_text,
+                    })
+                });
+                json!({
+                    "file": &*s.location().file,
+                    "line": s.location().line,
+                    "column": s.location().column,
+                    "message": s.to_string(),
+                    "edits": edits.collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", Value::from(suggestions));
+    } else {
+        for suggestion in &suggestions {
+            println!("{}: {suggestion}", suggestion.location());
+        }
+    }
+    Ok(if suggestions.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn analyze(args: &Cli, files: &[PathBuf], json: bool) -> Result<ExitCode> {
+    fn to_json(block: &BlockLayout) -> Value {
+        let position = |pos: LineColumn| json!({ "line": pos.line, "column": pos.column });
+        json!({
+            "start": position(block.start),
+            "end": position(block.end),
+            "width": block.width,
+            "broken": block.width.is_none(),
+            "reason": block.break_reason.map(|reason| reason.to_string()),
+            "children": block.children.iter().map(to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    fn print_tree(file: &str, block: &BlockLayout, depth: usize) {
+        let LineColumn { line, column } = block.start;
+        let layout = match (block.width, block.break_reason) {
+            (Some(width), _) => format!("on one line, {width} wide"),
+            (None, Some(reason)) => reason.to_string(),
+            (None, None) => "block broken".to_owned(),
         };
-        print!("{out}");
-        return Ok(ExitCode::SUCCESS);
-    }
-    let rustfmt_output =
-        parse_rustfmt_output(rustfmt_stdout).context("failed to parse rustfmt output")?;
-    for (&file, &src) in &rustfmt_output {
-        let Some(out) = formatter
-            .format(file, src)
-            .with_context(|| format!("failed to parse {file:?}"))?
-            .emit_error(&mut stderr)
-            .with_context(|| format!("failed to print a syntax error in {file:?}"))?
-        else {
-            return Ok(ExitCode::FAILURE);
+        let indent = "  ".repeat(depth);
+        println!("{indent}{file}:{line}:{}: {layout}", column + 1);
+        for child in &block.children {
+            print_tree(file, child, depth + 1);
+        }
+    }
+
+    let config =
+        args.fetch_config(None).context(ConfigError("failed to fetch the config".to_owned()))?;
+    let mut formatter = Formatter::new(config).with_layouts(true).with_only(args.only.clone());
+    let mut stderr = StandardStream::stderr(ColorChoice::Auto);
+    let mut macros = vec![];
+    for file in files {
+        let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+        let src = read_to_string(file)
+            .with_context(|| format!("failed to read the contents of {file:?}"))?;
+        let res = formatter.format(name, &src, None)?;
+        res.emit_diagnostics(&mut stderr)
+            .with_context(|| format!("failed to print the syntax errors in {file:?}"))?;
+        for layout in res.layouts() {
+            if json {
+                macros.push(json!({ "file": name, "root": to_json(layout) }));
+            } else {
+                print_tree(name, layout, 0);
+            }
+        }
+    }
+    if json {
+        println!("{}", Value::from(macros));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn fix_paste(args: &Cli, files: &[PathBuf]) -> Result<ExitCode> {
+    let config =
+        args.fetch_config(None).context(ConfigError("failed to fetch the config".to_owned()))?;
+    let mut formatter = Formatter::new(config);
+    let mut failed = false;
+    for file in files {
+        let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+        let src = read_to_string(file)
+            .with_context(|| format!("failed to read the contents of {file:?}"))?;
+        let fixes = match paste_fixes(name, &src) {
+            Ok(fixes) => fixes,
+            Err(e) => {
+                eprintln!("Error: failed to parse {file:?}: {e}");
+                failed = true;
+                continue;
+            }
         };
+        for fix in &fixes {
+            eprintln!("{}: {fix}", fix.location);
+        }
+        let fixed = apply_paste_fixes(&src, &fixes);
+        // the JSX-isms that aren't rewritten are reported by the formatter, in which case the
+        // fixes are still written
+        let out = formatter.format_source(name, &fixed).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            failed = true;
+            fixed
+        });
+        if out != src {
+            write(file, out).with_context(|| format!("failed to write to {file:?}"))?;
+        }
+    }
+    Ok(if failed { Failure::Parse.into() } else { ExitCode::SUCCESS })
+}
 
-        if args.check {
-            read_into(file, &mut src_buf)
-                .with_context(|| format!("failed to read the contents of {file:?}"))?;
-            if args.files_with_diff {
-                if src_buf != out.as_bytes() {
-                    write!(stdout, "{file}").context("failed to write a filename to stdout")?;
+/// A file formatted differently by the current version than by an older one
+struct AffectedFile {
+    name: String,
+    /// whether the current version reformats the file, assuming it was formatted by the older one
+    reformatted: bool,
+    /// the options whose old defaults format the file differently than the current ones
+    defaults: Vec<&'static str>,
+}
+
+fn style_diff(args: &Cli, since: &str, paths: &[PathBuf], json: bool) -> Result<ExitCode> {
+    let since = Version::parse(since)
+        .with_context(|| ConfigError(format!("invalid version: `{since}`")))?;
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))?;
+    let changes = changes_since(since, current)
+        .with_context(|| ConfigError(format!("failed to list the changes since {since}")))?;
+    let config =
+        args.fetch_config(None).context(ConfigError("failed to fetch the config".to_owned()))?;
+    let mut old_configs = vec![];
+    for change in &changes {
+        if let ChangeKind::Default { option, old } = change.kind {
+            let mut old_config = config.clone();
+            old_config.yew.apply_overrides([(option, old)])?;
+            old_configs.push((option, Formatter::new(old_config)));
+        }
+    }
+    let mut formatter = Formatter::new(config);
+    let mut files = vec![];
+    for path in paths {
+        collect_rust_files(path, &mut files, !args.no_ignore)?;
+    }
+    files.sort();
+
+    let mut affected = vec![];
+    for file in &files {
+        let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+        let src = read_to_string(file)
+            .with_context(|| format!("failed to read the contents of {file:?}"))?;
+        let formatted = match formatter.format_source(name, &src) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("Warning: skipping {file:?}: {e}");
+                continue;
+            }
+        };
+        let mut defaults = vec![];
+        for (option, old_formatter) in &mut old_configs {
+            if old_formatter.format_source(name, &src).is_ok_and(|old| old != formatted) {
+                defaults.push(*option);
+            }
+        }
+        let reformatted = formatted != src;
+        if reformatted || !defaults.is_empty() {
+            affected.push(AffectedFile { name: name.to_owned(), reformatted, defaults });
+        }
+    }
+
+    if json {
+        let changes: Vec<_> = changes
+            .iter()
+            .map(|change| {
+                let (kind, option, old) = match change.kind {
+                    ChangeKind::Default { option, old } => ("default", Some(option), Some(old)),
+                    ChangeKind::Layout => ("layout", None, None),
+                };
+                json!({
+                    "release": change.release.to_string(),
+                    "kind": kind,
+                    "option": option,
+                    "oldDefault": old,
+                    "description": change.description,
+                })
+            })
+            .collect();
+        let files: Vec<_> = affected
+            .iter()
+            .map(|file| {
+                json!({
+                    "file": file.name,
+                    "reformatted": file.reformatted,
+                    "defaults": file.defaults,
+                })
+            })
+            .collect();
+        println!("{}", json!({ "since": since.to_string(), "changes": changes, "files": files }));
+    } else {
+        println!("Changes to the formatting since {since}:");
+        for change in &changes {
+            println!("- {change}");
+        }
+        if !affected.is_empty() {
+            println!("Files formatted differently by {current}:");
+        }
+        for file in &affected {
+            match (&*file.defaults, file.reformatted) {
+                ([], _) => println!("- {}", file.name),
+                (defaults, reformatted) => println!(
+                    "- {}{}, depends on the defaults of `{}`",
+                    file.name,
+                    if reformatted { " (reformatted)" } else { "" },
+                    defaults.join("`, `")
+                ),
+            }
+        }
+    }
+    Ok(if affected.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+pub fn main() -> ExitCode {
+    let mut args = match Cli::try_parse() {
+        Ok(args) => args,
+        // `--help` & `--version` are also reported as errors
+        Err(err) if !err.use_stderr() => err.exit(),
+        Err(err) => {
+            _ = err.print();
+            return Failure::Config.into();
+        }
+    };
+    // with `--print-config`, the only file is where the config is written to
+    if !args.files.is_empty() && args.print_config.is_none() {
+        match expand_inputs(&args.files, args.recursive, !args.no_ignore) {
+            Ok(files) => args.files = files,
+            Err(err) => {
+                eprintln!("Error: {err:?}");
+                return Failure::of(&err).into();
+            }
+        }
+    }
+    if let Some(since) = &args.changed_since {
+        match changed_files(since) {
+            // with no files, the source would be read from stdin
+            Ok(files) if files.is_empty() => return ExitCode::SUCCESS,
+            Ok(files) => args.files = files,
+            Err(err) => {
+                eprintln!("Error: {err:?}");
+                return Failure::of(&err).into();
+            }
+        }
+    }
+    let res = if args.lsp {
+        lsp::run(&args)
+    } else if args.watch {
+        watch::run(&args)
+    } else if args.staged {
+        staged::run(&args)
+    } else if let Some(code) = &args.explain {
+        explain(code)
+    } else if args.help_config {
+        help_config()
+    } else if let Some(kind) = args.print_config {
+        print_config(&args, kind)
+    } else {
+        match &args.command {
+            Some(Action::Analyze { files, json }) => analyze(&args, files, *json),
+            Some(Action::Audit { paths }) => audit(&args, paths),
+            Some(Action::Lint { paths, max_closure_lines, json }) => {
+                lint(&args, paths, *max_closure_lines, *json)
+            }
+            Some(Action::FixPaste { files }) => fix_paste(&args, files),
+            Some(Action::StyleDiff { since, paths, json }) => {
+                style_diff(&args, since, paths, *json)
+            }
+            None => format(&args),
+        }
+    };
+    res.unwrap_or_else(|err| {
+        eprintln!("Error: {err:?}");
+        Failure::of(&err).into()
+    })
+}
+
+fn explain(code: &str) -> Result<ExitCode> {
+    let Some(code) = codes::find(code) else {
+        bail!(ConfigError(format!("`{code}` isn't a code of a yew-fmt error")));
+    };
+    println!("{}: {}\n\n{}", code.code, code.title, code.explanation);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The configs of the formatted files, fetched from their directories, unless `--config-path` or
+/// `--frozen-config` is passed, & cached by directory
+#[derive(Default)]
+struct FileConfigs {
+    by_dir: HashMap<PathBuf, Config>,
+    /// the unknown options already reported, e.g. in a config shared by several directories
+    warned: BTreeSet<String>,
+    /// the `yew.required_version`s already warned about with `--ignore-version`
+    warned_versions: BTreeSet<String>,
+}
+
+impl FileConfigs {
+    fn get(&mut self, args: &Cli, file: &Path) -> Result<&Config> {
+        let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(".".as_ref());
+        if !self.by_dir.contains_key(dir) {
+            let config = args
+                .fetch_config(Some(dir))
+                .with_context(|| ConfigError(format!("failed to fetch the config of {file:?}")))?;
+            self.check_unknown(args, &config)?;
+            self.check_version(args, &config)?;
+            self.by_dir.insert(dir.to_owned(), config);
+        }
+        Ok(&self.by_dir[dir])
+    }
+
+    /// Reports the unknown `yew.*` options of a config, along with the known ones they're likely
+    /// misspellings of, as warnings, or as a [`ConfigError`] with `--strict-config`
+    fn check_unknown(&mut self, args: &Cli, config: &Config) -> Result {
+        let mut unknown: Vec<&String> = config.yew.unknown.keys().collect();
+        unknown.sort();
+        for key in unknown {
+            if !self.warned.insert(key.clone()) {
+                continue;
+            }
+            let suggestion = closest_option(key)
+                .map_or_else(String::new, |option| format!(", did you mean `yew.{option}`?"));
+            if args.strict_config {
+                bail!(ConfigError(format!("unknown configuration option `yew.{key}`{suggestion}")));
+            }
+            eprintln!("Warning: Unknown configuration option `yew.{key}`{suggestion}");
+        }
+        Ok(())
+    }
+
+    /// Refuses to format with a config whose `yew.required_version` isn't satisfied by this
+    /// version of `yew-fmt`, only warning about it with `--ignore-version`
+    fn check_version(&mut self, args: &Cli, config: &Config) -> Result {
+        let Some(required) = &config.yew.required_version else { return Ok(()) };
+        let current = Version::parse(env!("CARGO_PKG_VERSION"))?;
+        if required.matches(current) {
+            return Ok(());
+        }
+        let msg =
+            format!("yew-fmt {current} doesn't satisfy `yew.required_version = \"{required}\"`");
+        if !args.ignore_version {
+            bail!(ConfigError(msg));
+        }
+        if self.warned_versions.insert(required.to_string()) {
+            eprintln!("Warning: {msg}");
+        }
+        Ok(())
+    }
+}
+
+fn format(args: &Cli) -> Result<ExitCode> {
+    let start = Instant::now();
+    let actual_stdout = BufferWriter::stdout(args.stdout_color_choice());
+    let mut stdout = actual_stdout.buffer();
+    let mut stderr = StandardStream::stderr(args.color_choice());
+    // for reading files to get the source
+    let mut src_buf = vec![];
+
+    if args.frozen_config {
+        let file_dirs = args.files.iter().map(|file| {
+            file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(".".as_ref())
+        });
+        for dir in file_dirs.chain(args.stdin_config_dir()).map(Some).chain([None]) {
+            let found = Config::find_all(dir).context("failed to search for config files")?;
+            if let Some(path) = found.first() {
+                bail!(ConfigError(format!(
+                    "found config file {path:?} while running with `--frozen-config`"
+                )));
+            }
+        }
+    }
+
+    // only applies to the source read from stdin, the files are formatted with their own configs,
+    // e.g. with a profile defined only in the config of their directory
+    let config = if !args.files.is_empty() {
+        Ok(Config::default())
+    } else {
+        args.fetch_config(args.stdin_config_dir())
+    }
+    .context(ConfigError("failed to fetch the config".to_owned()))?;
+    let mut configs = FileConfigs::default();
+    // the ignored files aren't even passed to rustfmt, for a syntax error in one, e.g. in a
+    // fixture, to not fail the run
+    let yewfmt_ignore = YewfmtIgnore::find()?;
+    let mut targets: Vec<&Path> = vec![];
+    for file in &args.files {
+        if !configs.get(args, file)?.ignore.is_ignored(file) && !yewfmt_ignore.is_ignored(file) {
+            targets.push(file);
+        }
+    }
+
+    let mut stdin_src = String::new();
+    if args.files.is_empty() {
+        io::stdin().read_to_string(&mut stdin_src).context("failed to read the input")?;
+    }
+    let range_mode = args.lines.is_some() || args.file_lines.is_some();
+    let inputs: Vec<(String, String)> = if range_mode {
+        // rustfmt can't be limited to specific lines on stable, so it's not run at all
+        if args.files.is_empty() {
+            vec![(args.stdin_name()?.to_owned(), stdin_src.clone())]
+        } else {
+            let mut files: Vec<PathBuf> = targets.iter().map(|file| file.to_path_buf()).collect();
+            if args.follow_mods {
+                for file in &targets {
+                    // the modules are in the crate of the file declaring them
+                    let edition = configs.get(args, file)?.edition;
+                    for child in child_modules(file, edition) {
+                        if !files.contains(&child) {
+                            files.push(child);
+                        }
+                    }
                 }
+            }
+            files
+                .iter()
+                .map(|file| {
+                    let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+                    let src = read_to_string(file)
+                        .with_context(|| format!("failed to read the contents of {file:?}"))?;
+                    anyhow::Ok((name.to_owned(), src))
+                })
+                .collect::<Result<_>>()?
+        }
+    } else {
+        // snippets, e.g. the files meant to be `include!`d, would be rejected by rustfmt, so only
+        // their HTML is formatted
+        let mut inputs = vec![];
+        let mut files = vec![];
+        for &file in &targets {
+            // also catches unreadable files, for which rustfmt would exit with the same code as
+            // for syntax errors
+            let src = read_to_string(file)
+                .with_context(|| format!("failed to read the contents of {file:?}"))?;
+            if is_snippet(&src) {
+                let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+                inputs.push((name.to_owned(), src));
             } else {
-                print_diff_for_file(&mut src_buf, &mut stdout, file, out)
-                    .with_context(|| format!("failed to generate a diff for {file:?}"))?;
+                files.push(file);
             }
-            continue;
         }
+        if args.files.is_empty() {
+            let src = if is_snippet(&stdin_src) {
+                stdin_src.clone()
+            } else {
+                let Some(rustfmt_stdout) = run_rustfmt(args, &[], &stdin_src)? else {
+                    return Ok(Failure::Parse.into());
+                };
+                rustfmt_stdout
+            };
+            inputs.push((args.stdin_name()?.to_owned(), src));
+        } else if !files.is_empty() {
+            let Some(rustfmt_stdout) = run_rustfmt(args, &files, &stdin_src)? else {
+                return Ok(Failure::Parse.into());
+            };
+            let outputs =
+                parse_rustfmt_output(&rustfmt_stdout).context("failed to parse rustfmt output")?;
+            // rustfmt formats the modules declared in the files along with them, possibly
+            // reading them from elsewhere, so they're filtered out after it's done
+            let given: Vec<PathBuf> = files.iter().filter_map(|f| f.canonicalize().ok()).collect();
+            inputs.extend(
+                outputs
+                    .into_iter()
+                    .filter(|(file, _)| {
+                        !args.skip_children
+                            || Path::new(file).canonicalize().is_ok_and(|f| given.contains(&f))
+                    })
+                    .map(|(file, src)| (file.to_owned(), src.to_owned())),
+            );
+        }
+        inputs
+    };
 
-        match args.emit {
+    if args.emit == EmitTarget::Json {
+        eprintln!("Warning: `--emit json` isn't supported, falling back to the default");
+    }
+    let emit = match args.emit {
+        EmitTarget::Checkstyle => EmitTarget::Checkstyle,
+        _ if args.check || args.diff => EmitTarget::Diff,
+        EmitTarget::Json => EmitTarget::Files,
+        emit => emit,
+    };
+    // rustfmt only honours `ignore` on nightly & isn't run at all in range mode, so the files,
+    // including the child modules found by rustfmt, are filtered here too; the source read from
+    // stdin is never ignored, as with rustfmt, & is formatted with the config found for it
+    let mut source_files = vec![];
+    for (name, src) in inputs {
+        let config = if args.files.is_empty() {
+            configs.check_unknown(args, &config)?;
+            configs.check_version(args, &config)?;
+            None
+        } else {
+            let config = configs.get(args, name.as_ref())?;
+            if config.ignore.is_ignored(name.as_ref()) || yewfmt_ignore.is_ignored(name.as_ref()) {
+                continue;
+            }
+            Some(config.clone())
+        };
+        let lines = match (&args.lines, &args.file_lines) {
+            (Some(lines), _) => Some(lines.clone()),
+            (_, Some(file_lines)) => Some(file_lines.ranges_of(name.as_ref())),
+            (None, None) => None,
+        };
+        source_files.push(SourceFile { name, src, lines, config });
+    }
+    let inputs = source_files;
+
+    let formatter =
+        Formatter::new(config).with_annotations(args.annotate).with_only(args.only.clone());
+    // the files with syntax errors in their macros are still formatted, with those macros left as
+    // is; the codes of the errors are listed at the end
+    let mut error_codes = BTreeSet::new();
+    let machine_readable = args.message_format != MessageFormat::Human;
+    let mut sarif = SarifLog::default();
+    let mut junit = JunitReport::default();
+    let mut files_changed = false;
+    let checkstyle = emit == EmitTarget::Checkstyle && !machine_readable;
+    let diff_style = if args.files_with_diff {
+        DiffStyle::NamesOnly
+    } else if args.diff {
+        DiffStyle::Unified
+    } else {
+        DiffStyle::Rustfmt
+    };
+    if checkstyle {
+        writeln!(stdout, "{CHECKSTYLE_HEADER}").context("failed to write the checkstyle header")?;
+    }
+    let mut summary = RunSummary::default();
+    let run_end = FormatDriver::new(formatter).run(&inputs, |input, res| {
+        let file = &input.name;
+        if args.verbose {
+            eprintln!("Formatting {file}");
+            let changed = if args.files.is_empty() {
+                stdin_src != res.output()
+            } else {
+                read_to_string(file)
+                    .with_context(|| format!("failed to read the contents of {file:?}"))?
+                    != res.output()
+            };
+            summary.add(res.stats(), changed);
+        }
+        if args.timings {
+            let Timings { syn_parsing, html_parsing, layout, printing } = res.timings();
+            eprintln!(
+                "{file}: {syn_parsing:.2?} parsing Rust, {html_parsing:.2?} parsing HTML, \
+                 {layout:.2?} laying out, {printing:.2?} printing"
+            );
+        }
+        for annotation in res.annotations() {
+            let (line, column) = (annotation.line, annotation.column + 1);
+            eprintln!("{file}:{line}:{column}: note: {}", annotation.reason);
+        }
+        error_codes.extend(res.diagnostics().iter().filter_map(|d| d.code.clone()));
+        if machine_readable {
+            for diagnostic in res.diagnostics() {
+                let message = match args.message_format {
+                    MessageFormat::Sarif => {
+                        sarif.add_diagnostic(file, &res, diagnostic);
+                        continue;
+                    }
+                    MessageFormat::Junit => {
+                        junit.add_diagnostic(&res, diagnostic).with_context(|| {
+                            format!("failed to report a syntax error in {file:?}")
+                        })?;
+                        continue;
+                    }
+                    MessageFormat::Github => github_diagnostic(file, &res, diagnostic),
+                    MessageFormat::Human | MessageFormat::Json => {
+                        diagnostic_message(file, &res, diagnostic)
+                            .with_context(|| {
+                                format!("failed to report a syntax error in {file:?}")
+                            })?
+                            .to_string()
+                    }
+                };
+                writeln!(stdout, "{message}").context("failed to print a message")?;
+            }
+            let out = res.output();
+            let src = if args.files.is_empty() {
+                &stdin_src
+            } else {
+                read_into(file, &mut src_buf)
+                    .with_context(|| format!("failed to read the contents of {file:?}"))?;
+                from_utf8(&src_buf)
+                    .with_context(|| format!("the contents of {file:?} are not UTF-8"))?
+            };
+            // the files that are formatted are passing test cases in the JUnit report
+            if args.message_format == MessageFormat::Junit {
+                junit
+                    .add_file(file, src, out)
+                    .with_context(|| format!("failed to report the changes to {file:?}"))?;
+            }
+            if src == out {
+                return Ok(ControlFlow::Continue(()));
+            }
+            match args.message_format {
+                MessageFormat::Sarif => sarif.add_file(file, src, out),
+                MessageFormat::Junit => (),
+                MessageFormat::Github => {
+                    for message in github_file(file, src, out) {
+                        writeln!(stdout, "{message}").context("failed to print a message")?;
+                    }
+                }
+                MessageFormat::Human | MessageFormat::Json => {
+                    let message = file_message(file, src, out)
+                        .with_context(|| format!("failed to report the changes to {file:?}"))?;
+                    writeln!(stdout, "{message}").context("failed to print a message")?;
+                }
+            }
+            files_changed = true;
+            if emit == EmitTarget::Files && !args.files.is_empty() {
+                if args.backup {
+                    write_with_backup(file, out)
+                        .with_context(|| format!("failed to write to {file:?} with backup"))?;
+                } else {
+                    write(file, out).with_context(|| format!("failed to write to {file:?}"))?;
+                }
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+        let out = res
+            .emit_diagnostics(&mut stderr)
+            .with_context(|| format!("failed to print the syntax errors in {file:?}"))?;
+
+        if args.files.is_empty() {
+            match emit {
+                EmitTarget::Diff => print_diff(&mut stdout, file, &stdin_src, out, diff_style)
+                    .context("failed to generate a diff for the input")?,
+                EmitTarget::Checkstyle => {
+                    files_changed |= print_checkstyle(&mut stdout, file, &stdin_src, out)
+                        .context("failed to generate a checkstyle report for the input")?
+                }
+                // `--emit json` has fallen back to `files` by now
+                EmitTarget::Files | EmitTarget::Stdout | EmitTarget::Json => print!("{out}"),
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        match emit {
+            EmitTarget::Diff | EmitTarget::Checkstyle => {
+                read_into(file, &mut src_buf)
+                    .with_context(|| format!("failed to read the contents of {file:?}"))?;
+                let src = from_utf8(&src_buf)
+                    .with_context(|| format!("the contents of {file:?} are not UTF-8"))?;
+                if emit == EmitTarget::Diff {
+                    print_diff(&mut stdout, file, src, out, diff_style)
+                        .with_context(|| format!("failed to generate a diff for {file:?}"))?;
+                } else {
+                    files_changed |=
+                        print_checkstyle(&mut stdout, file, src, out).with_context(|| {
+                            format!("failed to generate a checkstyle report for {file:?}")
+                        })?;
+                }
+            }
             EmitTarget::Stdout => print!("{file}:\n\n{out}"),
-            EmitTarget::Files => {
+            EmitTarget::Files | EmitTarget::Json => {
                 if args.backup {
                     write_with_backup(file, out)
                         .with_context(|| format!("failed to write to {file:?} with backup"))?;
@@ -251,9 +1652,41 @@ pub fn main() -> anyhow::Result<ExitCode> {
                 }
             }
         }
+        Ok(ControlFlow::Continue(()))
+    })?;
+    if args.verbose {
+        summary.print(start.elapsed());
+    }
+    if run_end != RunEnd::Completed {
+        return Ok(Failure::Parse.into());
     }
 
-    let stdout_written_to = !stdout.is_empty();
+    if checkstyle {
+        writeln!(stdout, "{CHECKSTYLE_FOOTER}").context("failed to write the checkstyle footer")?;
+    }
+    if args.message_format == MessageFormat::Sarif {
+        writeln!(stdout, "{:#}", sarif.finish()).context("failed to write the SARIF log")?;
+    }
+    if args.message_format == MessageFormat::Junit {
+        writeln!(stdout, "{}", junit.finish()).context("failed to write the JUnit report")?;
+    }
+    let diff_found = args.check
+        && if machine_readable || checkstyle { files_changed } else { !stdout.is_empty() };
     actual_stdout.print(&stdout).context("failed to flush stdout")?;
-    Ok(if stdout_written_to { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+    if let Some(first) = error_codes.first().filter(|_| !args.quiet && !machine_readable) {
+        if error_codes.len() > 1 {
+            let listed: Vec<_> = error_codes.iter().map(String::as_str).collect();
+            eprintln!("Some errors have detailed explanations: {}.", listed.join(", "));
+            eprintln!("For more information about an error, try `yew-fmt --explain {first}`.");
+        } else {
+            eprintln!("For more information about this error, try `yew-fmt --explain {first}`.");
+        }
+    }
+    Ok(if !error_codes.is_empty() {
+        Failure::Parse.into()
+    } else if diff_found {
+        Failure::DiffFound.into()
+    } else {
+        ExitCode::SUCCESS
+    })
 }
@@ -0,0 +1,77 @@
+//! Resolution of the files of the modules declared with `mod name;`, the same way rustc does it
+
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+use syn::{Attribute, Expr, ExprLit, Item, ItemMod, Lit, Meta};
+use yew_fmt::{config::Edition, edition::parse_file};
+
+/// Returns the value of a `#[path = "..."]` attribute
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(path), .. }) => Some(path.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// Collects the files of the out-of-line modules declared in `items`, `dir` being the directory
+/// in which the files of the modules are searched for & `path_dir` the one the `#[path]`
+/// attributes are relative to
+fn collect_children(items: &[Item], dir: &Path, path_dir: &Path, dst: &mut Vec<PathBuf>) {
+    for item in items {
+        let Item::Mod(ItemMod { attrs, ident, content, .. }) = item else { continue };
+        let path = path_attr(attrs);
+        let name = ident.to_string();
+        match content {
+            Some((_, items)) => {
+                let dir = dir.join(path.unwrap_or(name));
+                collect_children(items, &dir, &dir, dst);
+            }
+            None => {
+                let file = match path {
+                    Some(path) => path_dir.join(path),
+                    None if dir.join(format!("{name}.rs")).is_file() => {
+                        dir.join(format!("{name}.rs"))
+                    }
+                    None => dir.join(&name).join("mod.rs"),
+                };
+                if file.is_file() {
+                    dst.push(file);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the files of the modules declared in `root` with `mod name;` & in those modules'
+/// files, recursively, all the files being parsed by the rules of `edition`, that of the crate of
+/// `root`. The modules of the files that can't be read or parsed are left out, the errors being
+/// reported once those files are formatted themselves
+pub fn child_modules(root: &Path, edition: Edition) -> Vec<PathBuf> {
+    let mut res = vec![];
+    let mut queue = vec![root.to_owned()];
+    while let Some(file) = queue.pop() {
+        let Ok(src) = read_to_string(&file) else { continue };
+        let Ok(ast) = parse_file(&src, edition) else { continue };
+        let parent = file.parent().unwrap_or(".".as_ref());
+        // the modules of the root & of `mod.rs` files are searched for next to them, those of
+        // other files in the directory named after the file
+        let dir = match file.file_stem() {
+            Some(stem) if file != root && stem != "mod" => parent.join(stem),
+            _ => parent.to_owned(),
+        };
+        let mut children = vec![];
+        collect_children(&ast.items, &dir, parent, &mut children);
+        for child in children {
+            if child != root && !res.contains(&child) {
+                res.push(child.clone());
+                queue.push(child);
+            }
+        }
+    }
+    res
+}
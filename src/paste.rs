@@ -0,0 +1,206 @@
+//! Rewriting of the JSX-isms in HTML macros, for porting the markup of React components pasted
+//! into `html!`
+
+use crate::audit::Location;
+use crate::formatter::HtmlMacroFinder;
+use crate::lint::Edit;
+use crate::utils::Result;
+use proc_macro2::{Delimiter, LineColumn, Spacing, TokenStream, TokenTree};
+use std::fmt::{self, Display, Formatter};
+use syn::visit::Visit;
+
+/// The camelCased props of React's DOM elements that are written in lowercase in HTML, besides
+/// `className`, `htmlFor` & the event handlers
+const LOWERCASED_PROPS: &[&str] = &[
+    "autoComplete",
+    "autoFocus",
+    "autoPlay",
+    "colSpan",
+    "contentEditable",
+    "crossOrigin",
+    "encType",
+    "maxLength",
+    "minLength",
+    "noValidate",
+    "readOnly",
+    "rowSpan",
+    "spellCheck",
+    "srcSet",
+    "tabIndex",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PasteFixKind {
+    /// a prop of an element renamed from its JSX name, e.g. `className` to `class`
+    PropName { old: String, new: String },
+    /// a `{/* comment */}` replaced with the comment itself, since a block can't be empty
+    BracedComment,
+}
+
+/// A JSX-ism found by [`paste_fixes`]
+#[derive(Clone, Debug)]
+pub struct PasteFix {
+    pub location: Location,
+    pub kind: PasteFixKind,
+    pub edit: Edit,
+}
+
+impl Display for PasteFix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PasteFixKind::PropName { old, new } => write!(f, "renamed `{old}` to `{new}`"),
+            PasteFixKind::BracedComment => f.write_str("took the comment out of its braces"),
+        }
+    }
+}
+
+/// Returns the name a prop of a DOM element has in Yew if it's written the way JSX has it
+fn yew_prop_name(jsx: &str) -> Option<String> {
+    match jsx {
+        "className" => Some("class".to_owned()),
+        "htmlFor" => Some("for".to_owned()),
+        _ if LOWERCASED_PROPS.contains(&jsx) => Some(jsx.to_ascii_lowercase()),
+        // event handlers, e.g. `onClick`
+        _ => jsx
+            .strip_prefix("on")
+            .filter(|event| event.starts_with(|c: char| c.is_ascii_uppercase()))
+            .map(|_| jsx.to_ascii_lowercase()),
+    }
+}
+
+struct PasteFixer<'src> {
+    file: &'src str,
+    src: &'src str,
+    /// maps line number to byte offset in `src`
+    line_starts: Vec<usize>,
+    res: Vec<PasteFix>,
+}
+
+impl<'src> PasteFixer<'src> {
+    fn new(file: &'src str, src: &'src str) -> Self {
+        let line_starts = [0]
+            .into_iter()
+            .chain(src.char_indices().filter_map(|(i, c)| (c == '\n').then_some(i + 1)))
+            .collect();
+        Self { file, src, line_starts, res: vec![] }
+    }
+
+    fn byte_offset(&self, pos: LineColumn) -> usize {
+        let start = self.line_starts[pos.line - 1];
+        self.src[start..].char_indices().nth(pos.column).map_or(self.src.len(), |(i, _)| start + i)
+    }
+
+    fn push(&mut self, start: LineColumn, end: LineColumn, kind: PasteFixKind, new_text: String) {
+        self.res.push(PasteFix {
+            location: Location { file: self.file.into(), line: start.line, column: start.column },
+            kind,
+            edit: Edit { start, end, new_text },
+        });
+    }
+
+    /// Looks for the JSX-isms among the tokens of an HTML macro. Only the props of the elements
+    /// named in lowercase are renamed, since those of components are defined by their authors
+    fn html(&mut self, tokens: TokenStream) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut in_element_tag = false;
+        for (i, token) in tokens.iter().enumerate() {
+            let prev = i.checked_sub(1).and_then(|i| tokens.get(i));
+            let next = tokens.get(i + 1);
+            match token {
+                TokenTree::Punct(p) if p.as_char() == '<' => {
+                    in_element_tag = matches!(
+                        next,
+                        Some(TokenTree::Ident(name))
+                            if name.to_string().starts_with(|c: char| c.is_ascii_lowercase())
+                    );
+                }
+                TokenTree::Punct(p) if p.as_char() == '>' => in_element_tag = false,
+                TokenTree::Ident(name) if in_element_tag => {
+                    let is_prop_name = matches!(
+                        next,
+                        Some(TokenTree::Punct(eq)) if eq.as_char() == '=' && eq.spacing() == Spacing::Alone
+                    ) && !matches!(prev, Some(TokenTree::Punct(p)) if p.as_char() == '-');
+                    let old = name.to_string();
+                    if let Some(new) = yew_prop_name(&old).filter(|_| is_prop_name) {
+                        let span = name.span();
+                        self.push(
+                            span.start(),
+                            span.end(),
+                            PasteFixKind::PropName { old, new: new.clone() },
+                            new,
+                        );
+                    }
+                }
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                    if group.stream().is_empty() {
+                        self.braced_comment(group.span_open().start(), group.span_close().end());
+                    } else {
+                        self.nested_macros(group.stream());
+                    }
+                }
+                TokenTree::Group(group) => self.nested_macros(group.stream()),
+                _ => (),
+            }
+        }
+    }
+
+    /// Replaces a `{/* comment */}` with the comment, an empty block without comments is left
+    /// for the formatter to report
+    fn braced_comment(&mut self, start: LineColumn, end: LineColumn) {
+        let (start_offset, end_offset) = (self.byte_offset(start), self.byte_offset(end));
+        let inner = self.src[start_offset + 1..end_offset - 1].trim();
+        if inner.is_empty() {
+            return;
+        }
+        // a line comment must still be followed by a line break
+        let ends_with_line_comment =
+            inner.lines().last().is_some_and(|line| line.trim_start().starts_with("//"));
+        let new_text = if ends_with_line_comment { format!("{inner}\n") } else { inner.to_owned() };
+        self.push(start, end, PasteFixKind::BracedComment, new_text);
+    }
+
+    /// Looks for the HTML macros in the tokens of a Rust expression, e.g. of a block in the HTML
+    fn nested_macros(&mut self, tokens: TokenStream) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        for (i, token) in tokens.iter().enumerate() {
+            let TokenTree::Group(group) = token else { continue };
+            match &tokens[..i] {
+                [.., TokenTree::Ident(name), TokenTree::Punct(bang)]
+                    if (name == "html" || name == "html_nested") && bang.as_char() == '!' =>
+                {
+                    self.html(group.stream())
+                }
+                _ => self.nested_macros(group.stream()),
+            }
+        }
+    }
+}
+
+/// Returns the JSX-isms in the HTML macros of `src`, sorted by their position, that can be
+/// rewritten into Yew's syntax with [`apply_paste_fixes`]
+pub fn paste_fixes(filename: &str, src: &str) -> Result<Vec<PasteFix>> {
+    let file = syn::parse_file(src)?;
+    let mut finder = HtmlMacroFinder::default();
+    finder.visit_file(&file);
+    let mut fixer = PasteFixer::new(filename, src);
+    for mac in finder.0 {
+        fixer.html(mac.tokens.clone());
+    }
+    fixer.res.sort_by_key(|fix| (fix.edit.start.line, fix.edit.start.column));
+    Ok(fixer.res)
+}
+
+/// Applies the fixes returned by [`paste_fixes`] for the same `src`
+pub fn apply_paste_fixes(src: &str, fixes: &[PasteFix]) -> String {
+    let fixer = PasteFixer::new("", src);
+    let mut res = String::with_capacity(src.len());
+    let mut copied = 0;
+    for fix in fixes {
+        let start = fixer.byte_offset(fix.edit.start);
+        res.push_str(&src[copied..start]);
+        res.push_str(&fix.edit.new_text);
+        copied = fixer.byte_offset(fix.edit.end);
+    }
+    res.push_str(&src[copied..]);
+    res
+}
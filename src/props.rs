@@ -0,0 +1,57 @@
+use crate::formatter::{ChainingRule, FmtBlock, Format, FormatCtx, Located, Spacing};
+use crate::utils::Result;
+use syn::{punctuated::Pair, ExprStruct, FieldValue, Macro, MacroDelimiter};
+
+/// The contents of a `props! { Props { field: value, .. } }` invocation
+pub struct PropsMacro(pub ExprStruct);
+
+/// Only the invocations delimited by braces are formatted, since `rustfmt` formats the rest itself
+pub fn is_props_macro(mac: &Macro) -> bool {
+    matches!(mac.delimiter, MacroDelimiter::Brace(_))
+        && mac.path.segments.last().is_some_and(|name| name.ident == "props")
+}
+
+impl<'src> Format<'src> for PropsMacro {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        let Self(expr) = self;
+        block.add_source(ctx, &expr.path)?;
+        block.add_delimited_block_with_space(
+            ctx,
+            expr.brace_token.span.open(),
+            expr.brace_token.span.close(),
+            Some(Spacing { before: true, between: true, after: true }),
+            ChainingRule::Off,
+            |block, ctx| {
+                for (field, comma) in expr.fields.pairs().map(Pair::into_tuple) {
+                    field.format(block, ctx)?;
+                    if let Some(comma) = comma {
+                        block.add_source(ctx, comma)?;
+                        block.add_sep(ctx, comma.end())?;
+                    }
+                }
+                if let Some(dot2) = &expr.dot2_token {
+                    block.add_source(ctx, dot2)?;
+                    if let Some(rest) = &expr.rest {
+                        rest.format(block, ctx)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<'src> Format<'src> for FieldValue {
+    fn format(&self, block: &mut FmtBlock<'_, 'src>, ctx: &mut FormatCtx<'_, 'src>) -> Result {
+        for attr in &self.attrs {
+            block.add_source(ctx, attr)?;
+            block.add_space(ctx, attr.end())?;
+        }
+        block.add_source(ctx, &self.member)?;
+        if let Some(colon) = self.colon_token {
+            block.add_source(ctx, colon)?;
+            self.expr.format_with_space(block, ctx)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,464 @@
+//! The machine-readable reports of the diagnostics & of the reformatted files, printed instead of
+//! the diffs & the formatted code for CI systems & editor plugins
+
+use crate::{print_diff, DiffStyle};
+use anyhow::Context;
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle, Severity},
+    term::termcolor::NoColor,
+};
+use diffy::{create_patch, Line};
+use serde_json::{json, Value};
+use std::{env::current_dir, io::Write, ops::Range, path::Path};
+use yew_fmt::{codes::ERROR_CODES, formatter::FormatResult, Result};
+
+/// Returns the 1-based line & column of a byte offset in `text`
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    (
+        before.matches('\n').count() + 1,
+        before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1,
+    )
+}
+
+const fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
+
+/// Escapes the characters reserved in the attributes of XML elements
+fn xml_escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+pub const CHECKSTYLE_HEADER: &str =
+    "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<checkstyle version=\"4.3\">";
+pub const CHECKSTYLE_FOOTER: &str = "</checkstyle>";
+
+/// Prints the `<file>` element of the checkstyle report for a file if there's any difference
+/// between the source and the formatted text, with an error for every new line, reported at the
+/// line of the source it replaces, as rustfmt does. Returns whether there's any difference
+pub fn print_checkstyle(
+    out: &mut impl Write,
+    file: &str,
+    src: &str,
+    new_text: &str,
+) -> Result<bool> {
+    if src == new_text {
+        return Ok(false);
+    }
+    writeln!(out, "<file name=\"{}\">", xml_escape(file)).context("failed to write a file tag")?;
+    let patch = create_patch(src, new_text);
+    for hunk in patch.hunks() {
+        let mut line = hunk.old_range().start();
+        // the line of the source at which the current run of changes starts & the number of the
+        // new lines in it
+        let mut run = None;
+        for hunk_line in hunk.lines() {
+            match *hunk_line {
+                Line::Context(_) => {
+                    run = None;
+                    line += 1;
+                }
+                Line::Delete(_) => {
+                    run.get_or_insert((line, 0));
+                    line += 1;
+                }
+                Line::Insert(text) => {
+                    let (start, n_inserted) = run.get_or_insert((line, 0));
+                    let message = xml_escape(text.strip_suffix('\n').unwrap_or(text));
+                    writeln!(
+                        out,
+                        "<error line=\"{}\" severity=\"warning\" message=\"Should be `{message}`\" />",
+                        *start + *n_inserted
+                    )
+                    .context("failed to write an error tag")?;
+                    *n_inserted += 1;
+                }
+            }
+        }
+    }
+    writeln!(out, "</file>").context("failed to write a closing file tag")?;
+    Ok(true)
+}
+
+/// A run of changed lines of a source
+struct Change {
+    /// the byte range of the replaced lines in the source
+    range: Range<usize>,
+    new_text: String,
+}
+
+impl Change {
+    /// Returns the 1-based first & last lines of the source replaced by the change, or the line
+    /// before which the text is inserted if none are
+    fn lines(&self, src: &str) -> (usize, usize) {
+        let (start, _) = position(src, self.range.start);
+        // the range ends at the start of the line after the replaced ones
+        let (end, _) = position(src, self.range.end);
+        (start, end.saturating_sub(1).max(start))
+    }
+}
+
+/// Returns the runs of lines of `src` that differ in `new_text`, along with their replacements
+fn changes(src: &str, new_text: &str) -> Vec<Change> {
+    let line_starts: Vec<usize> = [0]
+        .into_iter()
+        .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+        .chain((!src.ends_with('\n') && !src.is_empty()).then_some(src.len()))
+        .collect();
+    let mut res = vec![];
+    let patch = create_patch(src, new_text);
+    for hunk in patch.hunks() {
+        let old_range = hunk.old_range();
+        // an empty range starts after the line it's placed at
+        let mut line = if old_range.is_empty() { old_range.start() } else { old_range.start() - 1 };
+        // the first line of the run of changes & the text inserted in place of it
+        let mut pending: Option<(usize, String)> = None;
+        let mut flush = |pending: &mut Option<(usize, String)>, line: usize| {
+            if let Some((start, new_text)) = pending.take() {
+                res.push(Change { range: line_starts[start]..line_starts[line], new_text });
+            }
+        };
+        for hunk_line in hunk.lines() {
+            match *hunk_line {
+                Line::Context(_) => {
+                    flush(&mut pending, line);
+                    line += 1;
+                }
+                Line::Delete(_) => {
+                    pending.get_or_insert_with(|| (line, String::new()));
+                    line += 1;
+                }
+                Line::Insert(text) => {
+                    pending.get_or_insert_with(|| (line, String::new())).1 += text
+                }
+            }
+        }
+        flush(&mut pending, line);
+    }
+    res
+}
+
+/// Returns the message printed with `--message-format json` for a diagnostic of `res`
+pub fn diagnostic_message(
+    file: &str,
+    res: &FormatResult,
+    diagnostic: &Diagnostic<()>,
+) -> Result<Value> {
+    let mut rendered = NoColor::new(vec![]);
+    res.emit_diagnostic(diagnostic, &mut rendered)?;
+    let labels: Vec<Value> = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            let (line, column) = position(res.source(), label.range.start);
+            json!({
+                "start": label.range.start,
+                "end": label.range.end,
+                "line": line,
+                "column": column,
+                "primary": label.style == LabelStyle::Primary,
+                "message": label.message,
+            })
+        })
+        .collect();
+    Ok(json!({
+        "type": "diagnostic",
+        "file": file,
+        "severity": severity_name(diagnostic.severity),
+        "code": diagnostic.code,
+        "message": diagnostic.message,
+        "labels": labels,
+        "notes": diagnostic.notes,
+        "rendered": String::from_utf8(rendered.into_inner())?,
+    }))
+}
+
+/// Returns the message printed with `--message-format json` for a file that's reformatted
+pub fn file_message(file: &str, src: &str, new_text: &str) -> Result<Value> {
+    let mut rendered = NoColor::new(vec![]);
+    print_diff(&mut rendered, file, src, new_text, DiffStyle::Rustfmt)?;
+    let changes: Vec<Value> = changes(src, new_text)
+        .into_iter()
+        .map(|c| json!({ "start": c.range.start, "end": c.range.end, "newText": c.new_text }))
+        .collect();
+    Ok(json!({
+        "type": "file",
+        "file": file,
+        "changes": changes,
+        "rendered": String::from_utf8(rendered.into_inner())?,
+    }))
+}
+
+/// The ID of the SARIF rule of the files that aren't formatted
+const UNFORMATTED_RULE: &str = "unformatted";
+
+/// Returns the path of a file relative to the current directory, with `/` as the separator, if
+/// it's in it, since CI systems expect the paths to be relative to the root of the repository
+pub fn relative_path(file: &str) -> Option<String> {
+    let cwd = current_dir().ok()?;
+    let relative = Path::new(file).strip_prefix(cwd).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Returns the URI of a file in a SARIF log
+fn artifact_uri(file: &str) -> String {
+    let uri = match relative_path(file) {
+        Some(relative) => relative,
+        None if Path::new(file).is_absolute() => {
+            let path = file.replace('\\', "/");
+            format!("file://{}{path}", if path.starts_with('/') { "" } else { "/" })
+        }
+        None => file.replace('\\', "/"),
+    };
+    uri.replace(' ', "%20")
+}
+
+/// Collects the diagnostics & the reformatted files of a run into a SARIF 2.1 log
+#[derive(Default)]
+pub struct SarifLog {
+    results: Vec<Value>,
+}
+
+impl SarifLog {
+    pub fn add_diagnostic(&mut self, file: &str, res: &FormatResult, diagnostic: &Diagnostic<()>) {
+        let location = |label: &codespan_reporting::diagnostic::Label<()>| {
+            let (start_line, start_column) = position(res.source(), label.range.start);
+            let (end_line, end_column) = position(res.source(), label.range.end);
+            let mut location = json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": artifact_uri(file) },
+                    "region": {
+                        "startLine": start_line,
+                        "startColumn": start_column,
+                        "endLine": end_line,
+                        "endColumn": end_column,
+                    },
+                },
+            });
+            if !label.message.is_empty() {
+                location["message"] = json!({ "text": label.message });
+            }
+            location
+        };
+        let (primary, secondary): (Vec<_>, Vec<_>) =
+            diagnostic.labels.iter().partition(|label| label.style == LabelStyle::Primary);
+        let mut message = diagnostic.message.clone();
+        for note in &diagnostic.notes {
+            message.push('\n');
+            message.push_str(note);
+        }
+        self.results.push(json!({
+            "ruleId": diagnostic.code,
+            "level": severity_name(diagnostic.severity),
+            "message": { "text": message },
+            "locations": primary.into_iter().map(location).collect::<Vec<_>>(),
+            "relatedLocations": secondary.into_iter().map(location).collect::<Vec<_>>(),
+        }));
+    }
+
+    /// Adds a result for every run of changed lines of a file, with the fix that reformats them
+    pub fn add_file(&mut self, file: &str, src: &str, new_text: &str) {
+        for change in changes(src, new_text) {
+            let (start_line, end_line) = change.lines(src);
+            let region = json!({ "startLine": start_line, "endLine": end_line });
+            let uri = artifact_uri(file);
+            self.results.push(json!({
+                "ruleId": UNFORMATTED_RULE,
+                "level": "warning",
+                "message": { "text": "the code isn't formatted" },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": uri }, "region": region },
+                }],
+                "fixes": [{
+                    "description": { "text": "reformat the code" },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": uri },
+                        "replacements": [{
+                            "deletedRegion": {
+                                "byteOffset": change.range.start,
+                                "byteLength": change.range.len(),
+                            },
+                            "insertedContent": { "text": change.new_text },
+                        }],
+                    }],
+                }],
+            }));
+        }
+    }
+
+    pub fn finish(self) -> Value {
+        let rules = ERROR_CODES.iter().map(|code| {
+            json!({
+                "id": code.code,
+                "shortDescription": { "text": code.title },
+                "fullDescription": { "text": code.explanation },
+            })
+        });
+        let unformatted = json!({
+            "id": UNFORMATTED_RULE,
+            "shortDescription": { "text": "the code isn't formatted" },
+        });
+        let rules: Vec<Value> = [unformatted].into_iter().chain(rules).collect();
+        json!({
+            "version": "2.1.0",
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "yew-fmt",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    },
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }],
+        })
+    }
+}
+
+/// Collects a test case for every file of a run into a JUnit XML report, for the CI systems that
+/// only show test reports: a file to be reformatted is a failed test with the diff reformatting it,
+/// & the errors in a file make it an errored test
+#[derive(Default)]
+pub struct JunitReport {
+    cases: Vec<String>,
+    failures: usize,
+    errors: usize,
+    /// the errors in the file whose test case is yet to be added
+    pending_errors: Vec<String>,
+}
+
+impl JunitReport {
+    pub fn add_diagnostic(&mut self, res: &FormatResult, diagnostic: &Diagnostic<()>) -> Result {
+        let mut rendered = NoColor::new(vec![]);
+        res.emit_diagnostic(diagnostic, &mut rendered)?;
+        let kind = diagnostic.code.as_deref().unwrap_or(severity_name(diagnostic.severity));
+        self.pending_errors.push(format!(
+            "<error message=\"{}\" type=\"{kind}\">{}</error>",
+            xml_escape(&diagnostic.message),
+            xml_escape(&String::from_utf8_lossy(rendered.get_ref())),
+        ));
+        Ok(())
+    }
+
+    /// Adds the test case of a file, with the errors added since the previous file
+    pub fn add_file(&mut self, file: &str, src: &str, new_text: &str) -> Result {
+        let name = xml_escape(&relative_path(file).unwrap_or(file.into()));
+        let mut case = format!("    <testcase name=\"{name}\" classname=\"yew-fmt\"");
+        if src == new_text && self.pending_errors.is_empty() {
+            case.push_str(" />");
+            self.cases.push(case);
+            return Ok(());
+        }
+        case.push_str(">\n");
+        if src != new_text {
+            let mut diff = NoColor::new(vec![]);
+            print_diff(&mut diff, file, src, new_text, DiffStyle::Unified)?;
+            case += &format!(
+                "      <failure message=\"the file isn't formatted\" type=\"{UNFORMATTED_RULE}\">{}\
+                 </failure>\n",
+                xml_escape(&String::from_utf8_lossy(diff.get_ref()))
+            );
+            self.failures += 1;
+        }
+        if !self.pending_errors.is_empty() {
+            for error in self.pending_errors.drain(..) {
+                case += &format!("      {error}\n");
+            }
+            self.errors += 1;
+        }
+        case.push_str("    </testcase>");
+        self.cases.push(case);
+        Ok(())
+    }
+
+    pub fn finish(self) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<testsuites>\n  <testsuite name=\"yew-fmt\" \
+             tests=\"{}\" failures=\"{}\" errors=\"{}\">\n{}{}  </testsuite>\n</testsuites>",
+            self.cases.len(),
+            self.failures,
+            self.errors,
+            self.cases.join("\n"),
+            if self.cases.is_empty() { "" } else { "\n" },
+        )
+    }
+}
+
+/// Escapes the message of a GitHub Actions workflow command, or one of its properties, which
+/// can't contain `:` or `,` either
+fn github_escape(text: &str, property: bool) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '%' => res.push_str("%25"),
+            '\r' => res.push_str("%0D"),
+            '\n' => res.push_str("%0A"),
+            ':' if property => res.push_str("%3A"),
+            ',' if property => res.push_str("%2C"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+/// Returns the GitHub Actions workflow command annotating the code pointed at by a diagnostic
+pub fn github_diagnostic(file: &str, res: &FormatResult, diagnostic: &Diagnostic<()>) -> String {
+    let mut location =
+        format!("file={}", github_escape(&relative_path(file).unwrap_or(file.into()), true));
+    if let Some(label) = diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary) {
+        let (line, col) = position(res.source(), label.range.start);
+        let (end_line, end_col) = position(res.source(), label.range.end);
+        location += &format!(",line={line},col={col},endLine={end_line},endColumn={end_col}");
+    }
+    if let Some(code) = &diagnostic.code {
+        location += &format!(",title={}", github_escape(code, true));
+    }
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+    let level = match diagnostic.severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "notice",
+    };
+    format!("::{level} {location}::{}", github_escape(&message, false))
+}
+
+/// Returns the GitHub Actions workflow commands annotating the runs of lines of a file that are
+/// to be reformatted, with the formatted code in the messages
+pub fn github_file(file: &str, src: &str, new_text: &str) -> Vec<String> {
+    let file = github_escape(&relative_path(file).unwrap_or(file.into()), true);
+    changes(src, new_text)
+        .into_iter()
+        .map(|change| {
+            let (line, end_line) = change.lines(src);
+            let message = format!("the code isn't formatted, it should be:\n{}", change.new_text);
+            format!(
+                "::warning file={file},line={line},endLine={end_line},title=yew-fmt::{}",
+                github_escape(message.trim_end(), false)
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,128 @@
+//! Lookup of the positions of the tokens in the source code: `proc_macro2` gives them as lines &
+//! columns counted in chars, the code is sliced by byte offsets
+
+use crate::{formatter::Location, utils::Result};
+use anyhow::{bail, Context};
+use proc_macro2::LineColumn;
+
+/// The width of the indentation `indent`, made of spaces & tabs, in columns
+pub fn indent_width(indent: &str, tab_spaces: usize) -> usize {
+    indent.chars().map(|c| if c == '\t' { tab_spaces } else { 1 }).sum()
+}
+
+/// The line tables of a source file, built once per formatted file.
+///
+/// The first line starts after the BOM, if there's one, since `syn` strips it before tokenizing;
+/// the lines returned leave out their line break, be it `\n` or `\r\n`.
+pub struct SourceMap<'src> {
+    src: &'src str,
+    /// the byte offsets of the starts of the lines
+    line_starts: Vec<usize>,
+    /// whether the line is all ASCII, in which case the columns are the same as the byte offsets
+    /// from the start of the line
+    ascii_lines: Vec<bool>,
+}
+
+impl<'src> SourceMap<'src> {
+    pub fn new(src: &'src str) -> Self {
+        let first = if src.starts_with('\u{feff}') { '\u{feff}'.len_utf8() } else { 0 };
+        let mut line_starts = vec![first];
+        line_starts.extend(src.match_indices('\n').map(|(i, _)| i + 1));
+        let ascii_lines = line_starts
+            .iter()
+            .zip(line_starts.iter().skip(1).copied().chain([src.len()]))
+            .map(|(&start, end)| src.as_bytes()[start..end].is_ascii())
+            .collect();
+        Self { src, line_starts, ascii_lines }
+    }
+
+    /// The byte offset of the start of the 1-based `line`
+    pub fn line_start(&self, line: usize) -> Result<usize> {
+        self.line_starts
+            .get(line.wrapping_sub(1))
+            .copied()
+            .with_context(|| format!("line {line} doesn't exist in the source file"))
+    }
+
+    /// The text of the 1-based `line` without its line break, or `None` if there's no such line
+    pub fn line(&self, line: usize) -> Option<&'src str> {
+        let start = *self.line_starts.get(line.wrapping_sub(1))?;
+        let end = self.line_starts.get(line).map_or(self.src.len(), |&next| next - 1);
+        let text = &self.src[start..end];
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    pub fn byte_offset(&self, LineColumn { line, column }: LineColumn) -> Result<usize> {
+        let line_start = self.line_start(line)?;
+        let column = if self.ascii_lines[line - 1] {
+            column
+        } else {
+            self.src[line_start..].chars().take(column).map(char::len_utf8).sum()
+        };
+
+        line_start.checked_add(column).with_context(|| {
+            format!("source position {line}:{column} can't be converted to a byte offset")
+        })
+    }
+
+    pub fn source_code(&self, loc: Location) -> Result<&'src str> {
+        let start = self.byte_offset(loc.start).context("failed to find the start of the span")?;
+        let end = self.byte_offset(loc.end).context("failed to find the end of the span")?;
+        self.src
+            .get(start..end)
+            .with_context(|| format!("byte range {start}..{end} is invalid for the source code"))
+    }
+
+    /// The column of the first token on the 1-based `line`, skipping the block comments before it
+    /// & counting a tab as `tab_spaces` columns
+    pub fn line_indent(&self, line: usize, tab_spaces: usize) -> Result<usize> {
+        enum State {
+            Space,
+            CommentStart,
+            Comment,
+            CommentEnd,
+        }
+        let mut state = State::Space;
+        let mut width = 0;
+
+        let Some(text) = self.line(line) else {
+            bail!("line {line} doesn't exist in the source file")
+        };
+        for ch in text.chars() {
+            width += if ch == '\t' { tab_spaces } else { 1 };
+            match ch {
+                ' ' | '\t' => {
+                    state = match state {
+                        State::Space => continue,
+                        State::CommentStart => State::Space,
+                        State::Comment => continue,
+                        State::CommentEnd => State::Space,
+                    }
+                }
+                '/' => {
+                    state = match state {
+                        State::Space => State::CommentStart,
+                        State::CommentStart => bail!("line {line} of the source file is empty"),
+                        State::Comment => continue,
+                        State::CommentEnd => State::Space,
+                    }
+                }
+                '*' => {
+                    state = match state {
+                        State::Space => continue,
+                        State::CommentStart => State::Comment,
+                        State::Comment => State::CommentEnd,
+                        State::CommentEnd => continue,
+                    }
+                }
+                _ => match state {
+                    State::Space => return Ok(width - 1),
+                    State::CommentStart => return Ok(width - 2),
+                    State::Comment => continue,
+                    State::CommentEnd => continue,
+                },
+            }
+        }
+        bail!("line {line} of the source file is empty")
+    }
+}
@@ -0,0 +1,117 @@
+//! Formatting of the contents of the files staged in git, for pre-commit hooks: the files are read
+//! from & written back to the index, so the unstaged changes are neither formatted nor committed
+
+use crate::{
+    git, print_diff, run_rustfmt, walk::YewfmtIgnore, Cli, DiffStyle, Failure, FileConfigs,
+};
+use anyhow::{bail, Context};
+use codespan_reporting::term::termcolor::StandardStream;
+use std::{
+    fs::{read_to_string, write},
+    io::Write,
+    process::{Command, ExitCode, Stdio},
+};
+use yew_fmt::{formatter::is_snippet, Formatter, Result};
+
+/// Stores `contents` as a blob in the repository, returning its hash
+fn hash_object(file: &str, contents: &str) -> Result<String> {
+    let mut git = Command::new("git")
+        .args(["hash-object", "-w", "--stdin", "--path", file])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run git")?;
+    if let Some(mut stdin) = git.stdin.take() {
+        stdin.write_all(contents.as_bytes()).context("failed to pass a blob to git")?;
+    }
+    let output = git.wait_with_output().context("failed to run git")?;
+    if !output.status.success() {
+        bail!("`git hash-object` failed: {}", String::from_utf8_lossy(&output.stderr).trim_end());
+    }
+    Ok(String::from_utf8(output.stdout).context("failed to parse git's output")?.trim().to_owned())
+}
+
+/// Replaces the staged contents of `file` with `contents`, keeping its mode. `file` is relative to
+/// the current directory, which is `prefix` relative to the root of the repository
+fn stage(prefix: &str, file: &str, contents: &str) -> Result {
+    let entry = git(&["ls-files", "--stage", "--", file])?;
+    let mode = entry.split_whitespace().next().with_context(|| format!("{file} isn't staged"))?;
+    let hash = hash_object(file, contents)?;
+    // unlike the other paths, the one given to `--cacheinfo` is relative to the root
+    git(&["update-index", "--cacheinfo", &format!("{mode},{hash},{prefix}{file}")]).map(drop)
+}
+
+/// Returns the staged contents of a file formatted, or `None` if it couldn't be formatted, in
+/// which case the errors have been already reported
+fn format_staged(
+    args: &Cli,
+    configs: &mut FileConfigs,
+    file: &str,
+    staged: &str,
+) -> Result<Option<String>> {
+    let input = if is_snippet(staged) {
+        staged.to_owned()
+    } else {
+        let Some(out) = run_rustfmt(args, &[], staged)? else { return Ok(None) };
+        out
+    };
+    let config = configs.get(args, file.as_ref())?.clone();
+    let mut formatter = Formatter::new(config).with_only(args.only.clone());
+    let res = formatter.format(file, &input, None)?;
+    let out = res.emit_error(&mut StandardStream::stderr(args.color_choice()))?;
+    Ok(out.map(str::to_owned))
+}
+
+/// Formats the staged Rust files in the current directory, writing the result back to the index.
+/// A file is also rewritten in the working tree if it has no unstaged changes, so that the
+/// formatting doesn't show up as one
+pub fn run(args: &Cli) -> Result<ExitCode> {
+    let staged = git(&["diff", "--cached", "--name-only", "--relative", "--diff-filter=d", "--"])?;
+    let prefix = git(&["rev-parse", "--show-prefix"])?;
+    let prefix = prefix.trim_end_matches('\n');
+    let mut configs = FileConfigs::default();
+    let yewfmt_ignore = YewfmtIgnore::find()?;
+    let mut stdout = StandardStream::stdout(args.stdout_color_choice());
+    let (mut diff_found, mut failed) = (false, false);
+    for file in staged.lines().filter(|file| file.ends_with(".rs")) {
+        if configs.get(args, file.as_ref())?.ignore.is_ignored(file.as_ref())
+            || yewfmt_ignore.is_ignored(file.as_ref())
+        {
+            continue;
+        }
+        let staged = git(&["show", &format!(":./{file}")])?;
+        let Some(out) = format_staged(args, &mut configs, file, &staged)? else {
+            failed = true;
+            continue;
+        };
+        if out == staged {
+            continue;
+        }
+        diff_found = true;
+        if args.check {
+            let style =
+                if args.files_with_diff { DiffStyle::NamesOnly } else { DiffStyle::Rustfmt };
+            print_diff(&mut stdout, file, &staged, &out, style)
+                .with_context(|| format!("failed to generate a diff for {file:?}"))?;
+            continue;
+        }
+
+        stage(prefix, file, &out)
+            .with_context(|| format!("failed to stage the formatted {file:?}"))?;
+        if read_to_string(file).is_ok_and(|current| current == staged) {
+            write(file, &out).with_context(|| format!("failed to write to {file:?}"))?;
+        }
+        if args.files_with_diff {
+            println!("{file}");
+        }
+    }
+
+    Ok(if failed {
+        Failure::Parse.into()
+    } else if diff_found && args.check {
+        Failure::DiffFound.into()
+    } else {
+        ExitCode::SUCCESS
+    })
+}
@@ -0,0 +1,169 @@
+//! Formatting of the CSS in the string literals passed to `stylist`'s `css!` & `style!` macros
+
+use std::{iter::repeat_n, mem::take};
+use syn::Macro;
+
+pub fn is_stylist_macro(mac: &Macro) -> bool {
+    mac.path.segments.last().is_some_and(|name| name.ident == "css" || name.ident == "style")
+}
+
+/// Returns the source of a string literal with its CSS put one declaration, selector or closing
+/// brace per line, each level of nesting being indented by `tab_spaces` more than the previous one,
+/// starting from `indent + tab_spaces`.
+/// Returns `None` if the literal is to be left as is, which is the case for literals that take up
+/// 1 line, have escapes, or CSS in them the formatter doesn't understand
+pub fn format_css_literal(lit: &str, indent: usize, tab_spaces: usize) -> Option<String> {
+    let (prefix, css, suffix) = split_literal(lit)?;
+    if !css.contains('\n') {
+        return None;
+    }
+    let lines = css_lines(css)?;
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut res = String::from(prefix);
+    res.push('\n');
+    for line in lines {
+        if let Some((depth, text)) = line {
+            res.extend(repeat_n(' ', indent + (depth + 1) * tab_spaces));
+            res.push_str(&text);
+        }
+        res.push('\n');
+    }
+    res.extend(repeat_n(' ', indent));
+    res.push_str(suffix);
+    Some(res)
+}
+
+/// Splits the source of a string literal into its opening quote, contents & closing quote.
+/// Non-raw literals with escapes are rejected, since the formatter acts on the literal's source
+fn split_literal(lit: &str) -> Option<(&str, &str, &str)> {
+    let quotes_len = match lit.strip_prefix('r') {
+        Some(rest) => rest.len() - rest.trim_start_matches('#').len() + 1,
+        None if lit.contains('\\') => return None,
+        None => 0,
+    };
+    let prefix_len = quotes_len + 1;
+    let suffix = lit.get(lit.len().checked_sub(quotes_len.max(1))?..)?;
+    let contents = lit.get(prefix_len..lit.len() - suffix.len())?;
+    Some((&lit[..prefix_len], contents, suffix))
+}
+
+/// Lines of the formatted CSS, `None` standing for a blank line
+type Lines = Vec<Option<(usize, String)>>;
+
+#[derive(Default)]
+struct CssLines {
+    lines: Lines,
+    depth: usize,
+    /// the text of the current item, with runs of whitespace collapsed into single spaces
+    cur: String,
+    /// the number of line breaks since the end of the last item
+    n_breaks: usize,
+}
+
+impl CssLines {
+    fn push(&mut self, text: String) {
+        let after_opening =
+            matches!(self.lines.last(), Some(Some((_, last))) if last.ends_with('{'));
+        if take(&mut self.n_breaks) > 1 && !self.lines.is_empty() && !after_opening {
+            self.lines.push(None);
+        }
+        self.lines.push(Some((self.depth, text)));
+    }
+
+    /// Ends the current item, if there's any
+    fn end_item(&mut self) {
+        let text = take(&mut self.cur);
+        let text = text.trim_end();
+        if !text.is_empty() {
+            self.push(text.to_owned());
+        }
+    }
+
+    /// Adds a comment either to the end of the previous line, if it's on the same line in the
+    /// source, to its own line, if there's no current item, or to the current item
+    fn add_comment(&mut self, comment: &str) {
+        if !self.cur.is_empty() {
+            self.cur.push_str(comment);
+        } else if let (0, Some(Some((_, last)))) = (self.n_breaks, self.lines.last_mut()) {
+            last.push(' ');
+            last.push_str(comment);
+        } else {
+            self.push(comment.to_owned());
+        }
+    }
+}
+
+fn css_lines(css: &str) -> Option<Lines> {
+    let mut res = CssLines::default();
+    let mut parens = 0usize;
+    let mut chars = css.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\n' if res.cur.is_empty() => res.n_breaks += 1,
+            _ if c.is_whitespace() => {
+                if !res.cur.is_empty() && !res.cur.ends_with(' ') {
+                    res.cur.push(' ')
+                }
+            }
+            '"' | '\'' => {
+                let len = css[i + 1..].find(c)? + 2;
+                if css[i..i + len].contains(['\\', '\n']) {
+                    return None;
+                }
+                res.cur.push_str(&css[i..i + len]);
+                chars.nth(len - 2);
+            }
+            '/' if chars.next_if(|&(_, c)| c == '*').is_some() => {
+                let len = css[i + 2..].find("*/")? + 4;
+                let comment = &css[i..i + len];
+                if comment.contains('\n') {
+                    return None;
+                }
+                res.add_comment(comment);
+                chars.nth(len - 3);
+            }
+            // `stylist`'s interpolation, its contents are Rust code
+            '$' if chars.next_if(|&(_, c)| c == '{').is_some() => {
+                let len = css[i..].find('}')? + 1;
+                res.cur.push_str(&css[i..i + len]);
+                chars.nth(len - 3);
+            }
+            '(' => {
+                parens += 1;
+                res.cur.push(c)
+            }
+            ')' => {
+                parens = parens.checked_sub(1)?;
+                res.cur.push(c)
+            }
+            '{' if parens == 0 => {
+                let selector = take(&mut res.cur);
+                let selector = selector.trim_end();
+                if selector.is_empty() {
+                    return None;
+                }
+                res.push(format!("{selector} {{"));
+                res.depth += 1;
+            }
+            '}' if parens == 0 => {
+                res.end_item();
+                res.depth = res.depth.checked_sub(1)?;
+                res.n_breaks = 0;
+                res.push("}".to_owned());
+            }
+            ';' if parens == 0 => {
+                res.cur.push(c);
+                res.end_item();
+            }
+            _ => res.cur.push(c),
+        }
+    }
+    if parens != 0 || res.depth != 0 {
+        return None;
+    }
+    res.end_item();
+    Some(res.lines)
+}
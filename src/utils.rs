@@ -1,10 +1,12 @@
-use anyhow::{anyhow, Context};
+use crate::config::{WidthHeuristic, Widths};
+use anyhow::{anyhow, bail, Context};
 use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::Deserialize;
 use std::{
-    fs::{write, File},
-    io::{self, Read, Seek, Write},
+    fmt::Display,
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
 };
 use syn::{
@@ -12,12 +14,17 @@ use syn::{
     parse::{Parse, ParseBuffer, ParseStream, Parser},
     punctuated::Punctuated,
 };
+use unicode_width::UnicodeWidthChar;
 
 pub type Result<T = (), E = anyhow::Error> = std::result::Result<T, E>;
 
 pub trait StrExt {
-    /// Returns the length of the last line of the string, or `None` if the string is 1 line.
-    fn last_line_len(&self) -> Option<usize>;
+    /// Returns the width of the string when printed, as measured by `widths`; a tab takes up
+    /// `tab_spaces` columns, the other control characters don't occupy any width, escape sequences
+    /// are measured as they're written in the source
+    fn width(&self, widths: Widths) -> usize;
+    /// Returns the width of the last line of the string, or `None` if the string is 1 line.
+    fn last_line_len(&self, widths: Widths) -> Option<usize>;
     /// Unchecked version of `split_at`, caller must ensure that `self.is_char_boundary(mid)`
     unsafe fn split_at_unchecked(&self, mid: usize) -> (&str, &str);
     /// Non-panicking version of `split_at`
@@ -25,8 +32,20 @@ pub trait StrExt {
 }
 
 impl StrExt for str {
-    fn last_line_len(&self) -> Option<usize> {
-        self.bytes().rev().enumerate().find_map(|(i, c)| (c == b'\n').then_some(i))
+    fn width(&self, widths: Widths) -> usize {
+        let n_tabs = self.bytes().filter(|&b| b == b'\t').count();
+        let rest = match widths.heuristic {
+            WidthHeuristic::Display => {
+                self.chars().map(|c| UnicodeWidthChar::width(c).unwrap_or(0)).sum()
+            }
+            WidthHeuristic::Chars => self.chars().filter(|c| !c.is_control()).count(),
+            WidthHeuristic::Bytes => self.bytes().filter(|b| !b.is_ascii_control()).count(),
+        };
+        rest + n_tabs * widths.tab_spaces
+    }
+
+    fn last_line_len(&self, widths: Widths) -> Option<usize> {
+        self.rfind('\n').map(|i| self[i + 1..].width(widths))
     }
 
     unsafe fn split_at_unchecked(&self, mid: usize) -> (&str, &str) {
@@ -96,6 +115,74 @@ impl FromStr for KVPairs {
     }
 }
 
+/// An inclusive range of 1-based line numbers, parsed from `start:end`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    /// Returns whether the range has any lines in common with the range `start..=end`
+    pub const fn overlaps(&self, start: usize, end: usize) -> bool {
+        self.start <= end && start <= self.end
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) =
+            s.split_once(':').with_context(|| format!("expected `start:end`, got `{s}`"))?;
+        let start = start.parse().with_context(|| format!("invalid line number: `{start}`"))?;
+        let end = end.parse().with_context(|| format!("invalid line number: `{end}`"))?;
+        if start == 0 || start > end {
+            bail!("invalid line range: `{s}`");
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Line ranges of specific files in the format of rustfmt's `--file-lines` option:
+/// `[{"file":"src/lib.rs","range":[7,13]},...]`
+#[derive(Clone)]
+pub struct FileLines(Box<[(PathBuf, LineRange)]>);
+
+impl FileLines {
+    /// Returns all the ranges specified for `file`
+    pub fn ranges_of(&self, file: &Path) -> Vec<LineRange> {
+        let file = file.canonicalize().ok();
+        self.0
+            .iter()
+            .filter(|(path, _)| path.canonicalize().ok() == file)
+            .map(|(_, range)| *range)
+            .collect()
+    }
+}
+
+impl FromStr for FileLines {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        #[derive(Deserialize)]
+        struct Entry {
+            file: PathBuf,
+            range: (usize, usize),
+        }
+
+        let entries: Vec<Entry> = serde_json::from_str(s).context("invalid JSON")?;
+        entries
+            .into_iter()
+            .map(|Entry { file, range: (start, end) }| {
+                if start == 0 || start > end {
+                    bail!("invalid line range for {file:?}: [{start}, {end}]");
+                }
+                Ok((file, LineRange { start, end }))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
 pub struct WithPrevMut<'slice, T> {
     inner: &'slice mut [T],
     index: usize,
@@ -209,28 +296,19 @@ pub fn parse2_with_ctx<T: ParseWithCtx>(stream: TokenStream, ctx: T::Context) ->
     (|input: ParseStream| T::parse(input, ctx)).parse2(stream)
 }
 
-/// like `std::fs::write`, but will also create a `.bk` file
-pub fn write_with_backup(filename: &str, new_text: impl AsRef<[u8]>) -> Result {
-    let new_text = new_text.as_ref();
-    let mut file =
-        File::options().read(true).write(true).open(filename).context("failed to open the file")?;
-    let mut old_text = vec![];
-    file.read_to_end(&mut old_text).context("failed to read the file")?;
-    if &*old_text != new_text {
-        let backup = Path::new(filename).with_extension("bk");
-        write(&backup, old_text)
-            .with_context(|| format!("failed to create a backup file {:?}", backup.as_os_str()))?;
-        file.rewind().context("failed to rewind the file handle")?;
-        file.set_len(0).context("failed to clear the file")?;
-        file.write_all(new_text).context("failed to write new data to the file")?;
-    }
-    Ok(())
+/// Prepended to the messages of the errors that are combined with a parse error only to point at
+/// the code related to it, e.g. at the opening tag of an element that isn't closed, to report them
+/// as secondary labels of the diagnostic
+const SECONDARY_LABEL_MARKER: &str = "\u{1}";
+
+/// Creates an error to be combined with a parse error, reported as a secondary label
+pub fn secondary_label(tokens: impl ToTokens, message: impl Display) -> syn::Error {
+    syn::Error::new_spanned(tokens, format!("{SECONDARY_LABEL_MARKER}{message}"))
 }
 
-/// like `fs::read`, but allows for reusing allocations
-pub fn read_into(file: impl AsRef<Path>, dst: &mut Vec<u8>) -> io::Result<()> {
-    dst.clear();
-    File::open(file)?.read_to_end(dst).map(drop)
+/// Returns the message of an error created with [`secondary_label`]
+pub fn as_secondary_label(message: &str) -> Option<&str> {
+    message.strip_prefix(SECONDARY_LABEL_MARKER)
 }
 
 /*#[macro_export]
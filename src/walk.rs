@@ -0,0 +1,185 @@
+//! Listing of the Rust files in directories, leaving out the hidden directories, `target` & the
+//! paths matched by the `.gitignore` & `.ignore` files, unless that's turned off with `--no-ignore`,
+//! and the `.yewfmtignore` file, listing the paths that are never formatted
+
+use anyhow::Context;
+use std::{
+    env::current_dir,
+    fs::{read_dir, read_to_string},
+    path::{Path, PathBuf},
+};
+use yew_fmt::{glob, Result};
+
+/// The files read the ignore rules from, in the order of precedence, the rules of the latter
+/// overriding those of the former
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// The file at the root of a repository listing the paths that are never formatted, however
+/// they're passed, in the syntax of `.gitignore`
+const YEWFMT_IGNORE: &str = ".yewfmtignore";
+
+/// A line of an ignore file, following the syntax of `.gitignore` minus the character classes
+struct Rule {
+    /// The directory of the ignore file, canonicalized
+    base: PathBuf,
+    pattern: Vec<String>,
+    /// Whether the rule re-includes the paths matched by the previous ones, i.e. starts with `!`
+    negated: bool,
+    /// Whether the rule only matches directories, i.e. ends with `/`
+    dir_only: bool,
+    /// Whether the pattern is matched against the path relative to `base`, i.e. has a `/` at the
+    /// start or in the middle, as opposed to the name of a file or directory at any depth
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = line.strip_prefix('!').map_or((false, line), |line| (true, line));
+        let (dir_only, line) = line.strip_suffix('/').map_or((false, line), |line| (true, line));
+        let anchored = line.contains('/');
+        let pattern: Vec<String> = glob::components(line).map(str::to_owned).collect();
+        if pattern.is_empty() {
+            return None;
+        }
+        Some(Self { base: base.to_owned(), pattern, negated, dir_only, anchored })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(&self.base) else { return false };
+        let relative = relative.to_string_lossy();
+        let components: Vec<&str> = glob::components(&relative).collect();
+        if self.anchored {
+            glob::path_matches(&self.pattern, &components, false)
+        } else {
+            components.last().is_some_and(|name| glob::component_matches(&self.pattern[0], name))
+        }
+    }
+}
+
+/// Appends the rules of the ignore files in `dir` to `dst`
+fn read_rules(dir: &Path, dst: &mut Vec<Rule>) {
+    for file in IGNORE_FILES {
+        // a missing or unreadable ignore file is the same as an empty one
+        let Ok(src) = read_to_string(dir.join(file)) else { continue };
+        dst.extend(src.lines().filter_map(|line| Rule::parse(dir, line)));
+    }
+}
+
+/// Whether the last rule matching the path, if any, ignores it
+fn is_ignored(rules: &[Rule], path: &Path, is_dir: bool) -> bool {
+    rules.iter().rev().find(|rule| rule.matches(path, is_dir)).is_some_and(|rule| !rule.negated)
+}
+
+/// The rules of the ignore files in the ancestors of `dir` up to the root of the git repository
+/// it's in, if it's in one, outermost first
+fn inherited_rules(dir: &Path) -> Vec<Rule> {
+    let mut rules = vec![];
+    let Some(dir) = dir.parent() else { return rules };
+    let Some(repo) = dir.ancestors().find(|dir| dir.join(".git").exists()) else { return rules };
+    let mut ancestors: Vec<&Path> = dir.ancestors().take_while(|dir| *dir != repo).collect();
+    ancestors.push(repo);
+    for dir in ancestors.into_iter().rev() {
+        read_rules(dir, &mut rules);
+    }
+    rules
+}
+
+/// Walks `dir`, whose canonical path is `real`, the paths being matched against the rules by the
+/// latter so that they're found regardless of how `dir` is written
+fn walk(
+    dir: &Path,
+    real: &Path,
+    rules: &mut Vec<Rule>,
+    ignores: bool,
+    recursive: bool,
+    dst: &mut Vec<PathBuf>,
+) -> Result {
+    let n_inherited = rules.len();
+    if ignores {
+        read_rules(real, rules);
+    }
+    for entry in read_dir(dir).with_context(|| format!("failed to read directory {dir:?}"))? {
+        let path = entry.with_context(|| format!("failed to read directory {dir:?}"))?.path();
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let is_dir = path.is_dir();
+        let real = real.join(name);
+        if ignores
+            && (is_dir && (name.starts_with('.') || name == "target")
+                || is_ignored(rules, &real, is_dir))
+        {
+            continue;
+        }
+        if is_dir {
+            if recursive {
+                walk(&path, &real, rules, ignores, recursive, dst)?;
+            }
+        } else if name.ends_with(".rs") {
+            dst.push(path);
+        }
+    }
+    rules.truncate(n_inherited);
+    Ok(())
+}
+
+fn collect(path: &Path, dst: &mut Vec<PathBuf>, ignores: bool, recursive: bool) -> Result {
+    if !path.is_dir() {
+        dst.push(path.to_owned());
+        return Ok(());
+    }
+    let real = path.canonicalize().with_context(|| format!("failed to resolve {path:?}"))?;
+    let mut rules = if ignores { inherited_rules(&real) } else { vec![] };
+    walk(path, &real, &mut rules, ignores, recursive, dst)
+}
+
+/// Collects the Rust files in `path` recursively, or `path` itself if it's not a directory. If
+/// `ignores` is set, the hidden directories, `target` & the ignored paths are skipped
+pub fn collect_rust_files(path: &Path, dst: &mut Vec<PathBuf>, ignores: bool) -> Result {
+    collect(path, dst, ignores, true)
+}
+
+/// Collects the Rust files directly in the directory `dir`, leaving out the ignored ones if
+/// `ignores` is set
+pub fn list_rust_files(dir: &Path, dst: &mut Vec<PathBuf>, ignores: bool) -> Result {
+    collect(dir, dst, ignores, false)
+}
+
+/// The paths listed in the `.yewfmtignore` file at the root of the git repository the current
+/// directory is in, or in the current directory if it's not in a repository
+pub struct YewfmtIgnore {
+    rules: Vec<Rule>,
+}
+
+impl YewfmtIgnore {
+    pub fn find() -> Result<Self> {
+        let cwd = current_dir().context("failed to get the current directory")?;
+        let root = cwd.ancestors().find(|dir| dir.join(".git").exists()).unwrap_or(&cwd);
+        let path = root.join(YEWFMT_IGNORE);
+        let rules = match read_to_string(&path) {
+            Ok(src) => src.lines().filter_map(|line| Rule::parse(root, line)).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e).with_context(|| format!("failed to read {path:?}")),
+        };
+        Ok(Self { rules })
+    }
+
+    /// Whether `file` or any of the directories it's in is listed, as with `.gitignore`, a file
+    /// in a listed directory can't be re-included with a `!` rule
+    pub fn is_ignored(&self, file: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        // the deleted files, e.g. those staged for deletion, can't be canonicalized
+        let Ok(file) = file.canonicalize().or_else(|_| current_dir().map(|cwd| cwd.join(file)))
+        else {
+            return false;
+        };
+        file.ancestors().any(|path| is_ignored(&self.rules, path, path != file))
+    }
+}
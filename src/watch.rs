@@ -0,0 +1,125 @@
+//! Reformatting of the Rust files in a set of paths as they change, for running alongside a dev
+//! server like `trunk serve`
+
+use crate::{
+    parse_rustfmt_output, plural, run_rustfmt,
+    walk::{collect_rust_files, YewfmtIgnore},
+    Cli, FileConfigs,
+};
+use anyhow::Context;
+use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use std::{
+    collections::HashMap,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+use yew_fmt::{formatter::is_snippet, Config, Formatter, Result};
+
+/// How often the files are checked for changes; the standard library has no way to get notified
+/// of them, so they're polled
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Returns the modification times of the Rust files in `paths`, leaving out the ignored ones
+fn scan(
+    args: &Cli,
+    paths: &[PathBuf],
+    configs: &mut FileConfigs,
+    yewfmt_ignore: &YewfmtIgnore,
+) -> Result<HashMap<PathBuf, SystemTime>> {
+    let mut files = vec![];
+    for path in paths {
+        collect_rust_files(path, &mut files, !args.no_ignore)?;
+    }
+    let mut modified = HashMap::new();
+    for file in files {
+        if configs.get(args, &file)?.ignore.is_ignored(&file) || yewfmt_ignore.is_ignored(&file) {
+            continue;
+        }
+        // the files deleted since being listed are dropped, the next scan won't list them
+        if let (Ok(path), Ok(time)) =
+            (file.canonicalize(), file.metadata().and_then(|m| m.modified()))
+        {
+            modified.insert(path, time);
+        }
+    }
+    Ok(modified)
+}
+
+/// Formats a file in place, returns whether it was changed. The syntax errors are reported to
+/// stderr, same as rustfmt's, and the file is left as is then
+fn format_file(
+    args: &Cli,
+    formatter: &mut Formatter,
+    configs: &mut FileConfigs,
+    file: &Path,
+) -> Result<bool> {
+    let name = file.to_str().with_context(|| format!("invalid path: {file:?}"))?;
+    let src = read_to_string(file).with_context(|| format!("failed to read {file:?}"))?;
+    let rustfmt_out;
+    let input = if is_snippet(&src) {
+        &*src
+    } else {
+        let Some(out) = run_rustfmt(args, &[file], "")? else { return Ok(false) };
+        rustfmt_out = out;
+        // the files of the modules declared in the file are printed too, but not changed
+        let outputs =
+            parse_rustfmt_output(&rustfmt_out).context("failed to parse rustfmt output")?;
+        outputs.get(name).copied().with_context(|| format!("rustfmt didn't print {file:?}"))?
+    };
+
+    formatter.replace_config(configs.get(args, file)?.clone());
+    let res = formatter.format(name, input, None)?;
+    let Some(out) = res.emit_error(&mut StandardStream::stderr(ColorChoice::Auto))? else {
+        return Ok(false);
+    };
+    if out == src {
+        return Ok(false);
+    }
+    write(file, out).with_context(|| format!("failed to write to {file:?}"))?;
+    Ok(true)
+}
+
+/// Watches the Rust files in the paths given as the inputs, or in the current directory, &
+/// reformats the ones that change until the process is stopped. The `Formatter` is reused for all
+/// the files & the configs are only read once per directory, as they're first needed
+pub fn run(args: &Cli) -> Result<ExitCode> {
+    // the inputs are only empty if none were given, those expanding to no files are reported
+    let paths = if args.files.is_empty() { vec![PathBuf::from(".")] } else { args.files.clone() };
+    let mut configs = FileConfigs::default();
+    let yewfmt_ignore = YewfmtIgnore::find()?;
+    let mut seen = scan(args, &paths, &mut configs, &yewfmt_ignore)?;
+    let mut formatter = Formatter::new(Config::default()).with_only(args.only.clone());
+    if !args.quiet {
+        eprintln!("Watching {} file{} for changes", seen.len(), plural(seen.len()));
+    }
+
+    loop {
+        sleep(POLL_INTERVAL);
+        let files = match scan(args, &paths, &mut configs, &yewfmt_ignore) {
+            Ok(files) => files,
+            // e.g. a directory being deleted while being scanned
+            Err(e) => {
+                eprintln!("Error: {e:?}");
+                continue;
+            }
+        };
+        for (file, modified) in files {
+            if seen.get(&file) == Some(&modified) {
+                continue;
+            }
+            // a failure to format a file shouldn't stop the watching, it's retried on the next
+            // change to it
+            match format_file(args, &mut formatter, &mut configs, &file) {
+                Ok(true) if !args.quiet => eprintln!("Formatted {}", file.display()),
+                Ok(_) => (),
+                Err(e) => eprintln!("Error: {e:?}"),
+            }
+            // the file's own rewrite isn't to be taken for a change
+            let modified = file.metadata().and_then(|m| m.modified()).unwrap_or(modified);
+            seen.insert(file, modified);
+        }
+    }
+}
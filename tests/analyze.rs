@@ -0,0 +1,30 @@
+//! The layouts printed by `yew-fmt analyze`
+
+use serde_json::Value;
+use std::fs::{remove_file, write};
+use std::process::Command;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn layout_tree_as_json() {
+    let file = std::env::temp_dir().join(format!("yew-fmt-analyze-{}.rs", std::process::id()));
+    write(&file, "fn f() -> Html {\n    html! { <div><p>{ \"text\" }</p></div> }\n}\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--config", "yew.use_small_heuristics=\"Default\"", "analyze", "--json"])
+        .arg(&file)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_file(&file).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let macros: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let root = &macros[0]["root"];
+    // the children of the `<div>` include an element, so they're broken by the heuristics along
+    // with the root
+    assert_eq!(root["broken"], true, "{root}");
+    assert_eq!(root["children"][1]["broken"], true, "{root}");
+    assert_eq!(root["start"]["line"], 2, "{root}");
+    // the children of the `<p>`, `{ "text" }`, fit on one line
+    let p_children = &root["children"][1]["children"][1];
+    assert_eq!(p_children["broken"], false, "{root}");
+    assert_eq!(p_children["width"], 10, "{root}");
+}
@@ -0,0 +1,32 @@
+//! The explanations of the layout decisions printed with `--annotate`
+
+mod common;
+use common::run_with_stdin;
+
+const SRC: &str = "fn f() -> Html {\n    html! { <Comp a={1} b={2} /> }\n}\n";
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn break_explained() {
+    let output = run_with_stdin(&["--annotate", "--config", "yew.attr_count_threshold=1"], SRC);
+    assert!(output.status.success());
+    // the position is that of the block in the formatted code
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "<stdin>:3:14: note: block broken because the element has 2 props, more than \
+         `yew.attr_count_threshold` (1)\n"
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "fn f() -> Html {\n    html! {\n        <Comp\n            a=1\n            b=2\n        />\n    \
+         }\n}\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn nothing_to_explain() {
+    let output = run_with_stdin(&["--annotate"], SRC);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
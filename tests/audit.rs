@@ -0,0 +1,80 @@
+//! The style drift reported by `yew-fmt audit`, the files being left intact
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::process::{Command, Output};
+
+/// Runs `yew-fmt audit src` in a temporary directory containing `files` in `src`
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn audit(name: &str, files: &[(&str, &str)]) -> Output {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-audit-{name}-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    for (file, src) in files {
+        write(dir.join("src").join(file), src).unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["audit", "src"])
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    for (file, src) in files {
+        assert_eq!(read_to_string(dir.join("src").join(file)).unwrap(), *src);
+    }
+    remove_dir_all(&dir).unwrap();
+    output
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn drift_reported() {
+    let output = audit(
+        "drift",
+        &[
+            (
+                "a.rs",
+                "fn a() -> Html {\n    html! { <Button kind=\"primary\" disabled=true /> }\n}\n",
+            ),
+            (
+                "b.rs",
+                "fn b() -> Html {\n    html! { <Button disabled=true kind={\"primary\"} /> }\n}\n",
+            ),
+            (
+                "c.rs",
+                "fn c() -> Html {\n    html! { <Button kind=\"primary\" disabled=false /> }\n}\n",
+            ),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "src/b.rs:2:13: `Button` gets `disabled` before `kind`, while 2 other invocation(s) put \
+         `kind` first\n\
+         src/b.rs:2:13: `kind` of `Button` is written as a literal in braces, while 2 other \
+         invocation(s) use a literal\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn consistent_invocations() {
+    let src = "fn a() -> Html {\n    html! { <Button kind=\"primary\" disabled=true /> }\n}\n";
+    let output = audit("consistent", &[("a.rs", src), ("b.rs", src)]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn invalid_config_reported() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-audit-config-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    write(dir.join("rustfmt.toml"), "[yew]\nhtml_width = \"wide\"\n").unwrap();
+    write(dir.join("src/a.rs"), "fn a() -> Html {\n    html! { <div /> }\n}\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["audit", "src"])
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    // reported as an invalid option, not as a failure to read the files
+    assert_eq!(output.status.code(), Some(3), "{}", String::from_utf8_lossy(&output.stderr));
+}
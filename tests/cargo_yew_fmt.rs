@@ -0,0 +1,78 @@
+//! `cargo yew-fmt` formatting the targets of the packages in a workspace
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const SRC: &str = "fn f() -> Html {\n    html! { <div   /> }\n}\n";
+const FORMATTED: &str = "fn f() -> Html {\n    html! { <div /> }\n}\n";
+
+/// Creates a temporary workspace with the packages `a` & `b`, each with an unformatted library
+#[allow(clippy::unwrap_used)]
+fn workspace(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-cargo-{name}-{}", std::process::id()));
+    write_file(
+        &dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"a\", \"b\"]\nresolver = \"2\"\n",
+    );
+    for package in ["a", "b"] {
+        let manifest =
+            format!("[package]\nname = \"{package}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n");
+        write_file(&dir.join(package).join("Cargo.toml"), &manifest);
+        write_file(&dir.join(package).join("src/lib.rs"), SRC);
+    }
+    dir
+}
+
+#[allow(clippy::unwrap_used)]
+fn write_file(path: &Path, contents: &str) {
+    create_dir_all(path.parent().unwrap()).unwrap();
+    write(path, contents).unwrap();
+}
+
+#[allow(clippy::expect_used)]
+fn cargo_yew_fmt(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cargo-yew-fmt"))
+        .arg("yew-fmt")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("cargo-yew-fmt should be invoked")
+}
+
+#[allow(clippy::unwrap_used)]
+fn libs(dir: &Path) -> [String; 2] {
+    ["a", "b"].map(|package| read_to_string(dir.join(package).join("src/lib.rs")).unwrap())
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn workspace_checked() {
+    let dir = workspace("check");
+    let output = cargo_yew_fmt(&dir, &["--check"]);
+    let libs = libs(&dir);
+    remove_dir_all(&dir).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for package in ["a", "b"] {
+        assert!(stdout.contains(&format!("{package}/src/lib.rs at line 1:")), "{stdout}");
+    }
+    assert_eq!(libs, [SRC, SRC]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn package_formatted() {
+    let dir = workspace("package");
+    let output = cargo_yew_fmt(&dir, &["-p", "a"]);
+    let libs = libs(&dir);
+    let unknown = cargo_yew_fmt(&dir, &["-p", "c"]);
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(libs, [FORMATTED, SRC]);
+    assert!(!unknown.status.success());
+    assert_eq!(
+        String::from_utf8(unknown.stderr).unwrap(),
+        "Error: package `c` is not a member of the workspace\n"
+    );
+}
@@ -0,0 +1,48 @@
+//! The files formatted with `--changed-since`
+
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::Path;
+use std::process::{Command, Output};
+
+const UNFORMATTED: &str = "fn f() -> Html {\n    html! { <a   /> }\n}\n";
+
+#[allow(clippy::expect_used)]
+fn run_in(dir: &Path, program: &str, args: &[&str]) -> Output {
+    Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap_or_else(|e| panic!("{program} should be invoked: {e}"))
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn only_changed_files_formatted() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-changed-since-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    write(dir.join("changed.rs"), "fn f() {}\n").unwrap();
+    write(dir.join("unchanged.rs"), UNFORMATTED).unwrap();
+    for args in [
+        &["init", "-q"][..],
+        &["add", "."],
+        &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", "init"],
+    ] {
+        assert!(run_in(&dir, "git", args).status.success(), "`git {}` failed", args.join(" "));
+    }
+    write(dir.join("changed.rs"), UNFORMATTED).unwrap();
+    write(dir.join("untracked.rs"), UNFORMATTED).unwrap();
+
+    let bin = env!("CARGO_BIN_EXE_yew-fmt");
+    let output = run_in(&dir, bin, &["--check", "-l", "--changed-since", "HEAD"]);
+    let unknown_ref = run_in(&dir, bin, &["--check", "--changed-since", "no-such-ref"]);
+    remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(1), "expected a difference to be found");
+    let files = String::from_utf8(output.stdout).unwrap();
+    // the files are formatted in no particular order
+    let mut names: Vec<_> =
+        files.lines().map(|file| Path::new(file).file_name().unwrap().to_owned()).collect();
+    names.sort();
+    assert_eq!(names, ["changed.rs", "untracked.rs"]);
+    assert_eq!(unknown_ref.status.code(), Some(3), "expected an invalid option");
+}
@@ -0,0 +1,32 @@
+//! The output & the exit codes of `--check`, which are the same as rustfmt's for the scripts
+//! written for it
+
+mod common;
+use common::run_with_stdin;
+use std::process::Output;
+
+fn check(src: &str) -> Output {
+    run_with_stdin(&["--check", "--color", "never"], src)
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn rustfmt_compatible() {
+    let formatted = check("fn f() -> Html {\n    html! { <a /> }\n}\n");
+    assert_eq!(formatted.status.code(), Some(0), "expected no difference to be found");
+    assert!(formatted.stdout.is_empty());
+
+    let unformatted = check("fn f() -> Html {\n    html! { <a   /> }\n}\n");
+    assert_eq!(unformatted.status.code(), Some(1), "expected a difference to be found");
+    assert_eq!(
+        String::from_utf8(unformatted.stdout).unwrap(),
+        "Diff in <stdin> at line 1:\n \
+         fn f() -> Html {\n\
+         -    html! { <a   /> }\n\
+         +    html! { <a /> }\n \
+         }\n"
+    );
+
+    let invalid = check("fn f( {}\n");
+    assert_eq!(invalid.status.code(), Some(2), "expected a parse failure");
+}
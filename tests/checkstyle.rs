@@ -0,0 +1,23 @@
+//! The report printed with `--emit checkstyle`
+
+mod common;
+use common::run_with_stdin;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn lines_to_change_reported() {
+    let src = "fn f() -> Html {\n    html! { <a   href=\"x&y\" /> }\n}\n";
+    let output = run_with_stdin(&["--check", "--emit", "checkstyle"], src);
+    assert_eq!(output.status.code(), Some(1), "expected a difference to be found");
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        report,
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <checkstyle version=\"4.3\">\n\
+         <file name=\"&lt;stdin&gt;\">\n\
+         <error line=\"2\" severity=\"warning\" \
+         message=\"Should be `    html! { &lt;a href=&quot;x&amp;y&quot; /&gt; }`\" />\n\
+         </file>\n\
+         </checkstyle>\n"
+    );
+}
@@ -1,8 +1,24 @@
 use std::fs::read_to_string;
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 
-#[allow(clippy::unwrap_used, clippy::expect_used)]
+/// Runs yew-fmt with `args`, passing `stdin` to it, returns its exit code & everything it printed
+#[allow(dead_code, clippy::unwrap_used, clippy::expect_used)]
+pub fn run_with_stdin(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("yew-fmt should be invoked");
+    // yew-fmt may exit before reading the source, e.g. with an invalid config
+    _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+#[allow(dead_code, clippy::unwrap_used, clippy::expect_used)]
 pub fn cmp(test_name: &'static str) {
     let mut file_name = PathBuf::from(test_name);
 
@@ -0,0 +1,279 @@
+//! The configs found in the directories of the formatted files & in their parents, merged
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::process::Command;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn nested_configs_merged() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-config-discovery-{}", std::process::id()));
+    create_dir_all(dir.join("src/legacy")).unwrap();
+    create_dir_all(dir.join("src/gen")).unwrap();
+    write(
+        dir.join("rustfmt.toml"),
+        "ignore = [\"src/gen\"]\n\n[yew]\nuse_prop_init_shorthand = true\n",
+    )
+    .unwrap();
+    write(dir.join("src/legacy/.rustfmt.toml"), "[yew]\nself_close_elements = false\n").unwrap();
+    let files = ["src/view.rs", "src/legacy/view.rs", "src/gen/view.rs"];
+    for file in files {
+        write(
+            dir.join(file),
+            "fn f(value: u8) -> Html {\n    html! { <Comp value={value}></Comp> }\n}\n",
+        )
+        .unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(files)
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let formatted = files.map(|file| read_to_string(dir.join(file)).unwrap());
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // the option set in the root applies in the nested directory too, along with the one set there
+    assert_eq!(
+        formatted.each_ref().map(|src| src.lines().nth(1).unwrap().trim()),
+        [
+            "html! { <Comp {value} /> }",
+            "html! { <Comp {value}></Comp> }",
+            // the `ignore` option of the root config
+            "html! { <Comp value={value}></Comp> }",
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn yew_fmt_toml_preferred() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-yew-fmt-toml-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    write(dir.join("rustfmt.toml"), "[yew]\nuse_prop_init_shorthand = false\n").unwrap();
+    write(dir.join("yew-fmt.toml"), "use_prop_init_shorthand = true\n").unwrap();
+    write(
+        dir.join("view.rs"),
+        "fn f(value: u8) -> Html {\n    html! { <Comp value={value} /> }\n}\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("view.rs")
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let formatted = read_to_string(dir.join("view.rs")).unwrap();
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(formatted, "fn f(value: u8) -> Html {\n    html! { <Comp {value} /> }\n}\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn cargo_metadata_read() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-cargo-metadata-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    create_dir_all(dir.join("legacy/src")).unwrap();
+    write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"legacy\"]\n\n[workspace.metadata.yew-fmt]\n\
+         use_prop_init_shorthand = true\n",
+    )
+    .unwrap();
+    write(
+        dir.join("legacy/Cargo.toml"),
+        "[package]\nname = \"legacy\"\nversion = \"0.1.0\"\n\n[package.metadata.yew-fmt]\n\
+         self_close_elements = false\n",
+    )
+    .unwrap();
+    let files = ["src/view.rs", "legacy/src/view.rs"];
+    for file in files {
+        write(
+            dir.join(file),
+            "fn f(value: u8) -> Html {\n    html! { <Comp value={value}></Comp> }\n}\n",
+        )
+        .unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(files)
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let formatted = files.map(|file| read_to_string(dir.join(file)).unwrap());
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        formatted.each_ref().map(|src| src.lines().nth(1).unwrap().trim()),
+        ["html! { <Comp {value} /> }", "html! { <Comp {value}></Comp> }"]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn config_path_bypasses_lookup() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-config-path-{}", std::process::id()));
+    create_dir_all(dir.join("ci")).unwrap();
+    create_dir_all(dir.join("app")).unwrap();
+    write(dir.join("ci/yew-fmt.toml"), "use_prop_init_shorthand = true\n").unwrap();
+    write(dir.join("app/rustfmt.toml"), "[yew]\nself_close_elements = false\n").unwrap();
+    let src = "fn f(value: u8) -> Html {\n    html! { <Comp value={value}></Comp> }\n}\n";
+    let formatted = |config_path: &str| {
+        write(dir.join("app/view.rs"), src).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+            .args(["--config-path", config_path, "view.rs"])
+            .current_dir(dir.join("app"))
+            .output()
+            .expect("yew-fmt should be invoked");
+        let formatted = read_to_string(dir.join("app/view.rs")).unwrap();
+        (output.status.code(), formatted.lines().nth(1).unwrap().trim().to_owned())
+    };
+    let results = ["../ci", "../ci/yew-fmt.toml", "../ci/rustfmt.toml"].map(formatted);
+    remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        results,
+        [
+            // the config in the directory of the file isn't read
+            (Some(0), "html! { <Comp {value} /> }".to_owned()),
+            (Some(0), "html! { <Comp {value} /> }".to_owned()),
+            (Some(3), "html! { <Comp value={value}></Comp> }".to_owned()),
+        ]
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn unknown_options_reported() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-unknown-options-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    write(dir.join("rustfmt.toml"), "[yew]\nhtml_widht = 80\nfoo = true\n").unwrap();
+    write(dir.join("view.rs"), "fn f() -> Html {\n    html! { <div></div> }\n}\n").unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+            .args(args)
+            .arg("view.rs")
+            .current_dir(&dir)
+            .output()
+            .expect("yew-fmt should be invoked")
+    };
+    let lenient = run(&[]);
+    let strict = run(&["--strict-config"]);
+    let formatted = read_to_string(dir.join("view.rs")).unwrap();
+    remove_dir_all(&dir).unwrap();
+
+    let stderr = String::from_utf8(lenient.stderr).unwrap();
+    assert!(lenient.status.success(), "{stderr}");
+    assert!(stderr.contains(
+        "Warning: Unknown configuration option `yew.html_widht`, did you mean `yew.html_width`?\n"
+    ));
+    assert!(stderr.contains("Warning: Unknown configuration option `yew.foo`\n"));
+
+    let stderr = String::from_utf8(strict.stderr).unwrap();
+    assert_eq!(strict.status.code(), Some(3));
+    assert!(stderr.contains("unknown configuration option `yew.foo`"), "{stderr}");
+    // the file is formatted by the first run only
+    assert_eq!(formatted, "fn f() -> Html {\n    html! { <div /> }\n}\n");
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn profiles_selected() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-profiles-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    write(
+        dir.join("rustfmt.toml"),
+        "[yew]\nuse_prop_init_shorthand = true\n\n[profile.legacy]\nself_close_elements = false\n",
+    )
+    .unwrap();
+    write(dir.join("src/yew-fmt.toml"), "[profile.legacy]\nuse_prop_init_shorthand = false\n")
+        .unwrap();
+    write(
+        dir.join("src/view.rs"),
+        "fn f(value: u8) -> Html {\n    html! { <Comp value={value}></Comp> }\n}\n",
+    )
+    .unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+            .args(["--emit", "stdout", "src/view.rs"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("yew-fmt should be invoked")
+    };
+    let outputs = [run(&[]), run(&["--profile", "legacy"]), run(&["--profile", "compact"])];
+    remove_dir_all(&dir).unwrap();
+    let [default, legacy, unknown] = outputs.map(|output| {
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        (output.status.code(), stdout.lines().nth(3).map(|line| line.trim().to_owned()))
+    });
+    assert_eq!(default, (Some(0), Some("html! { <Comp {value} /> }".to_owned())));
+    // the profile of each file overrides the other options of the file
+    assert_eq!(legacy, (Some(0), Some("html! { <Comp value={value}></Comp> }".to_owned())));
+    assert_eq!(unknown, (Some(3), None));
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn required_version_enforced() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-required-version-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    write(dir.join("view.rs"), "fn f() -> Html {\n    html! { <div></div> }\n}\n").unwrap();
+    let run = |required: &str, args: &[&str]| {
+        write(dir.join("yew-fmt.toml"), format!("required_version = {required:?}\n")).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+            .args(["--check", "view.rs"])
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("yew-fmt should be invoked");
+        (output.status.code(), String::from_utf8(output.stderr).unwrap())
+    };
+    let current = env!("CARGO_PKG_VERSION");
+    let satisfied = run(&format!("={current}"), &[]);
+    let wildcard = run(&format!("{}.*", current.rsplit_once('.').unwrap().0), &[]);
+    let unsatisfied = run(&format!(">{current}"), &[]);
+    let ignored = run(&format!(">{current}"), &["--ignore-version"]);
+    remove_dir_all(&dir).unwrap();
+
+    // the file isn't formatted, hence the diff found
+    assert_eq!(satisfied.0, Some(1), "{}", satisfied.1);
+    assert_eq!(wildcard.0, Some(1), "{}", wildcard.1);
+    assert_eq!(unsatisfied.0, Some(3));
+    let msg = format!("yew-fmt {current} doesn't satisfy `yew.required_version = \">{current}\"`");
+    assert!(unsatisfied.1.contains(&format!("Error: {msg}")), "{}", unsatisfied.1);
+    assert_eq!(ignored.0, Some(1));
+    assert!(ignored.1.contains(&format!("Warning: {msg}")), "{}", ignored.1);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn crate_edition_detected() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-crate-edition-{}", std::process::id()));
+    create_dir_all(dir.join("app/src")).unwrap();
+    create_dir_all(dir.join("legacy/src")).unwrap();
+    write(
+        dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"legacy\"]\n\n[workspace.package]\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    write(dir.join("app/Cargo.toml"), "[package]\nname = \"app\"\nedition.workspace = true\n")
+        .unwrap();
+    // no edition means Rust 2015, where `async` is an identifier
+    write(dir.join("legacy/Cargo.toml"), "[package]\nname = \"legacy\"\n").unwrap();
+    let files = ["app/src/lib.rs", "legacy/src/lib.rs"];
+    write(dir.join(files[0]), "async fn f() -> Html {\n    html! { <div></div> }\n}\n").unwrap();
+    write(dir.join(files[1]), "fn f(async: u8) -> Html {\n    html! { <p>{ async }</p> }\n}\n")
+        .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(files)
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let formatted = files.map(|file| read_to_string(dir.join(file)).unwrap());
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        formatted,
+        [
+            "async fn f() -> Html {\n    html! { <div /> }\n}\n",
+            "fn f(async: u8) -> Html {\n    html! { <p>{ async }</p> }\n}\n",
+        ]
+    );
+}
@@ -0,0 +1,149 @@
+//! The options overridden with `--config` & with the `YEW_FMT_*` environment variables
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Formats `src` read from stdin with `--config overrides`, returns the exit code, stdout & stderr
+fn run(overrides: &str, src: &str) -> (Option<i32>, String, String) {
+    run_with_env(&[], overrides, src)
+}
+
+/// Same as [`run`], with the environment variables `env` set
+fn run_with_env(env: &[(&str, &str)], overrides: &str, src: &str) -> (Option<i32>, String, String) {
+    run_with_env_and_args(env, &[], overrides, src)
+}
+
+/// Same as [`run_with_env`], with the extra arguments `args`
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn run_with_env_and_args(
+    env: &[(&str, &str)],
+    args: &[&str],
+    overrides: &str,
+    src: &str,
+) -> (Option<i32>, String, String) {
+    let mut yew_fmt = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(args)
+        .args(["--config", overrides])
+        .envs(env.iter().copied())
+        .current_dir(std::env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("yew-fmt should be invoked");
+    // with an invalid config, yew-fmt may exit before reading the source
+    _ = yew_fmt.stdin.take().unwrap().write_all(src.as_bytes());
+    let output = yew_fmt.wait_with_output().unwrap();
+    let [stdout, stderr] = [output.stdout, output.stderr].map(|s| String::from_utf8(s).unwrap());
+    (output.status.code(), stdout, stderr)
+}
+
+const SRC: &str = "fn f(value: u8) -> Html {\n    html! { <Comp value={value}></Comp> }\n}\n";
+
+#[test]
+fn options_overridden() {
+    let (code, stdout, _) =
+        run("yew.use_prop_init_shorthand=true,yew.self_close_elements=false", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "fn f(value: u8) -> Html {\n    html! { <Comp {value}></Comp> }\n}\n");
+}
+
+#[test]
+fn invalid_overrides_reported() {
+    let (code, _, stderr) = run("yew.html_width=wide", SRC);
+    assert_eq!(code, Some(3));
+    assert!(stderr.contains("received a `yew.html_width` value that's not an integer: `wide`"));
+
+    let (code, _, stderr) = run("yew.html_widht=80", SRC);
+    assert_eq!(code, Some(3));
+    assert!(stderr.contains("unknown option `yew.html_widht`, expected one of: `yew.html_width`, "));
+}
+
+#[test]
+fn env_overrides() {
+    let env =
+        [("YEW_FMT_USE_PROP_INIT_SHORTHAND", "true"), ("YEW_FMT_SELF_CLOSE_ELEMENTS", "false")];
+    let (code, stdout, _) = run_with_env(&env, "", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "fn f(value: u8) -> Html {\n    html! { <Comp {value}></Comp> }\n}\n");
+
+    // `--config` takes priority
+    let (code, stdout, _) = run_with_env(&env, "yew.self_close_elements=true", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "fn f(value: u8) -> Html {\n    html! { <Comp {value} /> }\n}\n");
+}
+
+#[test]
+fn unknown_env_overrides() {
+    // like the unknown options of the config files, only warned about
+    let (code, stdout, stderr) = run_with_env(&[("YEW_FMT_HTML_WIDHT", "80")], "", SRC);
+    assert_eq!(code, Some(0), "{stderr}");
+    assert_eq!(stdout, "fn f(value: u8) -> Html {\n    html! { <Comp value={value} /> }\n}\n");
+    assert!(stderr.contains(
+        "Warning: Unknown configuration option `yew.html_widht`, did you mean `yew.html_width`?"
+    ));
+
+    let env = [("YEW_FMT_FOO", "1")];
+    let (code, _, stderr) = run_with_env_and_args(&env, &["--strict-config"], "", SRC);
+    assert_eq!(code, Some(3));
+    assert!(stderr.contains("unknown configuration option `yew.foo`"), "{stderr}");
+}
+
+#[test]
+fn newline_style_applied() {
+    let src =
+        "fn f() -> Html {\n    html! { <div class=\"card\"><h1>{ \"A rather long title\" }</h1>\
+               <p>{ \"and some more text\" }</p></div> }\n}\n";
+    let formatted = [
+        "fn f() -> Html {",
+        "    html! {",
+        "        <div class=\"card\">",
+        "            <h1>{ \"A rather long title\" }</h1>",
+        "            <p>{ \"and some more text\" }</p>",
+        "        </div>",
+        "    }",
+        "}",
+        "",
+    ];
+    let (code, stdout, _) = run("newline_style=Windows", src);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, formatted.join("\r\n"));
+
+    let (code, stdout, _) = run("newline_style=Unix", &src.replace('\n', "\r\n"));
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, formatted.join("\n"));
+}
+
+#[test]
+fn html_width_follows_max_width() {
+    let (code, stdout, _) = run("max_width=30", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "fn f(value: u8) -> Html {\n    html! {\n        <Comp value={value} />\n    }\n}\n"
+    );
+
+    // unless it's set explicitly
+    let (code, stdout, _) = run("max_width=30,yew.html_width=100", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "fn f(value: u8) -> Html {\n    html! { <Comp value={value} /> }\n}\n");
+}
+
+#[test]
+fn html_width_percent_applied() {
+    // of `max_width`, which is 100 by default
+    let (code, stdout, _) = run("yew.html_width_percent=30", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "fn f(value: u8) -> Html {\n    html! {\n        <Comp value={value} />\n    }\n}\n"
+    );
+
+    let (code, stdout, _) = run("yew.html_width_percent=30,yew.html_width=100", SRC);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "fn f(value: u8) -> Html {\n    html! { <Comp value={value} /> }\n}\n");
+
+    let (code, _, stderr) = run("yew.html_width_percent=120", SRC);
+    assert_eq!(code, Some(3));
+    assert!(stderr.contains("`yew.html_width_percent` is 120, expected a percentage from 1 to 100"));
+}
@@ -0,0 +1,80 @@
+//! The errors reported for the HTML that fails to parse
+
+mod common;
+use common::run_with_stdin;
+use std::process::Command;
+
+/// Runs `yew-fmt --check` on `src` passed through stdin, returns the reported errors
+#[allow(clippy::unwrap_used)]
+fn errors(src: &str) -> String {
+    let output = run_with_stdin(&["--check", "--color", "never"], src);
+    assert_eq!(output.status.code(), Some(2), "expected a parse failure");
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn stray_punctuation_among_props() {
+    let errors = errors("fn f() -> Html {\n    html! { <div ,class=\"x\"; id=\"y\"></div> }\n}\n");
+    assert!(errors.contains("error[Y0002]: expected prop name, `..`, `>` or `/>`"), "{errors}");
+    // both the comma & the semicolon are pointed at
+    assert!(errors.contains("<stdin>:2:18"), "{errors}");
+    assert!(errors.contains("^         ^ expected prop name"), "{errors}");
+}
+
+#[test]
+fn stray_comma_before_base_props() {
+    let errors = errors("fn f() -> Html {\n    html! { <Comp a={1}, ..props /> }\n}\n");
+    assert!(errors.contains("error[Y0002]: expected prop name, `..`, `>` or `/>`"), "{errors}");
+    assert!(errors.contains("<stdin>:2:24"), "{errors}");
+}
+
+#[test]
+fn all_failing_macros_reported() {
+    let errors = errors(
+        "fn f() -> Html {\n    html! { <div ,class=\"x\"></div> }\n}\n\n\
+         fn g() -> Html {\n    html! { <span><b>{\"ok\"}</b></span> }\n}\n\n\
+         fn h() -> Html {\n    html! { <Comp a={1}, ..props /> }\n}\n",
+    );
+    assert!(errors.contains("<stdin>:2:18"), "{errors}");
+    assert!(errors.contains("<stdin>:10:24"), "{errors}");
+}
+
+#[test]
+fn unclosed_tag() {
+    let errors = errors("fn f() -> Html {\n    html! { <p>{\"unclosed\"} }\n}\n");
+    assert!(errors.contains("expected `</p>`"), "{errors}");
+    // reported at the end of the macro rather than at the start of the file
+    assert!(errors.contains("<stdin>:2:29"), "{errors}");
+    assert!(errors.contains("unclosed tag"), "{errors}");
+}
+
+#[test]
+fn mismatched_closing_tag() {
+    let errors = errors("fn f() -> Html {\n    html! { <div><p>{\"x\"}</div> }\n}\n");
+    assert!(errors.contains("error[Y0004]: mismatched closing tag, expected `</p>`"), "{errors}");
+    assert!(errors.contains("<stdin>:2:28"), "{errors}");
+    assert!(errors.contains("the tag being closed"), "{errors}");
+}
+
+#[test]
+fn ext_keyword_hinted() {
+    let errors =
+        errors("fn f() -> Html {\n    html! { <ul>match 1 { _ => <li/> }</ul><p ,></p> }\n}\n");
+    assert!(errors.contains("did you mean to set `yew.html_flavor` to \"Ext\"?"), "{errors}");
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn error_codes_explained() {
+    let errors = errors("fn f() -> Html {\n    html! { <p>{\"unclosed\"} }\n}\n");
+    assert!(errors.contains("error[Y0003]"), "{errors}");
+    assert!(errors.contains("try `yew-fmt --explain Y0003`"), "{errors}");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--explain", "Y0003"])
+        .output()
+        .expect("yew-fmt should be invoked");
+    assert!(output.status.success());
+    let explanation = String::from_utf8(output.stdout).unwrap();
+    assert!(explanation.starts_with("Y0003: "), "{explanation}");
+}
@@ -0,0 +1,39 @@
+//! The unified diff printed with `--diff`
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::process::Command;
+
+const SRC: &str = "fn f() -> Html {\n    html! { <a   href=\"x\" /> }\n}\n";
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn unified_diff_printed() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-diff-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    write(dir.join("src/lib.rs"), SRC).unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .expect("yew-fmt should be invoked")
+    };
+    let output = run(&["--diff", "src/lib.rs"]);
+    let checked = run(&["--diff", "--check", "src/lib.rs"]);
+    let unchanged = read_to_string(dir.join("src/lib.rs")).unwrap();
+    remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(0), "`--diff` alone shouldn't fail");
+    assert_eq!(checked.status.code(), Some(1), "expected a difference to be found");
+    assert_eq!(unchanged, SRC, "the file shouldn't be written");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "--- a/src/lib.rs\n\
+         +++ b/src/lib.rs\n\
+         @@ -1,3 +1,3 @@\n \
+         fn f() -> Html {\n\
+         -    html! { <a   href=\"x\" /> }\n\
+         +    html! { <a href=\"x\" /> }\n \
+         }\n"
+    );
+}
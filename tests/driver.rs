@@ -0,0 +1,105 @@
+//! The `FormatDriver` of the library, reporting the progress of a run & allowing to cancel it
+
+use std::ops::ControlFlow;
+use yew_fmt::driver::{CancellationToken, FormatDriver, RunEnd, SourceFile};
+use yew_fmt::{Config, Formatter};
+
+fn inputs() -> Vec<SourceFile> {
+    ["a.rs", "b.rs", "c.rs"]
+        .map(|name| SourceFile {
+            name: name.to_owned(),
+            src: "fn f() -> Html {\n    html! { <div   /> }\n}\n".to_owned(),
+            lines: None,
+            config: None,
+        })
+        .into()
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn progress_reported() {
+    let inputs = inputs();
+    let mut progress = vec![];
+    let mut outputs = vec![];
+    let end = FormatDriver::new(Formatter::new(Config::default()))
+        .with_progress(|p| progress.push((p.done, p.total, p.current.to_owned())))
+        .run(&inputs, |input, res| {
+            outputs.push((input.name.clone(), res.output().to_owned()));
+            Ok(ControlFlow::Continue(()))
+        })
+        .unwrap();
+    assert_eq!(end, RunEnd::Completed);
+    assert_eq!(
+        progress,
+        [(0, 3, "a.rs".to_owned()), (1, 3, "b.rs".to_owned()), (2, 3, "c.rs".to_owned())]
+    );
+    assert_eq!(outputs.len(), 3);
+    assert!(outputs.iter().all(|(_, out)| out.contains("html! { <div /> }")), "{outputs:?}");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn run_stopped() {
+    let inputs = inputs();
+    let mut formatted = vec![];
+    let end = FormatDriver::new(Formatter::new(Config::default()))
+        .run(&inputs, |input, _| {
+            formatted.push(input.name.clone());
+            Ok(if input.name == "b.rs" {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            })
+        })
+        .unwrap();
+    assert_eq!(end, RunEnd::Stopped);
+    assert_eq!(formatted, ["a.rs", "b.rs"]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn run_cancelled() {
+    let inputs = inputs();
+    let token = CancellationToken::new();
+    let mut formatted = vec![];
+    let end = FormatDriver::new(Formatter::new(Config::default()))
+        .with_cancellation(token.clone())
+        .run(&inputs, |input, _| {
+            formatted.push(input.name.clone());
+            // the file being handled is still processed, the run stops before the next one
+            token.cancel();
+            Ok(ControlFlow::Continue(()))
+        })
+        .unwrap();
+    assert_eq!(end, RunEnd::Cancelled);
+    assert_eq!(formatted, ["a.rs"]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn config_restored_on_error() {
+    let mut config = Config::default();
+    config.yew.self_close_elements = false;
+    let src = "fn f() -> Html {\n    html! { <div></div> }\n}\n";
+    let own = SourceFile {
+        name: "a.rs".to_owned(),
+        src: src.to_owned(),
+        lines: None,
+        config: Some(config),
+    };
+    let shared =
+        SourceFile { name: "b.rs".to_owned(), src: src.to_owned(), lines: None, config: None };
+    let mut driver = FormatDriver::new(Formatter::new(Config::default()));
+    let err =
+        driver.run(std::slice::from_ref(&own), |_, _| anyhow::bail!("not written")).unwrap_err();
+    assert_eq!(err.to_string(), "not written");
+    let mut output = String::new();
+    driver
+        .run(std::slice::from_ref(&shared), |_, res| {
+            output = res.output().to_owned();
+            Ok(ControlFlow::Continue(()))
+        })
+        .unwrap();
+    // formatted with the driver's config rather than with the one of the failed file
+    assert!(output.contains("html! { <div /> }"), "{output}");
+}
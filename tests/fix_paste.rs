@@ -0,0 +1,23 @@
+//! The rewriting of JSX-isms by `yew-fmt fix-paste`
+
+use std::fs::{copy, read_to_string, remove_file};
+use std::process::Command;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn jsx_isms_rewritten() {
+    let file = std::env::temp_dir().join(format!("yew-fmt-fix-paste-{}.rs", std::process::id()));
+    copy("tests/fix_paste/source.rs", &file).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("fix-paste")
+        .arg(&file)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let fixed = read_to_string(&file).unwrap();
+    remove_file(&file).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains(":5:14: renamed `className` to `class`"), "{stderr}");
+    let target = read_to_string("tests/fix_paste/target.rs").unwrap();
+    assert!(fixed == target, "{}", diffy::create_patch(&target, &fixed));
+}
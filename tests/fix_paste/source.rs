@@ -0,0 +1,13 @@
+use yew::prelude::*;
+
+fn card(props: &Props) -> Html {
+    html! {
+        <div className="card" onClick={props.onclick.clone()}>
+            {/* the header */}
+            <label htmlFor="name" tabIndex={0}>{"Name"}</label>
+            <input id="name" readOnly=true data-className="kept"/>
+            <Comp onClick={cb} />
+            { if x { html! { <span className="nested"/> } } else { html! {} } }
+        </div>
+    }
+}
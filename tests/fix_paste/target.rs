@@ -0,0 +1,13 @@
+use yew::prelude::*;
+
+fn card(props: &Props) -> Html {
+    html! {
+        <div class="card" onclick={props.onclick.clone()}>
+            /* the header */
+            <label for="name" tabindex=0>{ "Name" }</label>
+            <input id="name" readonly=true data-className="kept" />
+            <Comp onClick={cb} />
+            { if x { html! { <span class="nested" /> } } else { html! {} } }
+        </div>
+    }
+}
@@ -0,0 +1,47 @@
+//! `--frozen-config` formatting with the options passed on the command line only
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::process::{Command, Output};
+
+const SRC: &str = "fn f(value: u8) -> Html {\n    html! { <Comp value={value}></Comp> }\n}\n";
+
+/// Runs `yew-fmt --frozen-config` with `args` on `view.rs` in a temporary directory containing the
+/// `config`, if any, as `rustfmt.toml`, returns its output & the contents of the file afterwards
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn run(name: &str, config: Option<&str>, args: &[&str]) -> (Output, String) {
+    let dir =
+        std::env::temp_dir().join(format!("yew-fmt-frozen-config-{name}-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    if let Some(config) = config {
+        write(dir.join("rustfmt.toml"), config).unwrap();
+    }
+    write(dir.join("view.rs"), SRC).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("--frozen-config")
+        .args(args)
+        .arg("view.rs")
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let formatted = read_to_string(dir.join("view.rs")).unwrap();
+    remove_dir_all(&dir).unwrap();
+    (output, formatted)
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn config_file_rejected() {
+    let (output, formatted) = run("found", Some("[yew]\nuse_prop_init_shorthand = true\n"), &[]);
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("while running with `--frozen-config`"), "{stderr}");
+    assert!(stderr.contains("rustfmt.toml"), "{stderr}");
+    assert_eq!(formatted, SRC);
+}
+
+#[test]
+fn command_line_config_applied() {
+    let (output, formatted) = run("none", None, &["--config", "yew.use_prop_init_shorthand=true"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(formatted, "fn f(value: u8) -> Html {\n    html! { <Comp {value} /> }\n}\n");
+}
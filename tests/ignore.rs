@@ -0,0 +1,78 @@
+//! The files left out by the `ignore` option of the config
+
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process::Command;
+
+/// Runs `yew-fmt --check` on an unformatted file in `src/gen` & a formatted one in `src` of a
+/// temporary project with `ignore` set to `ignored`, returns whether a difference was found
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn diff_found(name: &str, ignored: &str) -> bool {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-ignore-{name}-{}", std::process::id()));
+    create_dir_all(dir.join("src/gen")).unwrap();
+    write(dir.join("rustfmt.toml"), format!("ignore = [{ignored:?}]\n")).unwrap();
+    write(dir.join("src/gen/view.rs"), "fn f() -> Html {\n    html! { <a   /> }\n}\n").unwrap();
+    write(dir.join("src/lib.rs"), "fn g() -> Html {\n    html! { <a /> }\n}\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--check", "src/gen/view.rs", "src/lib.rs"])
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    match output.status.code() {
+        Some(0) => false,
+        Some(1) => true,
+        _ => panic!("yew-fmt failed: {}", String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+#[test]
+fn separators_interchangeable() {
+    assert!(!diff_found("slash", "src/gen"));
+    assert!(!diff_found("backslash", "src\\gen"));
+    assert!(!diff_found("mixed", "./src/gen\\view.rs"));
+    assert!(!diff_found("wildcard", "**\\v*.rs"));
+    assert!(diff_found("other", "src/generated"));
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn ignored_however_passed() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-ignore-passed-{}", std::process::id()));
+    create_dir_all(dir.join("src/gen")).unwrap();
+    create_dir_all(dir.join("tests/fixtures/invalid")).unwrap();
+    write(dir.join("rustfmt.toml"), "ignore = [\"src/gen\", \"tests/fixtures/**\"]\n").unwrap();
+    write(dir.join("src/gen/view.rs"), "fn f() -> Html {\n    html! { <a   /> }\n}\n").unwrap();
+    // would fail the run with a syntax error if it were passed to rustfmt
+    write(dir.join("tests/fixtures/invalid/view.rs"), "fn f( {\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("--check")
+        .arg(dir.join("src/gen/view.rs"))
+        .args(["./src/../src/gen/view.rs", "tests/fixtures/invalid/view.rs", "-r", "tests"])
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn yewfmtignore_honoured() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-yewfmtignore-{}", std::process::id()));
+    create_dir_all(dir.join(".git")).unwrap();
+    create_dir_all(dir.join("src")).unwrap();
+    create_dir_all(dir.join("vendor/widgets")).unwrap();
+    write(dir.join(".yewfmtignore"), "# third-party code\nvendor/\n*.gen.rs\n").unwrap();
+    for file in ["vendor/widgets/lib.rs", "src/view.gen.rs"] {
+        write(dir.join(file), "fn f() -> Html {\n    html! { <a   /> }\n}\n").unwrap();
+    }
+    write(dir.join("src/lib.rs"), "fn g() -> Html {\n    html! { <a /> }\n}\n").unwrap();
+    // the file is found at the root of the repository & isn't turned off by `--no-ignore`
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--check", "../vendor/widgets/lib.rs", "view.gen.rs", "--no-ignore", "-r", "."])
+        .current_dir(dir.join("src"))
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,112 @@
+//! The directories & glob patterns given as the inputs
+
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::Path;
+use std::process::Command;
+
+const UNFORMATTED: &str = "fn f() -> Html {\n    html! { <a   /> }\n}\n";
+
+/// Runs `yew-fmt --check -l` with `args` on a temporary project with unformatted files in its
+/// root, in `src` & in `src/nested` plus the `extra` files, given as paths & contents, returns
+/// the exit code & the listed files relative to the root
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn listed_with(name: &str, extra: &[(&str, &str)], args: &[&str]) -> (Option<i32>, Vec<String>) {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-inputs-{name}-{}", std::process::id()));
+    let files = ["build.rs", "src/lib.rs", "src/nested/view.rs"].map(|file| (file, UNFORMATTED));
+    for (file, contents) in files.iter().chain(extra) {
+        let file = dir.join(file);
+        create_dir_all(file.parent().unwrap()).unwrap();
+        write(file, contents).unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--check", "-l"])
+        .args(args)
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let root = dir.canonicalize().unwrap();
+    remove_dir_all(&dir).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut files: Vec<String> = stdout
+        .lines()
+        .map(|file| {
+            let file = Path::new(file).strip_prefix(&root).unwrap_or(Path::new(file));
+            file.to_string_lossy().replace('\\', "/")
+        })
+        .collect();
+    // the files are formatted in no particular order
+    files.sort();
+    (output.status.code(), files)
+}
+
+fn listed(name: &str, args: &[&str]) -> (Option<i32>, Vec<String>) {
+    listed_with(name, &[], args)
+}
+
+#[test]
+fn directories_and_globs_expanded() {
+    assert_eq!(listed("dir", &["src"]), (Some(1), vec!["src/lib.rs".to_owned()]));
+    assert_eq!(
+        listed("recursive", &["-r", "."]),
+        (Some(1), ["build.rs", "src/lib.rs", "src/nested/view.rs"].map(str::to_owned).to_vec())
+    );
+    assert_eq!(
+        listed("glob", &["src/**/*.rs"]),
+        (Some(1), ["src/lib.rs", "src/nested/view.rs"].map(str::to_owned).to_vec())
+    );
+    assert_eq!(
+        listed("wildcard", &["*/n?sted/*"]),
+        (Some(1), vec!["src/nested/view.rs".to_owned()])
+    );
+    assert_eq!(listed("no-match", &["tests/*.rs"]), (Some(3), vec![]));
+}
+
+#[test]
+fn directory_without_rust_files_reported() {
+    let extra = [("app/ui/view.rs", UNFORMATTED)];
+    // read from stdin otherwise, with nothing to read
+    assert_eq!(listed_with("subdirs-only", &extra, &["app"]), (Some(3), vec![]));
+    assert_eq!(
+        listed_with("subdirs-recursive", &extra, &["-r", "app"]),
+        (Some(1), vec!["app/ui/view.rs".to_owned()])
+    );
+    let extra = [("src/nested/.ignore", "view.rs\n")];
+    assert_eq!(listed_with("all-ignored", &extra, &["src/nested"]), (Some(3), vec![]));
+}
+
+#[test]
+fn ignored_paths_skipped() {
+    let extra = [
+        (".git/HEAD", "ref: refs/heads/main\n"),
+        (".gitignore", "# generated\n/src/gen/\n*.bak.rs\n!keep.bak.rs\n"),
+        ("src/nested/.ignore", "view.rs\n"),
+        ("src/gen/bindings.rs", UNFORMATTED),
+        ("src/old.bak.rs", UNFORMATTED),
+        ("src/keep.bak.rs", UNFORMATTED),
+        ("target/debug/build.rs", UNFORMATTED),
+        (".cargo/script.rs", UNFORMATTED),
+    ];
+    assert_eq!(
+        listed_with("ignore", &extra, &["-r", "."]),
+        (Some(1), ["build.rs", "src/keep.bak.rs", "src/lib.rs"].map(str::to_owned).to_vec())
+    );
+    assert_eq!(
+        listed_with("ignore-glob", &extra, &["src/**/*.rs"]),
+        (Some(1), ["src/keep.bak.rs", "src/lib.rs"].map(str::to_owned).to_vec())
+    );
+    assert_eq!(
+        listed_with("no-ignore", &extra, &["-r", "--no-ignore", "src"]),
+        (
+            Some(1),
+            [
+                "src/gen/bindings.rs",
+                "src/keep.bak.rs",
+                "src/lib.rs",
+                "src/nested/view.rs",
+                "src/old.bak.rs"
+            ]
+            .map(str::to_owned)
+            .to_vec()
+        )
+    );
+}
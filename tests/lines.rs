@@ -0,0 +1,50 @@
+//! The macros formatted with `--lines` & `--file-lines`, the rest of the files being left intact
+
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process::Command;
+
+const SRC: &str = "fn f() -> Html {\n    html! { <a   /> }\n}\n\n\
+                   fn g() -> Html {\n    html! { <b   /> }\n}\n";
+
+/// Runs `yew-fmt --emit stdout` with `args` before the input `src/lib.rs` in a temporary project,
+/// returns the exit code & the formatted code
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn run(name: &str, args: &[&str]) -> (Option<i32>, String) {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-lines-{name}-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    write(dir.join("src/lib.rs"), SRC).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--emit", "stdout"])
+        .args(args)
+        .arg("src/lib.rs")
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let code = stdout.strip_prefix("src/lib.rs:\n\n").unwrap_or(&stdout).to_owned();
+    (output.status.code(), code)
+}
+
+#[test]
+fn lines_followed_by_files() {
+    let (code, out) = run("single", &["--lines", "5:7"]);
+    assert_eq!(code, Some(0));
+    assert!(out.contains("html! { <a   /> }") && out.contains("html! { <b /> }"), "{out}");
+
+    let (code, out) = run("several", &["--lines", "2:2,6:6"]);
+    assert_eq!(code, Some(0));
+    assert!(out.contains("html! { <a /> }") && out.contains("html! { <b /> }"), "{out}");
+}
+
+#[test]
+fn file_lines() {
+    let (code, out) = run("file", &["--file-lines", r#"[{"file":"src/lib.rs","range":[1,3]}]"#]);
+    assert_eq!(code, Some(0));
+    assert!(out.contains("html! { <a /> }") && out.contains("html! { <b   /> }"), "{out}");
+
+    // the ranges of the other files don't apply
+    let (code, out) = run("other", &["--file-lines", r#"[{"file":"src/main.rs","range":[1,3]}]"#]);
+    assert_eq!(code, Some(0));
+    assert_eq!(out, SRC);
+}
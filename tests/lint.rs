@@ -0,0 +1,82 @@
+//! The suggestions of `yew-fmt lint` to hoist long closures out of props, never applied by it
+
+use serde_json::Value;
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::process::{Command, Output};
+
+const SRC: &str =
+    "fn f() -> Html {\n    html! {\n        <button onclick={move |_| {\n            \
+                   let a = 1;\n            let b = 2;\n            log(a + b);\n        }} />\n    \
+                   }\n}\n";
+
+/// Runs `yew-fmt lint` with `args` on `view.rs` containing [`SRC`] in a temporary directory
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn lint(name: &str, args: &[&str]) -> Output {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-lint-{name}-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    write(dir.join("view.rs"), SRC).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("lint")
+        .args(args)
+        .arg("view.rs")
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    assert_eq!(read_to_string(dir.join("view.rs")).unwrap(), SRC);
+    remove_dir_all(&dir).unwrap();
+    output
+}
+
+/// Applies the edits of a suggestion printed with `--json` to `src`
+#[allow(clippy::unwrap_used)]
+fn apply(src: &str, edits: &[Value]) -> String {
+    let offset = |pos: &Value| {
+        let line = pos["line"].as_u64().unwrap() as usize;
+        let line_start: usize = src.split_inclusive('\n').take(line - 1).map(str::len).sum();
+        line_start + pos["column"].as_u64().unwrap() as usize
+    };
+    let mut res = src.to_owned();
+    let mut edits: Vec<_> = edits.iter().collect();
+    edits.sort_by_key(|edit| offset(&edit["start"]));
+    for edit in edits.into_iter().rev() {
+        let range = offset(&edit["start"])..offset(&edit["end"]);
+        res.replace_range(range, edit["newText"].as_str().unwrap());
+    }
+    res
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn long_closure_reported() {
+    let output = lint("text", &["--max-closure-lines", "3"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "view.rs:3:17: the closure passed to `onclick` spans 5 lines, consider hoisting it into \
+         `let onclick = ...;` before the HTML\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn short_closure_accepted() {
+    let output = lint("default", &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "");
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn edits_hoist_closure() {
+    let output = lint("json", &["--max-closure-lines", "3", "--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let suggestions: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let suggestions = suggestions.as_array().unwrap();
+    let [suggestion] = &**suggestions else { panic!("{suggestions:?}") };
+    assert_eq!(suggestion["file"], "view.rs");
+    assert_eq!(
+        apply(SRC, suggestion["edits"].as_array().unwrap()),
+        "fn f() -> Html {\n    let onclick = move |_| {\n        let a = 1;\n        let b = 2;\n        \
+         log(a + b);\n    };\n    html! {\n        <button {onclick} />\n    }\n}\n"
+    );
+}
@@ -0,0 +1,78 @@
+//! The language server run with `--lsp`, driven over stdio like an editor would
+
+use serde_json::{json, Value};
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+fn message(msg: &Value) -> String {
+    let content = msg.to_string();
+    format!("Content-Length: {}\r\n\r\n{content}", content.len())
+}
+
+/// Reads the responses the server sent until it exited
+#[allow(clippy::unwrap_used)]
+fn responses(mut output: impl BufRead) -> Vec<Value> {
+    let mut responses = vec![];
+    let mut header = String::new();
+    while output.read_line(&mut header).unwrap() != 0 {
+        let len = header.trim_end().strip_prefix("Content-Length: ").unwrap().parse().unwrap();
+        output.read_line(&mut header).unwrap();
+        let mut content = vec![0; len];
+        output.read_exact(&mut content).unwrap();
+        responses.push(serde_json::from_slice(&content).unwrap());
+        header.clear();
+    }
+    responses
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn document_formatted() {
+    // rustfmt has to be given the config of the document's project rather than of the current
+    // directory, which would indent by 4 spaces
+    let dir = std::env::temp_dir().join(format!("yew-fmt-lsp-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    write(dir.join("rustfmt.toml"), "tab_spaces = 2\n").unwrap();
+    let uri = format!("file://{}", dir.join("src/lib.rs").display());
+    let src = "fn f() -> Html {\nhtml! { <div   /> }\n}\n";
+
+    let input = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": uri, "languageId": "rust", "version": 1, "text": src },
+            },
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/formatting",
+            "params": { "textDocument": { "uri": uri }, "options": {} },
+        }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ];
+    let mut server = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("--lsp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("yew-fmt should be invoked");
+    let mut stdin = server.stdin.take().unwrap();
+    stdin.write_all(input.iter().map(message).collect::<String>().as_bytes()).unwrap();
+    let responses = responses(BufReader::new(server.stdout.take().unwrap()));
+    let status = server.wait().unwrap();
+    remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+    let [init, formatting, shutdown] = &*responses else { panic!("{responses:?}") };
+    assert_eq!(init["id"], 1);
+    assert_eq!(init["result"]["capabilities"]["documentFormattingProvider"], true);
+    assert_eq!(formatting["id"], 2);
+    let new_text = json!("fn f() -> Html {\n  html! { <div /> }\n}\n");
+    assert_eq!(formatting["result"][0]["newText"], new_text, "{formatting}");
+    assert_eq!(shutdown, &json!({ "jsonrpc": "2.0", "id": 3, "result": null }));
+}
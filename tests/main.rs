@@ -3,6 +3,41 @@
 mod common;
 use common::cmp;
 
+#[test]
+fn access_spec() {
+    cmp("tests/samples/access_spec")
+}
+
+#[test]
+fn access_spec_spaced() {
+    cmp("tests/samples/access_spec_spaced")
+}
+
+#[test]
+fn attr_count_threshold() {
+    cmp("tests/samples/attr_count_threshold")
+}
+
+#[test]
+fn attr_count_threshold_visual() {
+    cmp("tests/samples/attr_count_threshold_visual")
+}
+
+#[test]
+fn attr_indent() {
+    cmp("tests/samples/attr_indent")
+}
+
+#[test]
+fn attr_indent_visual() {
+    cmp("tests/samples/attr_indent_visual")
+}
+
+#[test]
+fn autoprops() {
+    cmp("tests/samples/autoprops")
+}
+
 #[test]
 fn base_and_ext_coexist_peacefully() {
     cmp("tests/samples/base_and_ext_coexist_peacefully")
@@ -18,21 +53,86 @@ fn breaking_propagated() {
     cmp("tests/samples/breaking_propagated")
 }
 
+#[test]
+fn classes_broken() {
+    cmp("tests/samples/classes_broken")
+}
+
+#[test]
+fn classes_joined() {
+    cmp("tests/samples/classes_joined")
+}
+
+#[test]
+fn closing_bracket_same_line() {
+    cmp("tests/samples/closing_bracket_same_line")
+}
+
+#[test]
+fn collapse_children() {
+    cmp("tests/samples/collapse_children")
+}
+
+#[test]
+fn compact_closing_tags() {
+    cmp("tests/samples/compact_closing_tags")
+}
+
+#[test]
+fn control_chars() {
+    cmp("tests/samples/control_chars")
+}
+
+#[test]
+fn dioxus() {
+    cmp("tests/samples/dioxus")
+}
+
+#[test]
+fn doc_comments() {
+    cmp("tests/samples/doc_comments")
+}
+
+#[test]
+fn edition_2015() {
+    cmp("tests/samples/edition_2015")
+}
+
 #[test]
 fn elements_broken_up() {
     cmp("tests/samples/elements_broken_up")
 }
 
+#[test]
+fn first_line_column() {
+    cmp("tests/samples/first_line_column")
+}
+
+#[test]
+fn fold_region() {
+    cmp("tests/samples/fold_region")
+}
+
 #[test]
 fn for_block() {
     cmp("tests/samples/for_block")
 }
 
+#[test]
+fn hard_tabs() {
+    cmp("tests/samples/hard_tabs")
+}
+
 #[test]
 fn html_for() {
     cmp("tests/samples/html_for")
 }
 
+#[test]
+fn html_in_other_macros() {
+    cmp("tests/samples/html_in_other_macros")
+}
+
 #[test]
 fn html_let() {
     cmp("tests/samples/html_let")
@@ -43,6 +143,11 @@ fn html_match() {
     cmp("tests/samples/html_match")
 }
 
+#[test]
+fn inline_config() {
+    cmp("tests/samples/inline_config")
+}
+
 #[test]
 fn issue_1() {
     cmp("tests/samples/issue_1")
@@ -113,12 +218,152 @@ fn issue_9() {
     cmp("tests/samples/issue_9")
 }
 
+#[test]
+fn iterable_children() {
+    cmp("tests/samples/iterable_children")
+}
+
+#[test]
+fn leading_block_comments() {
+    cmp("tests/samples/leading_block_comments")
+}
+
+#[test]
+fn leptos() {
+    cmp("tests/samples/leptos")
+}
+
+#[test]
+fn long_dashed_prop_names() {
+    cmp("tests/samples/long_dashed_prop_names")
+}
+
+#[test]
+fn macro_aliases() {
+    cmp("tests/samples/macro_aliases")
+}
+
+#[test]
+fn macro_rules() {
+    cmp("tests/samples/macro_rules")
+}
+
+#[test]
+fn maud() {
+    cmp("tests/samples/maud")
+}
+
+#[test]
+fn maud_detected() {
+    cmp("tests/samples/maud_detected")
+}
+
+#[test]
+fn max_element_width() {
+    cmp("tests/samples/max_element_width")
+}
+
 #[test]
 fn max_small_heuristics() {
     cmp("tests/samples/max_small_heuristics")
 }
 
+#[test]
+fn nested_html_in_blocks() {
+    cmp("tests/samples/nested_html_in_blocks")
+}
+
+#[test]
+fn no_newline_at_eof() {
+    cmp("tests/samples/no_newline_at_eof")
+}
+
 #[test]
 fn no_small_heuristics() {
     cmp("tests/samples/no_small_heuristics")
 }
+
+#[test]
+fn off_regions() {
+    cmp("tests/samples/off_regions")
+}
+
+#[test]
+fn props_macro() {
+    cmp("tests/samples/props_macro")
+}
+
+#[test]
+fn props_one_per_line() {
+    cmp("tests/samples/props_one_per_line")
+}
+
+#[test]
+fn props_packed() {
+    cmp("tests/samples/props_packed")
+}
+
+#[test]
+fn skip_directive() {
+    cmp("tests/samples/skip_directive")
+}
+
+#[test]
+fn skip_macro_invocations() {
+    cmp("tests/samples/skip_macro_invocations")
+}
+
+#[test]
+fn skip_macros() {
+    cmp("tests/samples/skip_macros")
+}
+
+#[test]
+fn skipped_statements() {
+    cmp("tests/samples/skipped_statements")
+}
+
+#[test]
+fn snippet_file() {
+    cmp("tests/samples/snippet_file")
+}
+
+#[test]
+fn stylist() {
+    cmp("tests/samples/stylist")
+}
+
+#[test]
+fn suspense_fallback() {
+    cmp("tests/samples/suspense_fallback")
+}
+
+#[test]
+fn sycamore() {
+    cmp("tests/samples/sycamore")
+}
+
+#[test]
+fn table_dialect() {
+    cmp("tests/samples/table_dialect")
+}
+
+#[test]
+fn tabs_and_bom() {
+    cmp("tests/samples/tabs_and_bom")
+}
+
+#[test]
+fn unchecked_html() {
+    cmp("tests/samples/unchecked_html")
+}
+
+#[test]
+fn unicode_width() {
+    cmp("tests/samples/unicode_width")
+}
+
+#[test]
+fn verbatim_reindented() {
+    cmp("tests/samples/verbatim_reindented")
+}
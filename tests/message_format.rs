@@ -0,0 +1,125 @@
+//! The messages printed with `--message-format json`
+
+mod common;
+use common::run_with_stdin;
+use serde_json::Value;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn diagnostics_and_changes_as_json() {
+    let src = "fn f() -> Html {\n    html! { <div><p>{\"x\"}</div> }\n}\n\n\
+               fn g() -> Html {\n    html! { <div>   <a   /></div> }\n}\n";
+    let output = run_with_stdin(&["--check", "--message-format", "json"], src);
+    assert_eq!(output.status.code(), Some(2), "expected a parse failure");
+    let messages: Vec<Value> = output
+        .stdout
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).unwrap())
+        .collect();
+    let [diagnostic, file] = &*messages else { panic!("expected 2 messages: {messages:?}") };
+
+    assert_eq!(diagnostic["type"], "diagnostic");
+    assert_eq!(diagnostic["code"], "Y0004");
+    assert_eq!(diagnostic["labels"][0]["line"], 2);
+    assert_eq!(diagnostic["labels"][0]["column"], 28);
+    assert!(diagnostic["rendered"].as_str().unwrap().starts_with("error[Y0004]"), "{diagnostic}");
+
+    // applying the changes to the source gives the formatted code
+    assert_eq!(file["type"], "file");
+    let mut formatted = src.to_owned();
+    for change in file["changes"].as_array().unwrap().iter().rev() {
+        let (start, end) = (change["start"].as_u64().unwrap(), change["end"].as_u64().unwrap());
+        let new_text = change["newText"].as_str().unwrap();
+        formatted.replace_range(start as usize..end as usize, new_text);
+    }
+    assert!(formatted.contains("    html! {\n        <div>\n            <a />\n"), "{formatted}");
+    assert!(formatted.starts_with("fn f() -> Html {\n    html! { <div><p>"), "{formatted}");
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn sarif_log() {
+    let src = "fn f() -> Html {\n    html! { <div><p>{\"x\"}</div> }\n}\n\n\
+               fn g() -> Html {\n    html! { <div>   <a   /></div> }\n}\n";
+    let output = run_with_stdin(&["--check", "--output-format", "sarif"], src);
+    assert_eq!(output.status.code(), Some(2), "expected a parse failure");
+    let log: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert!(run["tool"]["driver"]["rules"].as_array().unwrap().iter().any(|r| r["id"] == "Y0004"));
+
+    let [diagnostic, unformatted] = &**run["results"].as_array().unwrap() else {
+        panic!("expected 2 results: {run}")
+    };
+    assert_eq!(diagnostic["ruleId"], "Y0004");
+    let region = &diagnostic["locations"][0]["physicalLocation"]["region"];
+    assert_eq!((&region["startLine"], &region["startColumn"]), (&2.into(), &28.into()));
+    assert_eq!(diagnostic["relatedLocations"][0]["message"]["text"], "the tag being closed");
+
+    assert_eq!(unformatted["ruleId"], "unformatted");
+    let region = &unformatted["locations"][0]["physicalLocation"]["region"];
+    assert_eq!((&region["startLine"], &region["endLine"]), (&6.into(), &6.into()));
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn github_annotations() {
+    let src = "fn f() -> Html {\n    html! { <div><p>{\"x\"}</div> }\n}\n\n\
+               fn g() -> Html {\n    html! { <div>   <a   /></div> }\n}\n";
+    let output = run_with_stdin(
+        &["--check", "--output-format", "github", "--stdin-filename", "src/view.rs"],
+        src,
+    );
+    assert_eq!(output.status.code(), Some(2), "expected a parse failure");
+    let annotations = String::from_utf8(output.stdout).unwrap();
+    let [error, warning] = &*annotations.lines().collect::<Vec<_>>() else {
+        panic!("expected 2 annotations: {annotations}")
+    };
+    assert_eq!(
+        *error,
+        "::error file=src/view.rs,line=2,col=28,endLine=2,endColumn=31,title=Y0004\
+         ::mismatched closing tag, expected `</p>`"
+    );
+    assert!(warning.starts_with("::warning file=src/view.rs,line=6,endLine=6,title=yew-fmt::"));
+    assert!(warning.contains("should be:%0A    html! {%0A        <div>%0A"), "{warning}");
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn junit_report() {
+    let run = |src: &str| {
+        let output = run_with_stdin(
+            &["--check", "--output-format", "junit", "--stdin-filename", "src/view.rs"],
+            src,
+        );
+        (output.status.code(), String::from_utf8(output.stdout).unwrap())
+    };
+
+    let (code, report) = run("fn f() -> Html {\n    html! { <div><p>{\"x\"}</div> }\n}\n\n\
+                              fn g() -> Html {\n    html! { <div>   <a   /></div> }\n}\n");
+    assert_eq!(code, Some(2), "expected a parse failure");
+    assert!(report.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<testsuites>\n"));
+    assert!(report.contains("<testsuite name=\"yew-fmt\" tests=\"1\" failures=\"1\" errors=\"1\">"));
+    assert!(report.contains("<testcase name=\"src/view.rs\" classname=\"yew-fmt\">"), "{report}");
+    assert!(
+        report.contains("-    html! { &lt;div&gt;   &lt;a   /&gt;&lt;/div&gt; }\n"),
+        "{report}"
+    );
+    assert!(report.contains("+            &lt;a /&gt;\n"), "{report}");
+    assert!(
+        report.contains(
+            "<error message=\"mismatched closing tag, expected `&lt;/p&gt;`\" \
+                             type=\"Y0004\">error[Y0004]"
+        ),
+        "{report}"
+    );
+
+    // a formatted file is a passing test case
+    let (code, report) = run("fn f() -> Html {\n    html! { <div /> }\n}\n");
+    assert_eq!(code, Some(0), "{report}");
+    assert!(report.contains(
+        "tests=\"1\" failures=\"0\" errors=\"0\">\n    \
+                             <testcase name=\"src/view.rs\" classname=\"yew-fmt\" />\n"
+    ));
+}
@@ -0,0 +1,48 @@
+//! The files of the modules declared with `mod name;`, formatted along with the files declaring
+//! them unless `--skip-children` is passed, & only with `--follow-mods` when formatting lines
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::process::Command;
+
+const SRC: &str = "fn f() -> Html {\n    html! { <div   /> }\n}\n";
+const FORMATTED: &str = "fn f() -> Html {\n    html! { <div /> }\n}\n";
+
+/// Runs `yew-fmt` with `args` on the root `src/lib.rs` of a temporary crate with the modules
+/// `view` & `icons`, the latter's file's path set with `#[path]`, returns whether each of the 3
+/// files has been formatted
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn run(name: &str, args: &[&str]) -> [bool; 3] {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-modules-{name}-{}", std::process::id()));
+    create_dir_all(dir.join("src/generated")).unwrap();
+    let files = ["src/lib.rs", "src/view.rs", "src/generated/icons.rs"];
+    let root = format!("mod view;\n#[path = \"generated/icons.rs\"]\nmod icons;\n\n{SRC}");
+    write(dir.join(files[0]), &root).unwrap();
+    write(dir.join(files[1]), SRC).unwrap();
+    write(dir.join(files[2]), SRC).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(args)
+        .arg("src/lib.rs")
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    let formatted = files.map(|file| read_to_string(dir.join(file)).unwrap());
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    [formatted[0].ends_with(FORMATTED), formatted[1] == FORMATTED, formatted[2] == FORMATTED]
+}
+
+#[test]
+fn children_formatted() {
+    assert_eq!(run("default", &[]), [true, true, true]);
+}
+
+#[test]
+fn children_skipped() {
+    assert_eq!(run("skip", &["--skip-children"]), [true, false, false]);
+}
+
+#[test]
+fn children_followed_for_lines() {
+    assert_eq!(run("lines", &["--lines", "1:10"]), [true, false, false]);
+    assert_eq!(run("follow", &["--lines", "1:10", "--follow-mods"]), [true, true, true]);
+}
@@ -0,0 +1,35 @@
+//! The macros formatted with `--only`, the look-alike macros of other libraries being left intact
+
+mod common;
+use common::run_with_stdin;
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn matching_paths_formatted() {
+    let src = "use yew::prelude::*;\n\n\
+               fn f() -> Html {\n    html! { <div   /> }\n}\n\n\
+               fn g() -> Markup {\n    other::html! { <div   /> }\n}\n";
+    let output = run_with_stdin(&["--only", "yew::html"], src);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "use yew::prelude::*;\n\n\
+         fn f() -> Html {\n    html! { <div /> }\n}\n\n\
+         fn g() -> Markup {\n    other::html! { <div   /> }\n}\n"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn imported_look_alike_skipped() {
+    let src = "use other::html;\n\nfn f() -> Html {\n    html! { <div   /> }\n}\n";
+    let output = run_with_stdin(&["--only", "yew::html"], src);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), src);
+
+    let output = run_with_stdin(&["--only", "yew::html", "--only", "other::html"], src);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "use other::html;\n\nfn f() -> Html {\n    html! { <div /> }\n}\n"
+    );
+}
@@ -0,0 +1,67 @@
+//! The `yew.*` options printed by `--print-config` after rustfmt's, & the options listed by
+//! `--help-config`
+
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process::Command;
+
+/// Runs `yew-fmt --print-config` with `args` in a temporary project with configs in its root & in
+/// `src`, returns the `yew` table printed
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn yew_table(name: &str, args: &[&str]) -> Vec<String> {
+    let dir =
+        std::env::temp_dir().join(format!("yew-fmt-print-config-{name}-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    write(dir.join("rustfmt.toml"), "[yew]\nhtml_width = 80\n").unwrap();
+    write(dir.join("src/yew-fmt.toml"), "self_close_elements = false\n").unwrap();
+    write(dir.join("src/lib.rs"), "fn f() {}\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("--print-config")
+        .args(args)
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout.lines().skip_while(|line| *line != "[yew]").skip(1).map(str::to_owned).collect()
+}
+
+#[test]
+fn yew_options_printed() {
+    // `max_element_width` & `attr_count_threshold` are left out, being unset by default
+    let default = yew_table("default", &["default"]);
+    assert_eq!(default.len(), 21);
+    assert_eq!(default[0], "html_width = 100");
+    assert!(default.contains(&"self_close_elements = true".to_owned()));
+
+    let current = yew_table("current", &["current", "src/lib.rs"]);
+    assert_eq!(current.len(), 21);
+    assert_eq!(current[0], "html_width = 80");
+    assert!(current.contains(&"self_close_elements = false".to_owned()));
+
+    // only the options that differ from the defaults, as found from the current directory
+    assert_eq!(yew_table("minimal", &["minimal"]), ["html_width = 80"]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn all_options_listed() {
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("--help-config")
+        .output()
+        .expect("yew-fmt should be invoked");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let listed: Vec<&str> = stdout.lines().filter(|line| !line.starts_with(' ')).collect();
+    assert!(listed.contains(&"tab_spaces: an integer, default: 4"));
+    assert!(listed.contains(&"yew.max_element_width: an integer, default: none"));
+    // every option printed by `--print-config` has the same default in the listing
+    for option in yew_table("help", &["default"]) {
+        let (key, value) = option.split_once(" = ").unwrap();
+        assert!(
+            listed.iter().any(|line| line.starts_with(&format!("yew.{key}: "))
+                && line.ends_with(&format!("default: {value}"))),
+            "`yew.{key}` isn't listed with its default {value}"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! The flags of rustfmt that yew-fmt accepts without supporting them, so that tools passing them
+//! to rustfmt keep working with yew-fmt
+
+mod common;
+use common::run_with_stdin;
+
+const SRC: &str = "fn f() -> Html {\n    html! { <div   /> }\n}\n";
+const FORMATTED: &str = "fn f() -> Html {\n    html! { <div /> }\n}\n";
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn unstable_flags_ignored() {
+    let output = run_with_stdin(
+        &[
+            "--unstable-features",
+            "--skip-children",
+            "--error-on-unformatted",
+            "--style-edition",
+            "2024",
+        ],
+        SRC,
+    );
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), FORMATTED);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn emit_json_falls_back() {
+    let output = run_with_stdin(&["--emit", "json"], SRC);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), FORMATTED);
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Warning: `--emit json` isn't supported, falling back to the default\n"
+    );
+}
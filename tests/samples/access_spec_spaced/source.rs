@@ -0,0 +1,16 @@
+// config: yew.space_after_access_spec=true
+
+use yew::prelude::*;
+
+#[function_component]
+fn Input() -> Html {
+    let value = use_state(String::new);
+    html! {
+        <>
+            <input ~value={(*value).clone()} />
+            <input ~ value={(*value).clone()} ~checked={true} />
+            <input type="text" class="form-control" ~value={(*value).clone()} ~ placeholder="Type something here" />
+            <@{"input"} ~ value={(*value).clone()} />
+        </>
+    }
+}
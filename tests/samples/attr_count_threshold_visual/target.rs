@@ -0,0 +1,18 @@
+// config: yew.attr_count_threshold=3,yew.attr_indent_style="Visual"
+use yew::prelude::*;
+
+#[function_component]
+fn Form() -> Html {
+    html! {
+        <form class="form" onsubmit={onsubmit}>
+            <input type="text"
+                   value={(*value).clone()}
+                   oninput={oninput}
+                   placeholder="Your name" />
+            <Button kind={ButtonKind::Primary} disabled={!valid} onclick={onclick}>
+                { "Submit" }
+            </Button>
+            <img src="logo.svg" alt="Logo" width=16 />
+        </form>
+    }
+}
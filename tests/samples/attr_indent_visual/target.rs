@@ -0,0 +1,16 @@
+// config: yew.attr_indent_style="Visual"
+use yew::prelude::*;
+
+#[function_component]
+fn Form() -> Html {
+    html! {
+        <form class="form" onsubmit={onsubmit}>
+            <input type="text" value={(*value).clone()} oninput={oninput} placeholder="Your name" />
+            <Button kind={ButtonKind::Primary}
+                    disabled={!valid}
+                    onclick={Callback::from(move |_| submit.emit(()))}>
+                { "Submit" }
+            </Button>
+        </form>
+    }
+}
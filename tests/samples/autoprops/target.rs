@@ -0,0 +1,33 @@
+#[autoprops]
+#[function_component]
+fn Greeting(
+    name: &AttrValue,
+    #[prop_or(AttrValue::Static("Hello"))] greeting: &AttrValue,
+    #[prop_or_default] count: usize,
+) -> Html {
+    html! {
+        <p class="greeting">
+            { greeting }
+            { ", " }
+            { name }
+            { "! You have " }
+            { count }
+            { " new messages" }
+        </p>
+    }
+}
+
+#[function_component]
+fn App() -> Html {
+    let props = GreetingProps::builder()
+        .name("World")
+        .greeting("Hi")
+        .count(3)
+        .build();
+    html! {
+        <>
+            <Greeting name="World" greeting="Hi" count=3 />
+            <Greeting ..props />
+        </>
+    }
+}
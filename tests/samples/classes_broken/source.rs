@@ -0,0 +1,13 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Nav() -> Html {
+    let active = use_state(|| false);
+    html! {
+        <nav class={classes!("navbar", "is-fixed-top", "has-shadow", active.then_some("is-active"), "is-transparent")}>
+            <a class={classes!("navbar-item")} href="/">{ "Home" }</a>
+            <a class={classes!()}>{ "Empty" }</a>
+            <div class={yew::classes!["navbar-menu", "is-hoverable", "is-a-very-long-class-name-that-overflows"]} />
+        </nav>
+    }
+}
@@ -0,0 +1,17 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Tabs() -> Html {
+    let selected = use_state(|| 0);
+    html! {
+        <div class={classes!(
+            "tabs",
+            "is-boxed",
+        )}>
+            <ul class={classes!["tab-list", (*selected == 0).then_some("first"),]}>
+                <li class={classes!{"tab"}}>{ "One" }</li>
+                <li class={classes!("tab", "is-a-rather-long-class", (*selected == 1).then_some("is-active"),)}>{ "Two" }</li>
+            </ul>
+        </div>
+    }
+}
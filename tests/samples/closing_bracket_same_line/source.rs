@@ -0,0 +1,13 @@
+// config: yew.closing_bracket_same_line=true
+use yew::prelude::*;
+
+#[function_component]
+fn Form() -> Html {
+    html! {
+        <form class="form" onsubmit={onsubmit}><input type="text" value={(*value).clone()} oninput={oninput} placeholder="Your name" autocomplete="name" />
+        <Button kind={ButtonKind::Primary} disabled={!valid} onclick={Callback::from(move |_| submit.emit(()))}>{ "Submit" }</Button>
+        <@{tag} class={classes!("panel", "panel-bordered")} style="display: flex" onclick={onclick} // why
+        />
+        </form>
+    }
+}
@@ -0,0 +1,23 @@
+// config: yew.collapse_children=true
+
+fn f() -> Html {
+    html! {
+        <table><tr><td>{ first_value }</td><td>{ second_value }</td><td>{ third_value }</td><td>{ fourth }</td></tr></table>
+    }
+}
+fn g() -> Html {
+    html! {
+        <ul><li><a href="/a">{ "A" }</a></li><li><a href="/b">{ "B" }</a></li><li><a href="/c">{ "C" }</a></li><li>{ "D" }</li></ul>
+    }
+}
+fn f() -> Html {
+    html! { <div><p>{ "x" }</p></div> }
+}
+fn g() -> Html {
+    html! {
+        <section class="cards">
+            <div class="card"><h2>{ "Title" }</h2><p>{ "A description that is long enough to overflow the line" }</p></div>
+            <footer><span>{ "a" }</span><span>{ "b" }</span></footer>
+        </section>
+    }
+}
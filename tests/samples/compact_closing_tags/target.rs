@@ -0,0 +1,19 @@
+// config: yew.compact_closing_tags=true
+
+fn f() -> Html {
+    html! {
+        <div class="container">
+            <p>{ "first" }</p>
+            <span>{ "short" }</span></div>
+    }
+}
+fn g() -> Html {
+    html! {
+        <>
+            <p>{ "first" }</p>
+            <div>
+                <p>{ "nested" }</p>
+                <p>{ "multi-line last child" }</p></div>
+        </>
+    }
+}
@@ -0,0 +1,8 @@
+use
+yew::prelude::*;
+
+#[function_component]
+fn Comp() -> Html {
+    html! {<p>{"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"}</p>};
+    html! {<p class="x	y" title={"\r\t\u{1F600}"}>{"\r\n"}{""}</p>}
+}
@@ -0,0 +1,34 @@
+// config: yew.view_flavor="Dioxus"
+
+fn app() -> Element {
+    let mut count = use_signal(|| 0);
+    rsx! {
+        div {
+            class: "container",
+            id: "main",
+            h1 { "High-Five counter: {count}" }
+            button { onclick: move |_| count += 1, "Up high!" }
+            button {
+                onclick: move |_| { count -= 1; log::info!("down") },
+                disabled: count() == 0,
+                "Down low!"
+            }
+            // a comment
+            if count() > 5 {
+                p { "Big" }
+            } else if count() < 0 {
+                p { "Negative" }
+            } else {
+                "small"
+            }
+            for item in items.iter() {
+                li { key: "{item.id}", "{item.name}" }
+            }
+            MyComponent { name: "x", value: 42, ..props }
+            input { r#type: "text", value, "data-x": "1" }
+            my-element {}
+            {children}
+            br {}
+        }
+    }
+}
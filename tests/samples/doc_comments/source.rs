@@ -0,0 +1,43 @@
+// config: yew.format_doc_comments=true
+
+//! A list of items.
+//!
+//! ```
+//! # use yew::prelude::*;
+//! let list = html! { <ul class="list"><li>{ "first" }</li><li>{ "second" }</li><li>{ "third" }</li></ul> };
+//! ```
+
+use yew::prelude::*;
+
+/// Renders a greeting.
+///
+/// ```rust,no_run
+/// # use yew::prelude::*;
+/// # fn main() {
+/// let greeting = html! {
+/// <div class="greeting"><h1>{ "Hello" }</h1><p>{ "Nice to see you again, have a nice day" }</p></div>
+/// };
+/// # }
+/// ```
+///
+/// The markup isn't touched:
+/// ```html
+/// <div><p>not' rust</p></div>
+/// ```
+///
+/// Neither is code that fails to compile on purpose:
+/// ```compile_fail
+/// html! { <div> };
+/// ```
+#[function_component]
+pub fn Greeting() -> Html {
+    html! { <div class="greeting"><h1>{ "Hello" }</h1><p>{ "Nice to see you again, have a nice day" }</p></div> }
+}
+
+pub struct Wrapper {
+    /// Defaults to:
+    ///     ```
+    ///     html! { <span>{"empty"}</span> }
+    ///     ```
+    pub fallback: Html,
+}
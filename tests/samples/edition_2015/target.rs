@@ -0,0 +1,15 @@
+// config: edition=2015
+
+use yew::prelude::*;
+
+#[function_component]
+fn Comp(props: &Props) -> Html {
+    let async = props.async;
+    let r#try = use_state(|| 0);
+    html! {
+        <div class="status">
+            <Spinner async={async} />
+            <span>{ *try }</span>
+        </div>
+    }
+}
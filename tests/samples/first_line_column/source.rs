@@ -0,0 +1,3 @@
+// config: yew.html_width=80
+
+let x = (html! { <a>     {"x"}      </a> }, html! { <b class="aa">{"bbbbbbb"}</b> });
@@ -0,0 +1,15 @@
+use
+yew::prelude::*;
+
+#[function_component]
+fn Icon() -> Html {
+    html! {
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none" stroke="currentColor">
+            // yew-fmt: fold-start
+            <path stroke-linecap="round" stroke-linejoin="round" d="M3.75 6.75h16.5M3.75 12h16.5m-16.5 5.25h16.5" />
+            <g><path d="M12 4.5v15m7.5-7.5h-15" /><circle cx="12" cy="12" r="9" stroke-width="1.5" /></g>
+            // yew-fmt: fold-end
+            <path stroke-linecap="round" stroke-linejoin="round" d="M3.75 6.75h16.5M3.75 12h16.5m-16.5 5.25h16.5" />
+        </svg>
+    }
+}
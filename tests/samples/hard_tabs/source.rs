@@ -0,0 +1,15 @@
+// config: hard_tabs=true
+use yew::prelude::*;
+
+#[function_component]
+fn Card(props: &CardProps) -> Html {
+    let items = props.items.iter().map(|item| html! { <li key={item.id}>{ &item.name }</li> });
+    html! {
+        <div class="card"><h1>{ &props.title }</h1>
+        <ul>{ for items }</ul>
+        <footer onclick={Callback::from(|_| {
+            log::info!("clicked");
+        })}>{ "footer" } // the end
+        </footer></div>
+    }
+}
@@ -0,0 +1,16 @@
+fn f() {
+    let items = vec![
+        html! { <div><p/></div> },
+        yew::html! { <span>{ 1 }</span> },
+    ];
+    tokio::select! {
+        _ = a => html! { <div><p/></div> },
+    }
+    unknown! { html! { <div>{ $x }</div> } }
+}
+
+macro_rules! m {
+    ($x:expr) => {
+        html! { <div><p/>{ $x }</div> }
+    };
+}
@@ -0,0 +1,6 @@
+fn f() -> Html {
+    // yew-fmt: html_width=30, self_close_elements=false
+    let narrow = html! { <div><p></p><span>{ "text" }</span></div> };
+    let default = html! { <div><p></p><span>{ "text" }</span></div> };
+    html! { <>{ narrow }{ default }</> }
+}
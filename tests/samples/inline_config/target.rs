@@ -0,0 +1,18 @@
+fn f() -> Html {
+    // yew-fmt: html_width=30, self_close_elements=false
+    let narrow = html! {
+        <div>
+            <p></p>
+            <span>
+                { "text" }
+            </span>
+        </div>
+    };
+    let default = html! {
+        <div>
+            <p />
+            <span>{ "text" }</span>
+        </div>
+    };
+    html! { <>{ narrow }{ default }</> }
+}
@@ -0,0 +1,23 @@
+// config: yew.use_small_heuristics="Default"
+
+#[function_component]
+fn Wrapper(props: &Props) -> Html {
+    html! { <Comp>{ for props.children.iter() }</Comp> }
+}
+
+#[function_component]
+fn List(props: &Props) -> Html {
+    html! {
+        <ul class="list">{ for props.items.iter().map(|item| html! { <li>{ item }</li> }) }</ul>
+    }
+}
+
+#[function_component]
+fn Labelled(props: &Props) -> Html {
+    html! {
+        <Comp>
+            <span>{ "label" }</span>
+            { for props.children.iter() }
+        </Comp>
+    }
+}
@@ -0,0 +1,16 @@
+fn f() -> Html {
+    html! {
+        <div>
+            /* owned by the paragraph */
+            <p>{ "a" }</p>
+            /* inline */<span>{ "b" }</span>
+            <button
+                /* owned by the prop */
+                class="btn"
+                onclick={onclick}
+            >
+                { "c" }
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,16 @@
+// config: yew.view_flavor="Leptos"
+
+use leptos::*;
+
+#[component]
+fn Counter(initial: i32) -> impl IntoView {
+    let (count, set_count) = create_signal(initial);
+    view! {
+        <div class="counter" class:big={move || count.get() > 10}><button on:click=move |_| set_count.update(|n| *n += 1)>"Click me: " {move || count.get()}</button>
+        <Show when=move || count.get() % 2 == 0 fallback=|| view! { <p>"Odd"</p> }><p>"Even"</p></Show>
+        <input type="text" prop:value=count />
+        <For each=move || (0..count.get()) key=|n| *n let:n><span>{n}</span></For>
+        </div>
+        <></>
+    }
+}
@@ -0,0 +1,23 @@
+fn f() -> Html {
+    html! {
+        <div
+            data-some-extremely-long-configuration-flag-name-that-goes-on-and-on-forever-and-ever={x}
+            aria-another-very-long-dashed-attribute-name="value"
+        />
+    }
+}
+fn g() -> Html {
+    html! {
+        <div>
+            <Comp
+                data-some-extremely-long-configuration-flag-name={x}
+                data-another-long-one-here={y}
+            />
+        </div>
+    }
+}
+fn h() -> Html {
+    html! {
+        <input data-x-y-z="1" ~data-with-access-spec-applied-and-a-long-name-to-go-with-it={value} />
+    }
+}
@@ -0,0 +1,23 @@
+// config: yew.macro_aliases={view="html"}
+
+use yew::html as h;
+
+fn f() -> Html {
+    let a = ::yew::html! {
+        <div>
+            <p />
+        </div>
+    };
+    let b = h! {
+        <div>
+            <p />
+        </div>
+    };
+    let c = view! {
+        <div>
+            <p />
+        </div>
+    };
+    let d = other! { <div><p/></div> };
+    html! { <>{ a }{ b }{ c }{ d }</> }
+}
@@ -0,0 +1,16 @@
+// config: yew.format_macro_rules=true
+
+macro_rules! icon {
+    ($name:literal) => {
+        html! { <svg class="icon"><use href={concat!("#", $name)} /></svg> }
+    };
+    () => {
+        html! { <span class="icon-placeholder"><svg class="icon"></svg><span>{"missing icon"}</span></span> }
+    };
+}
+
+macro_rules! list {
+    ($($item:expr),*) => {
+        html! { <ul>$(<li>{ $item }</li>)*</ul> }
+    };
+}
@@ -0,0 +1,27 @@
+// config: yew.view_flavor="Maud",edition=2015
+
+use maud::{html, Markup, DOCTYPE};
+
+fn page(title: &str, items: &[Item], user: Option<&User>) -> Markup {
+    html! {
+        (DOCTYPE)
+        html lang="en" {
+            head { meta charset="utf-8"; title { (title) } link rel="stylesheet" href="/style.css"; }
+            body.dark#root data-page-id=(page_id) {
+                h1{"Hello, " (user.map_or("stranger", |u| &u.name)) "!"}
+                // a comment
+                .container.col-2 {
+                    @if let Some(user) = user { p.greeting { "Welcome back, " (user.name) } } @else if items.is_empty() { p { "Nothing here" } } @else { a href={ "/login?next=" (title) } { "Log in" } }
+                    ul { @for item in items { li.item.active[item.active] { (item.name) } } }
+                    @let total = items.len();
+                    @while false { br; }
+                    @match total { 0 => { "none" }, n if n > 10 => strong { "many" } _ => "some", }
+                    input type="checkbox" name="done" checked[done] disabled;
+                    option value=[selected.as_ref()] { }
+                    my-element { }
+                    { "grouped" " text" }
+                }
+            }
+        }
+    }
+}
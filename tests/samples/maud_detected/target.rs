@@ -0,0 +1,15 @@
+use yew::prelude::*;
+
+fn badge(count: usize) -> maud::Markup {
+    maud::html! {
+        span.badge { (count) }
+        @if count > 99 {
+            "+"
+        }
+    }
+}
+
+#[function_component]
+fn Badge() -> Html {
+    html! { <span class="badge">{ "0" }</span> }
+}
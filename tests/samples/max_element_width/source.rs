@@ -0,0 +1,13 @@
+// config: yew.max_element_width=40
+
+#[function_component]
+fn Form() -> Html {
+    html! {
+        <form class="form" method="post" action="/submit" enctype="multipart/form-data">
+            <p>{ "Text children still get to use the whole width of the line" }</p>
+            <input type="text" name="name" required=true />
+            <input type="submit" />
+            <@{tag} class="container" id="main" />
+        </form>
+    }
+}
@@ -0,0 +1,45 @@
+fn f() -> Html {
+    html! {
+        <div>
+            <Comp
+                render={html! {
+                    <div>
+                        <p />
+                    </div>
+                }}
+            />
+            <Comp
+                render={move |x| html! {
+                    <div>
+                        <p />
+                        { x }
+                    </div>
+                }}
+            />
+            { for items.iter().map(
+                |item| html! {
+                    <li>
+                        <p />
+                        { item }
+                    </li>
+                }
+            ) }
+            { items.iter().map(|item| html! {
+                <li>
+                    <p />
+                    { item }
+                </li>
+            }).collect::<Html>() }
+            { if a { html! {
+                <div>
+                    <p />
+                </div>
+            } } else { html! {} } }
+            { match a { Some(x) => html! {
+                <div>
+                    <p />
+                </div>
+            }, None => html! {} } }
+        </div>
+    }
+}
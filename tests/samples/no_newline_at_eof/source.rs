@@ -0,0 +1,5 @@
+// config: yew.newline_at_eof=false
+
+let list = html! { <ul><li>{ "a" }</li></ul> };
+
+
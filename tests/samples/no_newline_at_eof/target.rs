@@ -0,0 +1,9 @@
+// config: yew.newline_at_eof=false
+
+let list = html! {
+    <ul>
+        <li>{ "a" }</li>
+    </ul>
+};
+
+
@@ -0,0 +1,26 @@
+fn f() -> Html {
+    html! {
+        <ul>
+            <li>{ "formatted" }</li>
+            // yew-fmt: off
+            <li  class="a">{"left"}</li>
+            <li    class="b">{"as is"}</li>
+            // yew-fmt: on
+            <li class="c">{ "formatted again" }</li>
+        </ul>
+    }
+}
+
+// yew-fmt: off
+fn g() -> Html {
+    html! { <div><p/><p/><p>{"not formatted at all, even though this line is way too long"}</p></div> }
+}
+// yew-fmt: on
+
+fn h() -> Html {
+    html! {
+        <div>
+            <p />
+        </div>
+    }
+}
@@ -0,0 +1,12 @@
+fn f() {
+    let a = yew::props! { Props { a: 1, b } };
+    let b = props! {
+        ListProps {
+            items: vec![1, 2, 3],
+            on_select: ctx.link().callback(|i| Msg::Select(i)),
+            children: html! { <p /> }
+        }
+    };
+    let c = props! { Props { ..Default::default() } };
+    let d = props!(Props { a: 1 });
+}
@@ -0,0 +1,18 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Grid() -> Html {
+    html! {
+        <Cell
+            x=0
+            y=0
+            w=1
+            h=1
+            row_span=2
+            col_span=2
+            align={Align::Start}
+            justify={Justify::End}
+            class={classes!("cell", "cell-bordered")}
+        />
+    }
+}
@@ -0,0 +1,16 @@
+// config: yew.single_attribute_per_line=false
+use yew::prelude::*;
+
+#[function_component]
+fn Grid() -> Html {
+    html! {
+        <>
+            <Cell x={0} y={0} w={1} h={1} row_span={2} col_span={2} align={Align::Start} justify={Justify::End} class={classes!("cell", "cell-bordered")} />
+            <Cell x={1} y={0} w={1} h={1} onclick={Callback::from(|_| {
+                log::info!("clicked");
+            })} align={Align::Start} justify={Justify::End} class="cell" />
+            <div class={classes!("panel", "panel-bordered")} style="display: flex; flex-direction: column" id="panel" // the panel
+            />
+        </>
+    }
+}
@@ -0,0 +1,24 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Table() -> Html {
+    html! {
+        <table class="matrix">
+            // yew-fmt: skip
+            <tr><td>{ 1 }</td><td>{ 0 }</td></tr>
+            // yew-fmt: skip
+            <tr>
+                <td>{ 0 }</td>  <td>{ 1 }</td>
+            </tr>
+            <tr>
+                <td>{ 0 }</td>
+                <td>{ 0 }</td>
+            </tr>
+            // a normal comment
+            <tr>
+                <td>{ 1 }</td>
+                <td>{ 1 }</td>
+            </tr>
+        </table>
+    }
+}
@@ -0,0 +1,15 @@
+// config: skip_macro_invocations=["html"]
+
+use yew::prelude::*;
+
+fn skipped() -> Html {
+    html! {
+        <div   class="kept as is"><p>{ "Hello" }</p></div>
+    }
+}
+
+fn formatted() -> Html {
+    yew::html! {
+        <div   class="formatted"><p>{ "Hello" }</p></div>
+    }
+}
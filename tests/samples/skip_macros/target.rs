@@ -0,0 +1,32 @@
+use yew::prelude::*;
+
+#[rustfmt::skip::macros(html)]
+fn skipped() -> Html {
+    html! {
+        <div   class="kept as is"><p>{ "Hello" }</p></div>
+    }
+}
+
+#[rustfmt::skip::macros(html_nested)]
+fn only_nested_skipped() -> Html {
+    html! {
+        <div class="formatted">
+            <p>{ "Hello" }</p>
+        </div>
+    }
+}
+
+#[rustfmt::skip::macros(html)]
+mod skipped_module {
+    fn inner() -> Html {
+        html! {<span   />}
+    }
+}
+
+fn formatted() -> Html {
+    html! {
+        <div class="formatted">
+            <p>{ "Hello" }</p>
+        </div>
+    }
+}
@@ -0,0 +1,20 @@
+use yew::prelude::*;
+
+fn statements(flag: bool) -> Option<Html> {
+    #[rustfmt::skip]
+    let kept = html! { <div   class="kept as is"><p>{ "Hello" }</p></div> };
+    #[rustfmt::skip]
+    flag.then(|| html! { <div   class="kept as is"><p>{ "Hello" }</p></div> });
+    #[allow(unused_braces)]
+    flag.then(|| html! {
+        <div class="formatted">
+            <p>{ "Hello" }</p>
+        </div>
+    });
+    #[rustfmt::skip]
+    fn inner() -> Html {
+        html! { <span   /> }
+    }
+    #[rustfmt::skip]
+    Some(html! { <div   class="kept as is"><p>{ "Hello" }</p></div> })
+}
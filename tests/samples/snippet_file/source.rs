@@ -0,0 +1,6 @@
+// meant to be used as `include!("snippet_file/source.rs")` inside of a function
+let items = props.items.iter().map(|item| html!{<li key={item.id}>{&item.name}</li>});
+html! {
+    <div class="card"><h1>{ &props.title }</h1>
+    <ul>{for items}</ul></div>
+}
@@ -0,0 +1,27 @@
+// config: yew.format_stylist=true
+
+use stylist::{css, style};
+use yew::prelude::*;
+
+#[function_component]
+fn Card() -> Html {
+    let accent = "blue";
+    let card = css!(r#"
+  display:flex;   padding : 4px   8px;
+        .title{ font-weight: bold; color: ${accent}; }
+
+
+  &:hover {background: url("data:image/png;base64,AAA"); /* hover */ }
+    "#);
+    let short = css!("color: red;");
+    let args = style!(
+        "
+        margin: 0;
+          @media (max-width: 600px) { margin: 4px }
+        ",
+        accent = "blue"
+    );
+    html! {
+        <div class={card}><span class={short}>{ "Card" }</span><p class={args}>{ "Body" }</p></div>
+    }
+}
@@ -0,0 +1,25 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Comp() -> Html {
+    html! {
+        <Suspense fallback={html! { <div class="loading">{ "Loading..." }</div> }}>
+            <Content />
+        </Suspense>
+    };
+    html! {
+        <Suspense
+            fallback={html! {
+                <div class="loading-indicator-with-a-long-class-name">
+                    { "Loading the content, please wait..." }
+                </div>
+            }}
+        >
+            <List
+                render={Callback::from(|item: Item| html! { <li key={item.id}>{ item.name }</li> })}
+            />
+            <Empty fallback={html!()} />
+            <Empty fallback={html! {}} />
+        </Suspense>
+    }
+}
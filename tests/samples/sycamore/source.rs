@@ -0,0 +1,19 @@
+// config: yew.view_flavor="Sycamore"
+
+#[component]
+fn Counter() -> View {
+    let count = create_signal(0);
+    view! {
+        div(class="counter",id="main") {
+            p { "Count: " (count.get()) }
+            button(on:click=move |_| count.set(count.get() + 1), class="btn btn-primary", disabled=count.get() > 10,) { "+" }
+            br()
+            // a comment
+            input(bind:value=value, r#type="text")
+            MyComponent(value=123, ..attrs)
+            my-element(data-x="1") {}
+            ul { li { "a" } li { "b" } }
+        }
+    }
+}
+fn old(cx: Scope) -> View<G> { view! { cx, p { "Hi" } } }
@@ -0,0 +1,18 @@
+// config: yew.dialect_macros={Table="Table"}
+
+use crate::table;
+
+#[function_component]
+fn Users(props: &Props) -> Html {
+    let header = Table! { tr { <b>{ "Name" }</b>, <b>{ "Age" }</b> } };
+    let rows = Table! {
+        tr { { &props.users[0].name }, { props.users[0].age } }
+        // the second user
+        tr {
+            <a href={props.users[1].url.clone()} class="user-link">{ &props.users[1].name }</a>,
+            <span class="age">{ props.users[1].age }</span>
+        }
+        tr {}
+    };
+    html! { <table>{ header }{ rows }</table> }
+}
@@ -0,0 +1,7 @@
+﻿// meant to be `include!`d, starts with a BOM & is indented with tabs
+let greeting = html! { <p class="café">{ "héllo" }</p> };
+if show {
+	let card = html! {
+		<div class="card"><h1>{ "été" }</h1>
+		<p>{greeting}</p></div> };
+}
@@ -0,0 +1,23 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Comp() -> Html {
+    html! {
+        <div class="markdown">
+            { unsafe { render_raw(ptr) } }
+            { Html::from_html_unchecked(
+                AttrValue::from(
+                    "<p>Some <b>very</b> long   markdown\trendered   content that overflows</p>"
+                )
+            ) }
+            { Html::from_html_unchecked(
+                AttrValue::from(markdown_to_html(&props.source, &options, &plugins_list))
+            ) }
+        </div>
+    }
+}
+
+#[function_component]
+fn Short() -> Html {
+    html! { <p>{ Html::from_html_unchecked(AttrValue::from("<b>hi</b>")) }</p> }
+}
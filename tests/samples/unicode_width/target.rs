@@ -0,0 +1,20 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Greeting() -> Html {
+    html! { <p class="greeting">{ "こんにちは、世界！ようこそ、こんにちは" }</p> }
+}
+
+#[function_component]
+fn Farewell() -> Html {
+    html! {
+        <p class="farewell">
+            { "さようなら、世界！またね、さようなら、またね、さようなら、またね" }
+        </p>
+    }
+}
+
+#[function_component]
+fn Party() -> Html {
+    html! { <p class="party">{ "🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉🎉" }</p> }
+}
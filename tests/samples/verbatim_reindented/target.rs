@@ -0,0 +1,17 @@
+use yew::prelude::*;
+
+#[function_component]
+fn Comp() -> Html {
+    html! {
+        <button
+            class="btn"
+            onclick={move |_| {
+                counter.set(*counter + 1);
+                log!("clicked
+                  twice");
+            }}
+        >
+            { "+1" }
+        </button>
+    }
+}
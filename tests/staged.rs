@@ -0,0 +1,72 @@
+//! The formatting of the staged contents of the files with `--staged`
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::path::Path;
+use std::process::{Command, Output};
+
+const UNFORMATTED: &str = "fn f() -> Html {\n    html! { <a   /> }\n}\n";
+const FORMATTED: &str = "fn f() -> Html {\n    html! { <a /> }\n}\n";
+
+#[allow(clippy::expect_used)]
+fn run_in(dir: &Path, program: &str, args: &[&str]) -> Output {
+    Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap_or_else(|e| panic!("{program} should be invoked: {e}"))
+}
+
+#[allow(clippy::unwrap_used)]
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = run_in(dir, "git", args);
+    assert!(output.status.success(), "`git {}` failed", args.join(" "));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn index_formatted() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-staged-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "-q"]);
+    write(dir.join("partly_staged.rs"), UNFORMATTED).unwrap();
+    write(dir.join("staged.rs"), UNFORMATTED).unwrap();
+    write(dir.join("unstaged.rs"), UNFORMATTED).unwrap();
+    git(&dir, &["add", "partly_staged.rs", "staged.rs"]);
+    let unstaged_edit = format!("{UNFORMATTED}\nfn g( ) {{}}\n");
+    write(dir.join("partly_staged.rs"), &unstaged_edit).unwrap();
+
+    let bin = env!("CARGO_BIN_EXE_yew-fmt");
+    let checked = run_in(&dir, bin, &["--staged", "--check", "-l"]);
+    let output = run_in(&dir, bin, &["--staged"]);
+    let index = [git(&dir, &["show", ":partly_staged.rs"]), git(&dir, &["show", ":staged.rs"])];
+    let working_tree = ["partly_staged.rs", "staged.rs", "unstaged.rs"]
+        .map(|file| read_to_string(dir.join(file)).unwrap());
+    remove_dir_all(&dir).unwrap();
+
+    assert_eq!(checked.status.code(), Some(1), "expected a difference to be found");
+    assert_eq!(String::from_utf8(checked.stdout).unwrap(), "partly_staged.rs\nstaged.rs\n");
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(index, [FORMATTED, FORMATTED]);
+    assert_eq!(working_tree, [&*unstaged_edit, FORMATTED, UNFORMATTED]);
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn index_formatted_from_subdirectory() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-staged-subdir-{}", std::process::id()));
+    create_dir_all(dir.join("src/views")).unwrap();
+    git(&dir, &["init", "-q"]);
+    write(dir.join("src/lib.rs"), UNFORMATTED).unwrap();
+    write(dir.join("src/views/list.rs"), UNFORMATTED).unwrap();
+    git(&dir, &["add", "."]);
+
+    let output = run_in(&dir.join("src"), env!("CARGO_BIN_EXE_yew-fmt"), &["--staged"]);
+    let index = [git(&dir, &["show", ":src/lib.rs"]), git(&dir, &["show", ":src/views/list.rs"])];
+    let working_tree = read_to_string(dir.join("src/views/list.rs")).unwrap();
+    remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(index, [FORMATTED, FORMATTED]);
+    assert_eq!(working_tree, FORMATTED);
+}
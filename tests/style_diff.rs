@@ -0,0 +1,97 @@
+//! The formatting changes since an older version & the files they affect, listed by
+//! `yew-fmt style-diff`
+
+use serde_json::{json, Value};
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process::{Command, Output};
+
+/// Unformatted by any version
+const UNFORMATTED: &str = "fn f() -> Html {\n    html! { <div   /> }\n}\n";
+/// Formatted alike by all the versions
+const FORMATTED: &str = "fn f() -> Html {\n    html! { <div /> }\n}\n";
+/// Broken up by 0.5.1, which measured the width of the text in bytes rather than in columns
+const BYTE_WIDTH: &str = "fn f() -> Html {\n    html! {\n        <p class=\"note\">\n            \
+                          { \"漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢漢\" }\n        </p>\n    \
+                          }\n}\n";
+
+/// Runs `yew-fmt style-diff` with `args` in a temporary directory containing `a.rs`, `b.rs` &
+/// `c.rs`, respectively unformatted, formatted & formatted by 0.5.1 only
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn style_diff(name: &str, args: &[&str]) -> Output {
+    let dir =
+        std::env::temp_dir().join(format!("yew-fmt-style-diff-{name}-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    for (file, src) in [("a.rs", UNFORMATTED), ("b.rs", FORMATTED), ("c.rs", BYTE_WIDTH)] {
+        write(dir.join(file), src).unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .arg("style-diff")
+        .args(args)
+        .current_dir(&dir)
+        .output()
+        .expect("yew-fmt should be invoked");
+    remove_dir_all(&dir).unwrap();
+    output
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn changes_listed() {
+    let output = style_diff("text", &["0.5.1"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Changes to the formatting since 0.5.1:\n"), "{stdout}");
+    assert!(
+        stdout.contains(
+            "\n- unreleased: the width of the HTML is measured in the columns it's displayed in, \
+             rather than in bytes (`yew.width_heuristic` used to default to `\"bytes\"`)\n"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.ends_with(
+            "Files formatted differently by 0.5.1:\n- ./a.rs\n- ./c.rs (reformatted), depends on \
+             the defaults of `yew.width_heuristic`\n"
+        ),
+        "{stdout}"
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn changes_as_json() {
+    let output = style_diff("json", &["0.5.1", "--json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let res: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(res["since"], "0.5.1");
+    let changes = res["changes"].as_array().unwrap();
+    assert!(changes.contains(&json!({
+        "description": "the width of the HTML is measured in the columns it's displayed in, \
+                        rather than in bytes",
+        "kind": "default",
+        "oldDefault": "\"bytes\"",
+        "option": "yew.width_heuristic",
+        "release": "unreleased",
+    })));
+    assert_eq!(
+        res["files"],
+        json!([
+            { "defaults": [], "file": "./a.rs", "reformatted": true },
+            { "defaults": ["yew.width_heuristic"], "file": "./c.rs", "reformatted": true },
+        ])
+    );
+}
+
+#[test]
+#[allow(clippy::unwrap_used)]
+fn unknown_versions_rejected() {
+    for (version, error) in [
+        ("0.4.0", "the changes made before 0.5.1 aren't recorded"),
+        ("99.0.0", "99.0.0 is newer than the current version"),
+    ] {
+        let output = style_diff(version, &[version]);
+        assert_eq!(output.status.code(), Some(3));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(error), "{stderr}");
+    }
+}
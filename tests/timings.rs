@@ -0,0 +1,23 @@
+//! The times reported with `--timings`
+
+mod common;
+use common::run_with_stdin;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn phases_reported() {
+    let output =
+        run_with_stdin(&["--check", "--timings"], "fn f() -> Html {\n    html! { <a /> }\n}\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let [rustfmt, file] = stderr.lines().collect::<Vec<_>>()[..] else {
+        panic!("expected 2 lines in {stderr:?}")
+    };
+    assert!(rustfmt.starts_with("rustfmt: ") && rustfmt.ends_with(" for 1 file"), "{rustfmt:?}");
+    let phases: Vec<&str> = file
+        .strip_prefix("<stdin>: ")
+        .unwrap()
+        .split(", ")
+        .map(|p| p.split_once(' ').unwrap().1)
+        .collect();
+    assert_eq!(phases, ["parsing Rust", "parsing HTML", "laying out", "printing"]);
+}
@@ -0,0 +1,22 @@
+//! The summary of a run printed with `--verbose`
+
+mod common;
+use common::run_with_stdin;
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn summary_printed() {
+    let src = "fn f() -> Html {\n    html! { <a   /> }\n}\n\n\
+               // yew-fmt: off\n\
+               fn g() -> Html {\n    html! { <a   /> }\n}\n\
+               // yew-fmt: on\n\n\
+               fn h() -> Html {\n    html! { <a> }\n}\n";
+    let output = run_with_stdin(&["--check", "--verbose", "--color", "never"], src);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let summary: Vec<&str> =
+        stderr.lines().skip_while(|line| !line.starts_with("Processed")).take(2).collect();
+    let [files, macros] = summary[..] else { panic!("no summary in {stderr:?}") };
+    assert!(files.starts_with("Processed 1 file in "), "{files:?}");
+    assert!(files.ends_with("s, 1 of them changed"), "{files:?}");
+    assert_eq!(macros, "View macros: 1 formatted, 1 skipped, 1 left as is due to syntax errors");
+}
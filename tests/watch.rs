@@ -0,0 +1,40 @@
+//! The reformatting of the files as they change with `--watch`
+
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+#[test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+fn changed_file_reformatted() {
+    let dir = std::env::temp_dir().join(format!("yew-fmt-watch-{}", std::process::id()));
+    create_dir_all(dir.join("src")).unwrap();
+    let file = dir.join("src/lib.rs");
+    write(&file, "fn f() -> Html {\n    html! { <a /> }\n}\n").unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_yew-fmt"))
+        .args(["--watch", "src"])
+        .current_dir(&dir)
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("yew-fmt should be invoked");
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    stderr.read_line(&mut line).unwrap();
+    assert_eq!(line, "Watching 1 file for changes\n");
+
+    // the modification time may have a coarse resolution, so the change is made distinguishable
+    sleep(Duration::from_millis(1100));
+    write(&file, "fn f() -> Html {\n    html! { <a   /> }\n}\n").unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut formatted = false;
+    while !formatted && Instant::now() < deadline {
+        sleep(Duration::from_millis(100));
+        formatted = read_to_string(&file).unwrap() == "fn f() -> Html {\n    html! { <a /> }\n}\n";
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    remove_dir_all(&dir).unwrap();
+    assert!(formatted, "the file wasn't reformatted");
+}